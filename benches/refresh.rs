@@ -0,0 +1,89 @@
+// SPDX-License-Identifier: MIT
+
+//! Per-refresh cost of the process backends.
+//!
+//! Run with `cargo bench --bench refresh`. Besides the live /proc, the procfs
+//! backend is measured against a generated tree of `PROCESSES` processes so
+//! the numbers are comparable between machines.
+
+use cosmic_applet_process_killer::backend::{ProcessBackend, SysinfoBackend};
+use cosmic_applet_process_killer::procfs::ProcfsBackend;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const PROCESSES: u32 = 2500;
+const ROUNDS: u32 = 20;
+
+fn main() {
+    let fake_root = fake_proc(PROCESSES);
+
+    bench(
+        &format!("procfs, {PROCESSES} generated processes"),
+        ProcfsBackend::with_root(&fake_root),
+    );
+    bench("procfs, live /proc", ProcfsBackend::new());
+    bench("sysinfo, live /proc", SysinfoBackend::new());
+
+    fs::remove_dir_all(&fake_root).unwrap();
+}
+
+fn bench(label: &str, mut backend: impl ProcessBackend) {
+    let start = Instant::now();
+    let count = backend.sample().len();
+    let cold = start.elapsed();
+
+    let mut total = Duration::ZERO;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        std::hint::black_box(backend.sample());
+        total += start.elapsed();
+    }
+
+    println!(
+        "{label}: {count} processes, first refresh {cold:.2?}, then {:.2?} per refresh",
+        total / ROUNDS
+    );
+}
+
+/// Write a procfs-like tree with `count` processes to a temporary directory.
+fn fake_proc(count: u32) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("process-killer-bench-{}", std::process::id()));
+    fs::create_dir_all(&root).unwrap();
+    fs::write(root.join("stat"), "cpu  1 2 3 4\nbtime 1700000000\n").unwrap();
+
+    for pid in 1..=count {
+        write_process(&root, pid);
+    }
+
+    root
+}
+
+fn write_process(root: &Path, pid: u32) {
+    let dir = root.join(pid.to_string());
+    let ppid = pid / 2;
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("stat"),
+        format!(
+            "{pid} (worker {pid}) S {ppid} {pid} {pid} 0 -1 4194560 1200 0 0 0 \
+             {utime} {stime} 0 0 20 0 4 0 {start} 123456789 {rss} 18446744073709551615 \
+             1 1 0 0 0 0 0 4096 0 0 0 0 17 3 0 0 0 0 0\n",
+            utime = pid * 7,
+            stime = pid * 3,
+            start = 1000 + pid,
+            rss = 256 + pid,
+        ),
+    )
+    .unwrap();
+    fs::write(
+        dir.join("status"),
+        format!("Name:\tworker {pid}\nState:\tS (sleeping)\nUid:\t1000\t1000\t1000\t1000\n"),
+    )
+    .unwrap();
+    fs::write(
+        dir.join("cmdline"),
+        format!("/usr/bin/worker\0--id\0{pid}\0"),
+    )
+    .unwrap();
+}
@@ -25,3 +25,163 @@ error-sigkill-failed = Failed to send SIGKILL: {$error}
 error-sigterm-failed = Failed to send SIGTERM: {$error}
 notification-kill-failed = Failed to kill process: {$error}
 cpu-threshold = CPU Limit
+export-json = Export JSON
+export-csv = Export CSV
+notification-export-success = Snapshot saved to {$path}
+error-export-failed = Failed to export snapshot: {$error}
+tree-view = Tree
+details-tooltip = Details
+details-parent = Parent PID
+details-status = Status
+details-command = Command line
+details-executable = Executable
+details-nice = Nice
+details-io-priority = I/O priority
+priority-apply = Apply
+priority-tooltip = Priority
+priority-lower = Lower priority (nice 10)
+priority-lowest = Lowest priority (nice 19)
+priority-normal = Normal priority (nice 0)
+priority-io-idle = Disk only when idle
+io-class-realtime = Real-time
+io-class-best-effort = Best effort
+io-class-idle = Idle
+notification-priority-changed = Changed the priority of {$name}
+error-priority-permission = Not allowed to change the priority of {$name}. Raising priority needs administrator rights
+error-priority-failed = Failed to change priority: {$error}
+details-affinity = CPU cores
+affinity-all-threads = All threads
+affinity-all-cores = All cores
+affinity-error = Cannot read the CPU affinity: {$error}
+notification-affinity-changed = Changed the CPU cores of {$name}
+error-affinity-permission = Not allowed to change the CPU cores of {$name}
+details-threads = Threads
+header-tid = TID
+header-state = State
+thread-signal-tooltip = Send a signal to this thread
+thread-signal-usr1 = SIGUSR1 to this thread
+thread-signal-usr2 = SIGUSR2 to this thread
+notification-thread-signal-sent = Sent {$signal} to thread {$tid} of {$name}
+error-thread-not-found = Thread not found
+header-mem-metric = Mem ({$metric})
+memory-metric = Memory
+memory-metric-rss = Resident (RSS)
+memory-metric-pss = Proportional (PSS)
+memory-metric-uss = Private (USS)
+details-memory = Memory
+details-memory-unavailable = Breakdown not readable
+details-memory-rss = Resident
+details-memory-pss = Proportional
+details-memory-private = Private
+details-memory-shared = Shared
+details-memory-swap = Swapped
+disk-io = Disk I/O
+header-disk-read = Read
+header-disk-write = Write
+details-disk-io = Disk
+details-disk-rates = {$read} read · {$write} written
+details-sockets = Sockets
+sockets-none = No open sockets, or not allowed to see them
+socket-unnamed = (unnamed)
+notification-target-exited = {$name} exited before it was killed
+query-error-unknown-field = Unknown filter "{$field}". Try cpu, mem, pid, user, name, cmd, state, port or file
+query-error-missing-value = "{$field}" needs a value, e.g. {$field}:value
+query-error-not-comparable = "{$field}" cannot be compared with < or >
+query-error-invalid-number = "{$value}" is not a number
+query-error-invalid-size = "{$value}" is not a size, e.g. 500M or 1G
+query-error-invalid-regex = Invalid pattern /{$pattern}/: {$error}
+query-error-unknown-user = Unknown user "{$user}"
+query-error-unknown-state = Unknown state "{$state}". Try running, sleeping, disk, zombie, stopped or idle
+query-error-unclosed-quote = Missing closing quote
+save-search-tooltip = Save search
+remove-search-tooltip = Remove saved search
+details-open-files = Open files
+open-files-none = No open files, or not allowed to see them
+open-file-fd = fd {$fd}
+open-file-mapped = mapped
+open-file-cwd = working dir
+open-file-root = root dir
+open-file-exe = executable
+holders-open = Who holds a file…
+holders-title = Who holds this file
+holders-placeholder = File, directory or mount point, e.g. /run/media/me/usb
+holders-find = Find
+holders-searching = Searching…
+holders-none = Nothing holds {$path}, or not allowed to see it
+holders-found = Processes holding {$path}: {$count}
+holders-kill = Kill holders
+holders-force-kill = Force kill holders
+holders-filter = Show in list
+confirm-holders-message = Send {$signal} to {$count} processes holding {$path}?
+notification-holders-killed = Sent {$signal} to {$count} processes holding {$path}
+error-group-kill-none = None of these processes can be killed
+error-holders-kill-failed = Could not kill {$failed} of {$count} processes holding {$path}
+query-error-invalid-path = "{$path}" is not an absolute path, e.g. file:/mnt/usb
+details-unit = systemd unit
+unit-none = None
+unit-scope-system = {$unit} (system)
+unit-scope-user = {$unit} (user)
+unit-stop = Stop unit
+unit-restart = Restart unit
+unit-kill = Kill unit
+confirm-unit-stop = Stop {$unit} and every process in it?
+confirm-unit-restart = Restart {$unit}, stopping every process in it first?
+confirm-unit-kill = Kill every process of {$unit}?
+error-unit-self = {$unit} runs this process killer and cannot be stopped from it
+notification-unit-stopped = Stopping {$unit}
+notification-unit-restarted = Restarting {$unit}
+notification-unit-killed = Killed every process of {$unit}
+error-unit-permission = Not allowed to manage {$unit}; system services need administrator rights
+error-unit-not-found = Unit {$unit} not found
+error-unit-failed = Could not manage {$unit}: {$error}
+error-unit-restart-no-unit = {$name} does not run in a systemd unit and cannot be restarted
+details-app = App
+app-none = Not started as an app
+app-frozen = {$app} (frozen)
+app-freeze = Freeze app
+app-thaw = Thaw app
+frozen-tooltip = {$app} is frozen; click to thaw
+notification-app-frozen = Froze every process of {$app}
+notification-app-thawed = Thawed {$app}
+error-freeze-no-app = {$name} does not run in an app cgroup and cannot be frozen
+error-freeze-self = {$app} runs this process killer and cannot be frozen
+error-freeze-permission = Not allowed to freeze {$app}
+error-freeze-not-found = The cgroup of {$app} is gone or cgroup v2 is not available
+error-freeze-failed = Could not freeze or thaw {$app}: {$error}
+details-limits = Limits
+limits-unknown = Unknown
+limits-current = Memory: {$memory}, CPU: {$cpu}
+limits-memory = Memory
+limits-cpu = CPU
+limits-apply = Apply limits
+limit-none = No limit
+notification-limits-set = Limits of {$target} updated
+notification-limits-below-usage = {$target} already uses {$usage}, more than its new memory cap, and may be killed
+error-limits-permission = Not allowed to limit {$target}; system services need administrator rights
+error-limits-not-found = {$target} is gone, or its memory and CPU controllers are not enabled
+error-limits-failed = Could not limit {$target}: {$error}
+details-package = Package
+package-none = Not a Flatpak, Snap or AppImage
+package-flatpak = {$app} (Flatpak)
+package-snap = {$app} (Snap)
+package-appimage = {$app} (AppImage)
+package-kill = Kill {$app}
+confirm-package-message = Kill all {$count} processes of {$app}?
+notification-package-killed = Killed {$count} processes of {$app}
+error-package-none = {$name} is not part of a Flatpak, Snap or AppImage
+error-package-kill-failed = Could not kill {$failed} of {$count} processes of {$app}
+group-by-container = By container
+details-container = Container
+container-none = Not in a container
+container-docker = {$container} (Docker)
+container-podman = {$container} (Podman)
+container-stop = Stop container
+details-pid-namespace = PID namespace
+pid-namespace-host = Host
+confirm-container-stop = Stop container {$container} and every process in it?
+error-container-self = Container {$container} runs this process killer and cannot be stopped from it
+notification-container-stopped = Stopped container {$container}
+error-container-none = {$name} does not run in a container
+error-container-permission = Not allowed to stop {$container}; the runtime's socket needs to be accessible to you
+error-container-not-found = Container {$container} not found, or its runtime is not running
+error-container-failed = Could not stop {$container}: {$error}
@@ -24,4 +24,164 @@ notification-kill-success = Processo {$name} encerrado
 error-sigkill-failed = Falha ao enviar SIGKILL: {$error}
 error-sigterm-failed = Falha ao enviar SIGTERM: {$error}
 notification-kill-failed = Falha ao matar processo: {$error}
+notification-target-exited = {$name} terminou antes de ser encerrado
+query-error-unknown-field = Filtro desconhecido "{$field}". Use cpu, mem, pid, user, name, cmd, state, port ou file
+query-error-missing-value = "{$field}" precisa de um valor, ex.: {$field}:valor
+query-error-not-comparable = "{$field}" não pode ser comparado com < ou >
+query-error-invalid-number = "{$value}" não é um número
+query-error-invalid-size = "{$value}" não é um tamanho, ex.: 500M ou 1G
+query-error-invalid-regex = Padrão inválido /{$pattern}/: {$error}
+query-error-unknown-user = Usuário desconhecido "{$user}"
+query-error-unknown-state = Estado desconhecido "{$state}". Use running, sleeping, disk, zombie, stopped ou idle
+query-error-unclosed-quote = Falta fechar as aspas
+save-search-tooltip = Salvar pesquisa
+remove-search-tooltip = Remover pesquisa salva
 cpu-threshold = Limite CPU
+export-json = Exportar JSON
+export-csv = Exportar CSV
+notification-export-success = Instantâneo salvo em {$path}
+error-export-failed = Falha ao exportar instantâneo: {$error}
+tree-view = Árvore
+details-tooltip = Detalhes
+details-parent = PID do pai
+details-status = Estado
+details-command = Linha de comando
+details-executable = Executável
+details-nice = Nice
+details-io-priority = Prioridade de E/S
+priority-apply = Aplicar
+priority-tooltip = Prioridade
+priority-lower = Prioridade menor (nice 10)
+priority-lowest = Prioridade mínima (nice 19)
+priority-normal = Prioridade normal (nice 0)
+priority-io-idle = Disco só quando ocioso
+io-class-realtime = Tempo real
+io-class-best-effort = Melhor esforço
+io-class-idle = Ocioso
+notification-priority-changed = Prioridade de {$name} alterada
+error-priority-permission = Sem permissão para alterar a prioridade de {$name}. Aumentar a prioridade exige privilégios de administrador
+error-priority-failed = Falha ao alterar a prioridade: {$error}
+details-affinity = Núcleos de CPU
+affinity-all-threads = Todas as threads
+affinity-all-cores = Todos os núcleos
+affinity-error = Não foi possível ler a afinidade de CPU: {$error}
+notification-affinity-changed = Núcleos de CPU de {$name} alterados
+error-affinity-permission = Sem permissão para alterar os núcleos de CPU de {$name}
+details-threads = Threads
+header-tid = TID
+header-state = Estado
+thread-signal-tooltip = Enviar um sinal para esta thread
+thread-signal-usr1 = SIGUSR1 para esta thread
+thread-signal-usr2 = SIGUSR2 para esta thread
+notification-thread-signal-sent = {$signal} enviado para a thread {$tid} de {$name}
+error-thread-not-found = Thread não encontrada
+header-mem-metric = Mem ({$metric})
+memory-metric = Memória
+memory-metric-rss = Residente (RSS)
+memory-metric-pss = Proporcional (PSS)
+memory-metric-uss = Privada (USS)
+details-memory = Memória
+details-memory-unavailable = Detalhamento não disponível
+details-memory-rss = Residente
+details-memory-pss = Proporcional
+details-memory-private = Privada
+details-memory-shared = Compartilhada
+details-memory-swap = Em swap
+disk-io = E/S de disco
+header-disk-read = Leitura
+header-disk-write = Escrita
+details-disk-io = Disco
+details-disk-rates = {$read} lidos · {$write} escritos
+details-sockets = Sockets
+sockets-none = Nenhum socket aberto, ou sem permissão para vê-los
+socket-unnamed = (sem nome)
+details-open-files = Arquivos abertos
+open-files-none = Nenhum arquivo aberto, ou sem permissão para vê-los
+open-file-fd = fd {$fd}
+open-file-mapped = mapeado
+open-file-cwd = diretório atual
+open-file-root = diretório raiz
+open-file-exe = executável
+holders-open = Quem usa um arquivo…
+holders-title = Quem usa este arquivo
+holders-placeholder = Arquivo, diretório ou ponto de montagem, ex.: /run/media/eu/usb
+holders-find = Procurar
+holders-searching = Procurando…
+holders-none = Nada usa {$path}, ou sem permissão para ver
+holders-found = Processos que usam {$path}: {$count}
+holders-kill = Encerrar processos
+holders-force-kill = Forçar encerramento
+holders-filter = Mostrar na lista
+confirm-holders-message = Enviar {$signal} para {$count} processos que usam {$path}?
+notification-holders-killed = {$signal} enviado para {$count} processos que usam {$path}
+error-group-kill-none = Nenhum destes processos pode ser encerrado
+error-holders-kill-failed = Não foi possível encerrar {$failed} de {$count} processos que usam {$path}
+query-error-invalid-path = "{$path}" não é um caminho absoluto, ex.: file:/mnt/usb
+details-unit = Unidade systemd
+unit-none = Nenhuma
+unit-scope-system = {$unit} (sistema)
+unit-scope-user = {$unit} (usuário)
+unit-stop = Parar unidade
+unit-restart = Reiniciar unidade
+unit-kill = Matar unidade
+confirm-unit-stop = Parar {$unit} e todos os processos nele?
+confirm-unit-restart = Reiniciar {$unit}, parando antes todos os processos nele?
+confirm-unit-kill = Finalizar todos os processos de {$unit}?
+error-unit-self = {$unit} executa este finalizador de processos e não pode ser parado por ele
+notification-unit-stopped = Parando {$unit}
+notification-unit-restarted = Reiniciando {$unit}
+notification-unit-killed = Todos os processos de {$unit} foram encerrados
+error-unit-permission = Sem permissão para gerenciar {$unit}; serviços do sistema exigem privilégios de administrador
+error-unit-not-found = Unidade {$unit} não encontrada
+error-unit-failed = Não foi possível gerenciar {$unit}: {$error}
+error-unit-restart-no-unit = {$name} não roda em uma unidade systemd e não pode ser reiniciado
+details-app = Aplicativo
+app-none = Não iniciado como aplicativo
+app-frozen = {$app} (congelado)
+app-freeze = Congelar aplicativo
+app-thaw = Descongelar aplicativo
+frozen-tooltip = {$app} está congelado; clique para descongelar
+notification-app-frozen = Todos os processos de {$app} foram congelados
+notification-app-thawed = {$app} foi descongelado
+error-freeze-no-app = {$name} não roda em um cgroup de aplicativo e não pode ser congelado
+error-freeze-self = {$app} executa este finalizador de processos e não pode ser congelado
+error-freeze-permission = Sem permissão para congelar {$app}
+error-freeze-not-found = O cgroup de {$app} não existe mais ou o cgroup v2 não está disponível
+error-freeze-failed = Não foi possível congelar ou descongelar {$app}: {$error}
+details-limits = Limites
+limits-unknown = Desconhecido
+limits-current = Memória: {$memory}, CPU: {$cpu}
+limits-memory = Memória
+limits-cpu = CPU
+limits-apply = Aplicar limites
+limit-none = Sem limite
+notification-limits-set = Limites de {$target} atualizados
+notification-limits-below-usage = {$target} já usa {$usage}, mais do que seu novo limite de memória, e pode ser finalizado
+error-limits-permission = Sem permissão para limitar {$target}; serviços do sistema exigem direitos de administrador
+error-limits-not-found = {$target} não existe mais, ou seus controladores de memória e CPU não estão ativados
+error-limits-failed = Não foi possível limitar {$target}: {$error}
+details-package = Pacote
+package-none = Não é um Flatpak, Snap ou AppImage
+package-flatpak = {$app} (Flatpak)
+package-snap = {$app} (Snap)
+package-appimage = {$app} (AppImage)
+package-kill = Encerrar {$app}
+confirm-package-message = Finalizar todos os {$count} processos de {$app}?
+notification-package-killed = {$count} processos de {$app} foram encerrados
+error-package-none = {$name} não faz parte de um Flatpak, Snap ou AppImage
+error-package-kill-failed = Não foi possível encerrar {$failed} de {$count} processos de {$app}
+group-by-container = Por contêiner
+details-container = Contêiner
+container-none = Não está em um contêiner
+container-docker = {$container} (Docker)
+container-podman = {$container} (Podman)
+container-stop = Parar contêiner
+details-pid-namespace = Namespace de PID
+pid-namespace-host = Host
+confirm-container-stop = Parar o contêiner {$container} e todos os processos nele?
+error-container-self = O contêiner {$container} executa este finalizador de processos e não pode ser parado por ele
+notification-container-stopped = Contêiner {$container} parado
+error-container-none = {$name} não roda em um contêiner
+error-container-permission = Sem permissão para parar {$container}; o socket do runtime precisa estar acessível para você
+error-container-not-found = Contêiner {$container} não encontrado, ou seu runtime não está em execução
+error-container-failed = Não foi possível parar {$container}: {$error}
@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: MIT
+
+//! CPU affinity: the cores a process may run on.
+//!
+//! Read and set with sched_getaffinity(2)/sched_setaffinity(2). Like the
+//! priorities, an affinity belongs to a single thread; a new mask can be given
+//! to the main thread only or to every thread of the process.
+
+use crate::priority::for_each_thread;
+use crate::process::{self, ProcessError, ProcessResult};
+use nix::errno::Errno;
+use nix::sched::{sched_getaffinity, sched_setaffinity, CpuSet};
+use nix::unistd::{sysconf, Pid, SysconfVar};
+
+/// Which cores a process may run on, one entry per core of the system
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CpuMask(Vec<bool>);
+
+impl CpuMask {
+    /// Every core of the system
+    pub fn all() -> Self {
+        Self(vec![true; cpu_count()])
+    }
+
+    /// Only the listed cores, out of the system's
+    pub fn from_cpus(cpus: impl IntoIterator<Item = usize>) -> Self {
+        let mut mask = Self(vec![false; cpu_count()]);
+        for cpu in cpus {
+            mask.set(cpu, true);
+        }
+        mask
+    }
+
+    /// Number of cores of the system
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether no core is allowed, which the kernel refuses
+    pub fn is_empty(&self) -> bool {
+        !self.0.contains(&true)
+    }
+
+    pub fn contains(&self, cpu: usize) -> bool {
+        self.0.get(cpu).copied().unwrap_or(false)
+    }
+
+    /// Allow or forbid a core; cores the system does not have are ignored.
+    pub fn set(&mut self, cpu: usize, allowed: bool) {
+        if let Some(entry) = self.0.get_mut(cpu) {
+            *entry = allowed;
+        }
+    }
+
+    /// The allowed cores, in order
+    pub fn cpus(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0
+            .iter()
+            .enumerate()
+            .filter_map(|(cpu, allowed)| allowed.then_some(cpu))
+    }
+
+    fn to_cpu_set(&self) -> ProcessResult<CpuSet> {
+        let mut set = CpuSet::new();
+        for cpu in self.cpus() {
+            set.set(cpu).map_err(ProcessError::from)?;
+        }
+        Ok(set)
+    }
+}
+
+/// Cores configured on this system, including offline ones
+pub fn cpu_count() -> usize {
+    sysconf(SysconfVar::_NPROCESSORS_CONF)
+        .ok()
+        .flatten()
+        .and_then(|count| usize::try_from(count).ok())
+        .filter(|count| *count > 0)
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()))
+        .min(CpuSet::count())
+}
+
+/// Cores the process (its main thread) may run on
+pub fn affinity(pid: u32) -> ProcessResult<CpuMask> {
+    let set = sched_getaffinity(process::nix_pid(pid)?)?;
+    Ok(CpuMask(
+        (0..cpu_count())
+            .map(|cpu| set.is_set(cpu).unwrap_or(false))
+            .collect(),
+    ))
+}
+
+/// Restrict a process to the cores in `mask`, either its main thread or all
+/// of its threads.
+pub fn set_affinity(pid: u32, mask: &CpuMask, all_threads: bool) -> ProcessResult<()> {
+    if mask.is_empty() {
+        return Err(ProcessError::Unknown(Errno::EINVAL.desc().to_string()));
+    }
+    let set = mask.to_cpu_set()?;
+    let apply = |tid: u32| sched_setaffinity(Pid::from_raw(tid as i32), &set);
+
+    if all_threads {
+        for_each_thread(pid, apply)
+    } else {
+        Ok(sched_setaffinity(process::nix_pid(pid)?, &set)?)
+    }
+}
@@ -1,15 +1,35 @@
 // SPDX-License-Identifier: MIT
 
-use crate::config::Config;
-use crate::fl;
-use crate::process::{ProcessError, ProcessInfo, ProcessManager, SortBy};
 use cosmic::cosmic_config::{self, CosmicConfigEntry};
+use cosmic::iced::widget::scrollable::{self, AbsoluteOffset};
+use cosmic::iced::widget::rich_text;
 use cosmic::iced::{window::Id, Alignment, Length, Limits, Subscription};
 use cosmic::iced_winit::commands::popup::{destroy_popup, get_popup};
 use cosmic::prelude::*;
 use cosmic::widget;
-use futures_util::SinkExt;
-use std::time::Duration;
+use cosmic_applet_process_killer::cgroup;
+use cosmic_applet_process_killer::config::Config;
+use cosmic_applet_process_killer::controller::{
+    truncate_name, ConfirmationMode, Message as ProcessMessage, ProcessController,
+};
+use cosmic_applet_process_killer::dbus;
+use cosmic_applet_process_killer::fl;
+use cosmic_applet_process_killer::process::{ProcessInfo, SortBy};
+use cosmic_applet_process_killer::sampler::{self, LatestSample};
+use cosmic_applet_process_killer::view;
+use cosmic_applet_process_killer::virtual_list::VirtualList;
+use futures_util::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock};
+
+static LIST_ID: LazyLock<widget::Id> = LazyLock::new(|| widget::Id::new("process-list"));
+
+/// Height of a process row, which the virtualized list relies on
+const ROW_HEIGHT: f32 = 34.0;
+const LIST_HEIGHT: f32 = 300.0;
+/// Width of the name column and the characters of a label that fit in it
+const NAME_WIDTH: f32 = 130.0;
+const NAME_CHARS: usize = 22;
 
 /// The application model stores app-specific state used to describe its interface and
 /// drive its logic.
@@ -20,34 +40,12 @@ pub struct AppModel {
     popup: Option<Id>,
     /// Configuration data that persists between application runs.
     config: Config,
-    /// Process manager
-    process_manager: ProcessManager,
-    /// List of processes
-    processes: Vec<ProcessInfo>,
-    /// Show all processes or only high CPU
-    show_all: bool,
-    /// Sort order
-    sort_by: SortBy,
-    /// Search filter
-    search_query: String,
-    /// Selected process for confirmation
-    selected_process: Option<ProcessInfo>,
-    /// Confirmation dialog state
-    confirmation_mode: Option<ConfirmationMode>,
-    /// Toast notification state
-    toast: Option<Toast>,
-}
-
-#[derive(Debug, Clone, PartialEq)]
-pub enum ConfirmationMode {
-    Kill,
-    ForceKill,
-}
-
-#[derive(Debug, Clone, PartialEq)]
-pub struct Toast {
-    message: String,
-    is_error: bool,
+    /// Process list, search, selection and kill confirmation
+    controller: ProcessController,
+    /// Rows in view and their order
+    list: VirtualList,
+    /// Latest sample, which the D-Bus service answers from
+    latest: LatestSample,
 }
 
 impl Default for AppModel {
@@ -56,14 +54,9 @@ impl Default for AppModel {
             core: cosmic::Core::default(),
             popup: None,
             config: Config::default(),
-            process_manager: ProcessManager::new(),
-            processes: Vec::new(),
-            show_all: false,
-            sort_by: SortBy::Cpu,
-            search_query: String::new(),
-            selected_process: None,
-            confirmation_mode: None,
-            toast: None,
+            controller: ProcessController::new(),
+            list: VirtualList::new(ROW_HEIGHT, LIST_HEIGHT),
+            latest: LatestSample::new(),
         }
     }
 }
@@ -76,18 +69,14 @@ pub enum Message {
     PopupClosed(Id),
     SubscriptionChannel,
     UpdateConfig(Config),
-    RefreshProcesses,
-    KillProcess(u32),
-    ForceKillProcess(u32),
-    ToggleShowAll(bool),
-    SortBy(SortBy),
-    UpdateSearch(String),
-    SelectProcess(Option<u32>),
-    ConfirmKill,
-    ConfirmForceKill,
-    CancelConfirmation,
-    ShowToast(String, bool),
-    ClearToast,
+    Process(ProcessMessage),
+    DbusRequest(dbus::Request),
+    ListScrolled {
+        offset: f32,
+        height: f32,
+    },
+    /// The pointer entered or left the process list
+    ListHovered(bool),
 }
 
 /// Create a COSMIC application from the app model
@@ -118,20 +107,27 @@ impl cosmic::Application for AppModel {
         _flags: Self::Flags,
     ) -> (Self, Task<cosmic::Action<Self::Message>>) {
         // Construct the app model with the runtime's core.
-        let mut app = AppModel {
+        let config_handler = cosmic_config::Config::new(Self::APP_ID, Config::VERSION).ok();
+        let config = config_handler
+            .as_ref()
+            .map(|context| match Config::get_entry(context) {
+                Ok(config) => config,
+                Err((_errors, config)) => config,
+            })
+            .unwrap_or_default();
+        let mut controller = ProcessController::new();
+        if let Some(handler) = config_handler {
+            controller = controller.with_search_store(Arc::new(handler));
+        }
+        controller.set_saved_searches(config.saved_searches.clone());
+        controller.set_show_disk_io(config.show_disk_io);
+        let app = AppModel {
             core,
-            config: cosmic_config::Config::new(Self::APP_ID, Config::VERSION)
-                .map(|context| match Config::get_entry(&context) {
-                    Ok(config) => config,
-                    Err((_errors, config)) => config,
-                })
-                .unwrap_or_default(),
+            config,
+            controller,
             ..Default::default()
         };
 
-        // Load initial processes
-        app.refresh_processes();
-
         (app, Task::none())
     }
 
@@ -160,7 +156,7 @@ impl cosmic::Application for AppModel {
             .push(widget::horizontal_space())
             .push(widget::tooltip(
                     widget::button::icon(widget::icon::from_name("view-refresh-symbolic"))
-                        .on_press(Message::RefreshProcesses)
+                        .on_press(Message::Process(ProcessMessage::RefreshProcesses))
                         .padding(4),
                     widget::text(fl!("refresh-tooltip")),
                     widget::tooltip::Position::Bottom,
@@ -170,23 +166,57 @@ impl cosmic::Application for AppModel {
         content = content.push(header);
 
         // Search bar
-        let search = widget::text_input(fl!("search-placeholder"), &self.search_query)
-            .on_input(Message::UpdateSearch)
+        let search = widget::text_input(fl!("search-placeholder"), self.controller.search_query())
+            .on_input(|query| Message::Process(ProcessMessage::UpdateSearch(query)))
             .width(Length::Fill);
+        let save_search = widget::tooltip(
+            widget::button::icon(widget::icon::from_name("starred-symbolic"))
+                .on_press_maybe(
+                    self.controller
+                        .can_save_search()
+                        .then_some(Message::Process(ProcessMessage::SaveSearch)),
+                )
+                .padding(4),
+            widget::text(fl!("save-search-tooltip")),
+            widget::tooltip::Position::Bottom,
+        );
+
+        content = content.push(
+            widget::row()
+                .spacing(4)
+                .align_y(Alignment::Center)
+                .push(search)
+                .push(save_search),
+        );
 
-        content = content.push(search);
+        if let Some(error) = self.controller.search_error() {
+            content = content.push(
+                widget::row()
+                    .spacing(4)
+                    .align_y(Alignment::Center)
+                    .push(widget::icon::from_name("dialog-warning-symbolic").size(14))
+                    .push(widget::text(error).size(11)),
+            );
+        }
+
+        if let Some(saved) = view::saved_searches(&self.controller, 4) {
+            content = content.push(saved.map(Message::Process));
+        }
 
         // Filter controls
         let filter_row = widget::row()
             .spacing(4)
             .align_y(Alignment::Center)
             .push(widget::text(fl!("show-all")).size(12))
-            .push(widget::toggler(self.show_all).on_toggle(Message::ToggleShowAll));
+            .push(
+                widget::toggler(self.controller.show_all())
+                    .on_toggle(|show_all| Message::Process(ProcessMessage::ToggleShowAll(show_all))),
+            );
 
         content = content.push(filter_row);
 
         // Column Headers
-        let header_row = widget::row()
+        let mut header_row = widget::row()
             .spacing(4)
             .padding([0, 0])
             .push(
@@ -195,10 +225,10 @@ impl cosmic::Application for AppModel {
                         .width(Length::Fill)
                         .align_x(cosmic::iced::alignment::Horizontal::Center),
                 )
-                    .on_press(Message::SortBy(SortBy::Name))
+                    .on_press(Message::Process(ProcessMessage::SortBy(SortBy::Name)))
                     .padding(0)
                     .class(cosmic::theme::Button::Text)
-                    .width(Length::Fixed(85.0))
+                    .width(Length::Fixed(NAME_WIDTH))
             )
             .push(
                 widget::button::custom(
@@ -206,7 +236,7 @@ impl cosmic::Application for AppModel {
                         .width(Length::Fill)
                         .align_x(cosmic::iced::alignment::Horizontal::Center),
                 )
-                    .on_press(Message::SortBy(SortBy::Pid))
+                    .on_press(Message::Process(ProcessMessage::SortBy(SortBy::Pid)))
                     .padding(0)
                     .class(cosmic::theme::Button::Text)
                     .width(Length::Fixed(60.0))
@@ -217,26 +247,29 @@ impl cosmic::Application for AppModel {
                         .width(Length::Fill)
                         .align_x(cosmic::iced::alignment::Horizontal::Center),
                 )
-                    .on_press(Message::SortBy(SortBy::Cpu))
+                    .on_press(Message::Process(ProcessMessage::SortBy(SortBy::Cpu)))
                     .padding(0)
                     .class(cosmic::theme::Button::Text)
                     .width(Length::Fixed(60.0))
             )
             .push(
                 widget::button::custom(
-                    widget::text(fl!("header-mem"))
+                    widget::text(self.config.memory_header())
                         .width(Length::Fill)
                         .align_x(cosmic::iced::alignment::Horizontal::Center),
                 )
-                    .on_press(Message::SortBy(SortBy::Memory))
+                    .on_press(Message::Process(ProcessMessage::SortBy(SortBy::Memory)))
                     .padding(0)
                     .class(cosmic::theme::Button::Text)
                     .width(Length::Fixed(70.0))
             );
+        for header in view::disk_io_headers(&self.controller, 70.0) {
+            header_row = header_row.push(header.map(Message::Process));
+        }
         content = content.push(header_row);
 
         // Confirmation dialog overlay
-        if let (Some(process), Some(mode)) = (&self.selected_process, &self.confirmation_mode) {
+        if let Some((process, mode)) = self.controller.confirmation() {
             let dialog = widget::column()
                 .spacing(8)
                 .padding(12)
@@ -253,20 +286,31 @@ impl cosmic::Application for AppModel {
                     widget::text(format!("{} (PID: {})", process.name, process.pid))
                         .size(11)
                 )
+                // Live values, updated with every sample until confirmed
+                .push(
+                    widget::text(format!(
+                        "{} {:.0}% · {} {}",
+                        fl!("header-cpu"),
+                        process.cpu_usage,
+                        self.config.memory_header(),
+                        view::memory_label(process.memory)
+                    ))
+                    .size(11)
+                )
                 .push(
                     widget::row()
                         .spacing(4)
                         .push(
                             widget::button::destructive(fl!("confirm"))
                                 .on_press(if matches!(mode, ConfirmationMode::ForceKill) {
-                                    Message::ConfirmForceKill
+                                    Message::Process(ProcessMessage::ConfirmForceKill)
                                 } else {
-                                    Message::ConfirmKill
+                                    Message::Process(ProcessMessage::ConfirmKill)
                                 })
                         )
                         .push(
                             widget::button::text(fl!("cancel"))
-                                .on_press(Message::CancelConfirmation)
+                                .on_press(Message::Process(ProcessMessage::CancelConfirmation))
                         )
                 );
 
@@ -277,29 +321,55 @@ impl cosmic::Application for AppModel {
             );
         }
 
-        // Process list with actions
-        let mut process_list = widget::list_column().spacing(2);
+        // Process list with actions; only the rows in view are built
+        let filtered_processes = self.controller.filtered_processes();
 
-        let filtered_processes = self.get_filtered_processes();
-
-        if filtered_processes.is_empty() {
-            process_list = process_list.add(
+        let process_list = if filtered_processes.is_empty() {
+            widget::column().push(
                 widget::container(widget::text(fl!("no-processes")))
                     .padding(10)
                     .center_x(Length::Fill),
-            );
+            )
         } else {
-            for process in filtered_processes.iter() {
-                let process_row = self.create_process_row(process);
-                process_list = process_list.add(process_row);
+            let by_pid: HashMap<u32, &ProcessInfo> = filtered_processes
+                .iter()
+                .map(|process| (process.pid, *process))
+                .collect();
+            let range = self.list.visible_range();
+            let below = self.list.rows().len() - range.end;
+
+            let mut process_list = widget::column().push(widget::Space::with_height(
+                Length::Fixed(self.list.height_of(range.start)),
+            ));
+            for pid in &self.list.rows()[range] {
+                if let Some(process) = by_pid.get(pid) {
+                    process_list = process_list.push(
+                        widget::container(self.create_process_row(process))
+                            .height(Length::Fixed(ROW_HEIGHT))
+                            .align_y(Alignment::Center),
+                    );
+                }
             }
-        }
+            process_list.push(widget::Space::with_height(Length::Fixed(
+                self.list.height_of(below),
+            )))
+        };
 
         let scrollable = widget::scrollable(process_list)
-            .height(Length::Fixed(300.0))
+            .id(LIST_ID.clone())
+            .on_scroll(|viewport| Message::ListScrolled {
+                offset: viewport.absolute_offset().y,
+                height: viewport.bounds().height,
+            })
+            .height(Length::Fixed(LIST_HEIGHT))
             .width(Length::Fill);
 
-        content = content.push(scrollable);
+        // Rows keep their order while the pointer is over them
+        content = content.push(
+            widget::mouse_area(scrollable)
+                .on_enter(Message::ListHovered(true))
+                .on_exit(Message::ListHovered(false)),
+        );
 
         // Info footer
         let count = filtered_processes.len() as i32;
@@ -308,7 +378,7 @@ impl cosmic::Application for AppModel {
         content = content.push(info);
 
         // Toast notification
-        if let Some(ref toast) = self.toast {
+        if let Some(toast) = self.controller.toast() {
             let toast_text = widget::text(&toast.message)
                 .size(11);
             
@@ -326,16 +396,34 @@ impl cosmic::Application for AppModel {
 
     /// Register subscriptions for this application.
     fn subscription(&self) -> Subscription<Self::Message> {
-        struct RefreshSubscription;
+        struct SamplerSubscription;
+        struct DbusSubscription;
+
+        let latest = self.latest.clone();
+
+        let settings = self.config.sampler_settings();
 
         Subscription::batch(vec![
-            // Auto-refresh every 2 seconds
+            // Sample processes in the background
+            Subscription::run_with_id(
+                (std::any::TypeId::of::<SamplerSubscription>(), settings),
+                sampler::messages(settings, self.latest.clone()).map(Message::Process),
+            ),
+            // Serve the D-Bus interface and forward its requests
             Subscription::run_with_id(
-                std::any::TypeId::of::<RefreshSubscription>(),
+                std::any::TypeId::of::<DbusSubscription>(),
                 cosmic::iced::stream::channel(4, move |mut channel| async move {
-                    loop {
-                        tokio::time::sleep(Duration::from_secs(2)).await;
-                        _ = channel.send(Message::RefreshProcesses).await;
+                    let (sender, mut requests) = tokio::sync::mpsc::unbounded_channel();
+                    let _connection = match dbus::serve(latest, sender).await {
+                        Ok(connection) => connection,
+                        Err(why) => {
+                            eprintln!("failed to register D-Bus service: {why}");
+                            return;
+                        }
+                    };
+
+                    while let Some(request) = requests.recv().await {
+                        _ = channel.send(Message::DbusRequest(request)).await;
                     }
                 }),
             ),
@@ -348,92 +436,79 @@ impl cosmic::Application for AppModel {
 
     /// Handles messages emitted by the application and its widgets.
     fn update(&mut self, message: Self::Message) -> Task<cosmic::Action<Self::Message>> {
-        match message {
-            Message::SubscriptionChannel => {}
+        let task = match message {
+            Message::SubscriptionChannel => Task::none(),
             Message::UpdateConfig(config) => {
+                self.controller.set_saved_searches(config.saved_searches.clone());
+                self.controller.set_show_disk_io(config.show_disk_io);
                 self.config = config;
+                Task::none()
             }
-            Message::RefreshProcesses => {
-                self.refresh_processes();
-            }
-            Message::KillProcess(pid) => {
-                self.handle_kill_process(pid, false);
+            Message::Process(message) => {
+                self.controller.update(message);
+                Task::none()
             }
-            Message::ForceKillProcess(pid) => {
-                self.handle_kill_process(pid, true);
-            }
-            Message::ConfirmKill => {
-                if let Some(process) = self.selected_process.clone() {
-                    self.execute_kill(&process, false);
+            Message::TogglePopup => {
+                if let Some(p) = self.popup.take() {
+                    self.list.set_hovered(false);
+                    destroy_popup(p)
+                } else {
+                    self.open_popup()
                 }
-                self.confirmation_mode = None;
-                self.selected_process = None;
             }
-            Message::ConfirmForceKill => {
-                if let Some(process) = self.selected_process.clone() {
-                    self.execute_kill(&process, true);
+            Message::DbusRequest(request) => {
+                match request {
+                    dbus::Request::ShowPopup(query) => {
+                        self.controller.update(ProcessMessage::UpdateSearch(query));
+                    }
+                    dbus::Request::ConfirmKill { pid, force } => {
+                        self.controller.update(if force {
+                            ProcessMessage::ForceKillProcess(pid)
+                        } else {
+                            ProcessMessage::KillProcess(pid)
+                        });
+                    }
                 }
-                self.confirmation_mode = None;
-                self.selected_process = None;
-            }
-            Message::CancelConfirmation => {
-                self.confirmation_mode = None;
-                self.selected_process = None;
-            }
-            Message::ToggleShowAll(show_all) => {
-                self.show_all = show_all;
-            }
-            Message::SortBy(sort_by) => {
-                self.sort_by = sort_by;
-                self.refresh_processes();
-            }
-            Message::UpdateSearch(query) => {
-                self.search_query = query;
-            }
-            Message::SelectProcess(pid) => {
-                if let Some(pid) = pid {
-                    self.selected_process = self.processes.iter()
-                        .find(|p| p.pid == pid)
-                        .cloned();
+
+                if self.popup.is_none() {
+                    self.open_popup()
                 } else {
-                    self.selected_process = None;
+                    Task::none()
                 }
             }
-            Message::ShowToast(message, is_error) => {
-                self.toast = Some(Toast { message, is_error });
+            Message::ListScrolled { offset, height } => {
+                // Scrolling does not change the rows
+                self.list.scrolled(offset, height);
+                return Task::none();
             }
-            Message::ClearToast => {
-                self.toast = None;
-            }
-            Message::TogglePopup => {
-                return if let Some(p) = self.popup.take() {
-                    destroy_popup(p)
-                } else {
-                    self.refresh_processes();
-                    let new_id = Id::unique();
-                    self.popup.replace(new_id);
-                    let mut popup_settings = self.core.applet.get_popup_settings(
-                        self.core.main_window_id().unwrap(),
-                        new_id,
-                        None,
-                        None,
-                        None,
-                    );
-                    popup_settings.positioner.size_limits = Limits::NONE
-                        .max_width(500.0)
-                        .min_width(450.0)
-                        .min_height(450.0)
-                        .max_height(500.0);
-                    get_popup(popup_settings)
-                }
+            Message::ListHovered(hovered) => {
+                self.list.set_hovered(hovered);
+                Task::none()
             }
             Message::PopupClosed(id) => {
                 if self.popup.as_ref() == Some(&id) {
                     self.popup = None;
+                    self.list.set_hovered(false);
                 }
+                Task::none()
             }
-        }
-        Task::none()
+        };
+
+        // Rows may have changed: keep the list and its scroll position in
+        // step, without reordering while a kill is being confirmed
+        self.list.set_paused(self.controller.is_confirming());
+        let pids: Vec<u32> = self
+            .controller
+            .filtered_processes()
+            .iter()
+            .map(|p| p.pid)
+            .collect();
+        let scroll = match self.list.set_rows(pids) {
+            Some(y) => scrollable::scroll_to(LIST_ID.clone(), AbsoluteOffset { x: 0.0, y }),
+            None => Task::none(),
+        };
+
+        Task::batch([task, scroll, view::jobs(&mut self.controller, Message::Process)])
     }
 
     fn style(&self) -> Option<cosmic::iced_runtime::Appearance> {
@@ -442,137 +517,50 @@ impl cosmic::Application for AppModel {
 }
 
 impl AppModel {
-    fn refresh_processes(&mut self) {
-        let mut processes = self.process_manager.get_processes(self.sort_by);
-        if !self.show_all {
-            processes.truncate(10);
-        }
-        self.processes = processes;
-    }
-
-    fn get_filtered_processes(&self) -> Vec<&ProcessInfo> {
-        if self.search_query.is_empty() {
-            self.processes.iter().collect()
-        } else {
-            let query = self.search_query.to_lowercase();
-            self.processes
-                .iter()
-                .filter(|p| {
-                    p.name.to_lowercase().contains(&query)
-                        || p.pid.to_string().contains(&query)
-                })
-                .collect()
-        }
-    }
-
-    fn handle_kill_process(&mut self, pid: u32, force: bool) {
-        // Find the process
-        let process = match self.processes.iter().find(|p| p.pid == pid) {
-            Some(p) => p.clone(),
-            None => {
-                self.toast = Some(Toast {
-                    message: fl!("error-process-not-found"),
-                    is_error: true,
-                });
-                return;
-            }
-        };
-
-        // Check permissions before showing confirmation
-        match self.process_manager.can_kill_process(&process) {
-            Err(ProcessError::PermissionDenied) => {
-                self.toast = Some(Toast {
-                    message: fl!("notification-permission-denied"),
-                    is_error: true,
-                });
-                return;
-            }
-            Err(ProcessError::Protected(name)) => {
-                self.toast = Some(Toast {
-                    message: fl!("notification-protected", name = name),
-                    is_error: true,
-                });
-                return;
-            }
-            Err(e) => {
-                self.toast = Some(Toast {
-                    message: format!("{}: {:?}", fl!("error-unknown-error"), e),
-                    is_error: true,
-                });
-                return;
-            }
-            Ok(()) => {}
-        }
-
-        // Show confirmation dialog
-        self.selected_process = Some(process);
-        self.confirmation_mode = Some(if force {
-            ConfirmationMode::ForceKill
-        } else {
-            ConfirmationMode::Kill
-        });
-    }
-
-    fn execute_kill(&mut self, process: &ProcessInfo, force: bool) {
-        let result = if force {
-            self.process_manager.force_kill_process(process.pid)
-        } else {
-            self.process_manager.kill_process(process.pid)
-        };
-
-        match result {
-            Ok(()) => {
-                self.toast = Some(Toast {
-                    message: if force {
-                        fl!("notification-force-kill-success", name = process.name.clone())
-                    } else {
-                        fl!("notification-kill-success", name = process.name.clone())
-                    },
-                    is_error: false,
-                });
-                self.refresh_processes();
-            }
-            Err(e) => {
-                let error_msg = match e {
-                    ProcessError::SignalFailed(msg) => {
-                        if force {
-                            fl!("error-sigkill-failed", error = msg)
-                        } else {
-                            fl!("error-sigterm-failed", error = msg)
-                        }
-                    }
-                    ProcessError::PermissionDenied => fl!("notification-permission-denied"),
-                    ProcessError::NotFound => fl!("error-process-not-found"),
-                    ProcessError::Protected(name) => {
-                        fl!("notification-protected", name = name)
-                    }
-                    ProcessError::Unknown(msg) => {
-                        fl!("error-unknown-error", error = msg)
-                    }
-                };
-                
-                self.toast = Some(Toast {
-                    message: fl!("notification-kill-failed", error = error_msg),
-                    is_error: true,
-                });
-                self.refresh_processes();
-            }
-        }
+    fn open_popup(&mut self) -> Task<cosmic::Action<Message>> {
+        self.controller.request_sample();
+        let new_id = Id::unique();
+        self.popup.replace(new_id);
+        let mut popup_settings = self.core.applet.get_popup_settings(
+            self.core.main_window_id().unwrap(),
+            new_id,
+            None,
+            None,
+            None,
+        );
+        popup_settings.positioner.size_limits = Limits::NONE
+            .max_width(500.0)
+            .min_width(450.0)
+            .min_height(450.0)
+            .max_height(500.0);
+        get_popup(popup_settings)
     }
 
     fn create_process_row<'a>(&self, process: &'a ProcessInfo) -> Element<'a, Message> {
-        let is_selected = self.selected_process.as_ref().map(|p| p.pid) == Some(process.pid);
-
-        // Truncar nome se muito longo
-        let display_name = if process.name.len() > 15 {
-            format!("{}...", &process.name[..12])
-        } else {
-            process.name.clone()
-        };
+        let is_selected = self.controller.is_selected(process);
 
-        let name_text = widget::text(display_name)
+        // Program and arguments, with the search matches in bold
+        let label = truncate_name(&process.label(), NAME_CHARS);
+        let name_text = rich_text(view::search_spans(&self.controller, &label))
             .size(12)
-            .width(Length::Fixed(85.0));
+            .width(Length::Fixed(NAME_WIDTH));
+        // No room for a second line: containers name themselves on hover,
+        // packaged apps their app ID and services their unit
+        let subtitle = match (&process.container, &process.package, &process.unit) {
+            (Some(container), _, _) => Some(container.label().to_string()),
+            (None, Some(package), _) => Some(package.app_id.clone()),
+            (None, None, Some(unit)) => Some(unit.name.clone()),
+            (None, None, None) => None,
+        };
+        let name_text: Element<'a, Message> = match subtitle {
+            Some(subtitle) => widget::tooltip(
+                name_text,
+                widget::text(subtitle),
+                widget::tooltip::Position::Top,
+            )
+            .into(),
+            None => name_text.into(),
+        };
 
         let pid_text = widget::text(format!("{}", process.pid))
             .size(11)
@@ -584,18 +572,18 @@ impl AppModel {
             .width(Length::Fixed(60.0))
             .align_x(cosmic::iced::alignment::Horizontal::Center);
 
-        let mem_text = widget::text(format!("{:.0}MB", process.memory as f32 / 1024.0 / 1024.0))
+        let mem_text = widget::text(view::memory_label(process.memory))
             .size(11)
             .width(Length::Fixed(70.0))
             .align_x(cosmic::iced::alignment::Horizontal::Center);
 
         // Check if process can be killed
-        let can_kill = self.process_manager.can_kill_process(process).is_ok();
+        let can_kill = self.controller.can_kill(process);
 
         // Compact action buttons
         let kill_button = widget::tooltip(
             widget::button::custom(widget::icon::from_name("process-stop-symbolic"))
-                .on_press(Message::KillProcess(process.pid))
+                .on_press(Message::Process(ProcessMessage::KillProcess(process.pid)))
                 .padding(4)
                 .class(cosmic::theme::Button::Text),
             widget::text(fl!("kill-tooltip")),
@@ -604,7 +592,7 @@ impl AppModel {
 
         let force_kill_button = widget::tooltip(
             widget::button::custom(widget::icon::from_name("edit-delete-symbolic"))
-                .on_press(Message::ForceKillProcess(process.pid))
+                .on_press(Message::Process(ProcessMessage::ForceKillProcess(process.pid)))
                 .padding(4)
                 .class(cosmic::theme::Button::Text),
             widget::text(fl!("force-kill-tooltip")),
@@ -624,18 +612,36 @@ impl AppModel {
                         .padding(4)
                 )
         };
+        // Processes of a frozen app are marked, and thawed with a click
+        let buttons = match process.app_cgroup.as_deref().filter(|_| process.frozen) {
+            Some(app_cgroup) => widget::row().spacing(2).push(widget::tooltip(
+                widget::button::icon(widget::icon::from_name("media-playback-pause-symbolic"))
+                    .on_press(Message::Process(ProcessMessage::SetFrozen {
+                        pid: process.pid,
+                        frozen: false,
+                    }))
+                    .padding(4),
+                widget::text(fl!("frozen-tooltip", app = cgroup::app_name(app_cgroup))),
+                widget::tooltip::Position::Top,
+            )),
+            None => widget::row(),
+        }
+        .push(buttons);
 
-        let info_row = widget::row()
+        let mut info_row = widget::row()
             .spacing(4)
             .align_y(Alignment::Center)
             .push(name_text)
             .push(pid_text)
             .push(cpu_text)
-            .push(mem_text)
-            .push(widget::horizontal_space());
+            .push(mem_text);
+        for rate in view::disk_io_rates(&self.controller, process, 11, 70.0) {
+            info_row = info_row.push(rate);
+        }
+        let info_row = info_row.push(widget::horizontal_space());
 
         let info_button = widget::button::custom(info_row)
-            .on_press(Message::SelectProcess(Some(process.pid)))
+            .on_press(Message::Process(ProcessMessage::SelectProcess(Some(process.pid))))
             .padding([4, 0])
             .width(Length::Fill)
             .class(if is_selected {
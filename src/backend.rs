@@ -0,0 +1,212 @@
+// SPDX-License-Identifier: MIT
+
+//! Sources of process samples.
+//!
+//! [`ProcessManager`](crate::process::ProcessManager) reads processes through a
+//! [`ProcessBackend`]. On Linux the lean [`ProcfsBackend`] is used; the
+//! [`SysinfoBackend`] remains as a portable fallback.
+
+use crate::cgroup::Limits;
+use crate::files::{Holder, OpenFile};
+use crate::memory::{MemoryBreakdown, MemoryMetric};
+use crate::priority::{self, IoPriority};
+use crate::process::{is_system_process, search_cmdline, ProcessInfo, ThreadInfo};
+use crate::procfs::ProcfsBackend;
+use crate::sockets::SocketInfo;
+use std::path::{Path, PathBuf};
+use sysinfo::{Pid, Process, ProcessRefreshKind, ProcessesToUpdate, System, ThreadKind, UpdateKind};
+
+/// Something that can list the running processes
+pub trait ProcessBackend: Send {
+    /// Read every running process, unsorted.
+    ///
+    /// CPU usage is measured since the previous call, so the first sample
+    /// reports zero for every process.
+    fn sample(&mut self) -> Vec<ProcessInfo>;
+
+    /// Read a single process. Backends that cannot read one on its own
+    /// look it up in a new sample, which CPU usage is then measured from.
+    fn process(&mut self, pid: u32) -> Option<ProcessInfo> {
+        self.sample().into_iter().find(|p| p.pid == pid)
+    }
+
+    /// Read the threads of a process, unsorted. CPU usage is measured since
+    /// the previous call for the same process. Backends that cannot list
+    /// threads return none.
+    fn threads(&mut self, _pid: u32) -> Vec<ThreadInfo> {
+        Vec::new()
+    }
+
+    /// Report [`ProcessInfo::memory`] by `metric`, along with the breakdown
+    /// it comes from. Backends without a breakdown keep reporting RSS.
+    fn set_memory_metric(&mut self, _metric: MemoryMetric) {}
+
+    /// Measure [`ProcessInfo::disk_read`] and [`ProcessInfo::disk_write`],
+    /// which costs another file per process. Backends that cannot leave
+    /// them at zero.
+    fn set_disk_io(&mut self, _enabled: bool) {}
+
+    /// Fill in [`ProcessInfo::ports`], which means reading the descriptors
+    /// of every process. Backends that cannot leave them empty.
+    fn set_ports(&mut self, _enabled: bool) {}
+
+    /// Fill in [`ProcessInfo::held_paths`] with which of `paths` each
+    /// process holds, which means reading the descriptors and mappings of
+    /// every process. Backends that cannot leave them empty.
+    fn set_held_paths(&mut self, _paths: Vec<PathBuf>) {}
+
+    /// Read the files a process holds open.
+    fn open_files(&mut self, _pid: u32) -> Vec<OpenFile> {
+        Vec::new()
+    }
+
+    /// Find the processes holding `path` or anything below it.
+    fn holders(&mut self, _path: &Path) -> Vec<Holder> {
+        Vec::new()
+    }
+
+    /// Read the sockets a process has open.
+    fn sockets(&mut self, _pid: u32) -> Vec<SocketInfo> {
+        Vec::new()
+    }
+
+    /// Read the memory and CPU limits of a single process's cgroup.
+    fn limits(&mut self, _pid: u32) -> Option<Limits> {
+        None
+    }
+
+    /// Read the memory breakdown of a single process.
+    fn memory_breakdown(&mut self, _pid: u32) -> Option<MemoryBreakdown> {
+        None
+    }
+
+    /// Ask the kernel for the I/O priority of a single process, whose nice
+    /// value decides it when none was set.
+    fn io_priority(&mut self, pid: u32, nice: i32) -> Option<IoPriority> {
+        priority::io_priority(pid, nice).ok()
+    }
+}
+
+/// The best backend for this system
+pub fn default_backend() -> Box<dyn ProcessBackend> {
+    if Path::new("/proc/self/stat").exists() {
+        Box::new(ProcfsBackend::new())
+    } else {
+        Box::new(SysinfoBackend::new())
+    }
+}
+
+/// Backend built on the `sysinfo` crate
+pub struct SysinfoBackend {
+    system: System,
+}
+
+impl SysinfoBackend {
+    pub fn new() -> Self {
+        Self {
+            system: System::new(),
+        }
+    }
+
+    /// Only what the process list shows; static data is read once per process
+    fn refresh_kind() -> ProcessRefreshKind {
+        ProcessRefreshKind::new()
+            .with_cpu()
+            .with_memory()
+            .with_user(UpdateKind::OnlyIfNotSet)
+            .with_cmd(UpdateKind::OnlyIfNotSet)
+            .with_exe(UpdateKind::OnlyIfNotSet)
+    }
+
+    fn info(pid: Pid, process: &Process) -> ProcessInfo {
+        let name = process.name().to_string_lossy().to_string();
+        let nice = priority::nice(pid.as_u32()).unwrap_or(0);
+        let cmdline: Vec<String> = process
+            .cmd()
+            .iter()
+            .map(|arg| arg.to_string_lossy().to_string())
+            .collect();
+
+        ProcessInfo {
+            pid: pid.as_u32(),
+            is_system: is_system_process(&name),
+            name,
+            cpu_usage: process.cpu_usage(),
+            memory: Some(process.memory()),
+            status: format!("{:?}", process.status()),
+            parent_pid: process.parent().map(|parent| parent.as_u32()),
+            uid: process.user_id().map(|uid| **uid),
+            start_time: process.start_time(),
+            search_cmdline: search_cmdline(&cmdline),
+            cmdline,
+            exe: process.exe().map(Path::to_path_buf),
+            unit: None,
+            app_cgroup: None,
+            frozen: false,
+            limits: None,
+            package: None,
+            container: None,
+            pid_namespace: None,
+            nice,
+            io_priority: None,
+            memory_breakdown: None,
+            disk_read: 0,
+            disk_write: 0,
+            ports: Vec::new(),
+            held_paths: Vec::new(),
+        }
+    }
+}
+
+impl Default for SysinfoBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProcessBackend for SysinfoBackend {
+    fn sample(&mut self) -> Vec<ProcessInfo> {
+        self.system
+            .refresh_processes_specifics(ProcessesToUpdate::All, true, Self::refresh_kind());
+
+        self.system
+            .processes()
+            .iter()
+            // sysinfo also lists the threads of each process
+            .filter(|(_, process)| process.thread_kind() != Some(ThreadKind::Userland))
+            .map(|(pid, process)| Self::info(*pid, process))
+            .collect()
+    }
+
+    /// A process as of the last sample, as refreshing a single process
+    /// would restart the measure of its CPU usage
+    fn process(&mut self, pid: u32) -> Option<ProcessInfo> {
+        let pid = Pid::from_u32(pid);
+        self.system.process(pid).map(|process| Self::info(pid, process))
+    }
+
+    /// Threads as of the last sample, which sysinfo lists as processes
+    fn threads(&mut self, pid: u32) -> Vec<ThreadInfo> {
+        let pid = Pid::from_u32(pid);
+        let Some(process) = self.system.process(pid) else {
+            return Vec::new();
+        };
+        let mut tids: Vec<Pid> = process.tasks().into_iter().flatten().copied().collect();
+        if !tids.contains(&pid) {
+            tids.push(pid);
+        }
+
+        tids.into_iter()
+            .filter_map(|tid| {
+                let task = self.system.process(tid)?;
+                Some(ThreadInfo {
+                    tid: tid.as_u32(),
+                    name: task.name().to_string_lossy().to_string(),
+                    cpu_usage: task.cpu_usage().min(100.0),
+                    status: format!("{:?}", task.status()),
+                    nice: priority::nice(tid.as_u32()).unwrap_or(0),
+                })
+            })
+            .collect()
+    }
+}
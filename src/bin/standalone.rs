@@ -3,18 +3,77 @@
 //! Standalone launcher for Process Killer
 //! Can be launched with Ctrl+Shift+Esc or from terminal
 
+use cosmic::cosmic_config::{self, CosmicConfigEntry};
+use cosmic_applet_process_killer::cli::{self, Command, ExportArgs};
+use cosmic_applet_process_killer::config::{Config, CONFIG_ID};
+use cosmic_applet_process_killer::export;
+use cosmic_applet_process_killer::process::{filter_processes, ProcessManager};
 use cosmic_applet_process_killer::standalone::StandaloneApp;
+use std::io::{self, Write};
 
 fn main() -> cosmic::iced::Result {
     // Initialize i18n
     let requested_languages = i18n_embed::DesktopLanguageRequester::requested_languages();
     cosmic_applet_process_killer::i18n::init(&requested_languages);
 
-    // Launch as a standalone window application
-    cosmic::app::run::<StandaloneApp>(
+    let flags = match cli::parse(std::env::args().skip(1)) {
+        Ok(Command::Window(flags)) => flags,
+        Ok(Command::Export(args)) => {
+            if let Err(why) = run_export(&args) {
+                eprintln!("error while exporting processes: {why}");
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Ok(Command::Help) => {
+            println!("{}", cli::USAGE);
+            return Ok(());
+        }
+        Err(why) => {
+            eprintln!("{why}\n\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    // Launch as a standalone window application, or hand the flags to the
+    // window that is already open
+    cosmic::app::run_single_instance::<StandaloneApp>(
         cosmic::app::Settings::default()
             .size_limits(cosmic::iced::Limits::NONE.min_width(600.0).min_height(400.0))
             .size(cosmic::iced::Size::new(800.0, 600.0)),
-        (),
+        flags,
     )
 }
+
+/// Write the same list the window would show for these options.
+fn run_export(args: &ExportArgs) -> io::Result<()> {
+    let config = cosmic_config::Config::new(CONFIG_ID, Config::VERSION)
+        .ok()
+        .map(|context| match Config::get_entry(&context) {
+            Ok(config) => config,
+            Err((_errors, config)) => config,
+        })
+        .unwrap_or_default();
+
+    let mut manager = ProcessManager::new();
+    manager.set_memory_metric(config.memory_metric);
+    manager.set_disk_io(config.show_disk_io || args.sort_by.needs_disk_io());
+    manager.set_ports(args.search.needs_ports());
+    manager.set_held_paths(args.search.held_paths());
+    // CPU usage and disk rates are computed between two refreshes
+    manager.sample();
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+
+    let mut processes = manager.get_processes(args.sort_by);
+    if !args.show_all {
+        processes.truncate(10);
+    }
+    let filtered = filter_processes(&processes, &args.search);
+
+    let writer: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(io::BufWriter::new(std::fs::File::create(path)?)),
+        None => Box::new(io::stdout().lock()),
+    };
+
+    export::write_snapshot(writer, args.format, config.memory_metric, &filtered)
+}
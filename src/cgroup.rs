@@ -0,0 +1,219 @@
+// SPDX-License-Identifier: MIT
+
+//! Freezing and limiting whole applications through cgroup v2.
+//!
+//! SIGSTOP only stops the process it is sent to, so the helpers and children
+//! of a multi-process application keep running. systemd and COSMIC start
+//! applications in their own cgroup, an `app-*.scope` or `app-*.service`
+//! unit, and writing `1` to its `cgroup.freeze` stops every process inside at
+//! once. The user's own cgroups are delegated to them, so no privileges are
+//! needed. Whether a cgroup is frozen is reported by `cgroup.events`, which
+//! also covers cgroups frozen through a parent.
+//!
+//! The same cgroups cap the memory (`memory.max`) and CPU time (`cpu.max`)
+//! of everything inside them. Services are limited through systemd instead,
+//! see [`UnitManager::set_limits`](crate::systemd::UnitManager::set_limits).
+
+use crate::process::{ProcessError, ProcessResult};
+use crate::systemd;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+/// Mount point of the cgroup v2 hierarchy
+pub const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+
+/// The cgroup of the application a process belongs to, given the process's
+/// cgroup path: the innermost `app-*` unit and everything above it.
+pub fn app_cgroup(cgroup: &str) -> Option<&str> {
+    let mut end = None;
+    let mut offset = 0;
+    for component in cgroup.split('/') {
+        offset += component.len();
+        if component.starts_with("app-")
+            && (component.ends_with(".scope") || component.ends_with(".service"))
+        {
+            end = Some(offset);
+        }
+        offset += 1;
+    }
+    end.map(|end| &cgroup[..end])
+}
+
+/// The cgroup whose limits apply to a process, given the process's cgroup
+/// path: its app's, or else that of a unit that can be managed on its own.
+pub fn limit_cgroup(cgroup: &str) -> Option<&str> {
+    app_cgroup(cgroup).or_else(|| {
+        systemd::unit_of(cgroup)
+            .filter(systemd::Unit::is_manageable)
+            .and(systemd::unit_cgroup(cgroup))
+    })
+}
+
+/// Name of the unit an application cgroup stands for, for display
+pub fn app_name(cgroup: &str) -> &str {
+    cgroup.rsplit('/').next().unwrap_or(cgroup)
+}
+
+/// Whether the cgroup at `cgroup` below `root` is frozen
+pub fn is_frozen(root: &Path, cgroup: &str) -> bool {
+    fs::read_to_string(cgroup_dir(root, cgroup).join("cgroup.events"))
+        .is_ok_and(|events| parse_frozen(&events))
+}
+
+/// The `frozen` key of `cgroup.events`, a `key value` line per event
+pub fn parse_frozen(events: &str) -> bool {
+    events
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .any(|(key, value)| key == "frozen" && value.trim() == "1")
+}
+
+/// Memory and CPU limits of a cgroup, `None` where there is no limit
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Limits {
+    /// Most memory its processes may use together, in bytes
+    pub memory_max: Option<u64>,
+    /// Most CPU time its processes may use together, in percent of one core
+    pub cpu_max: Option<u32>,
+}
+
+/// Period of the CPU quotas written here, in microseconds, as systemd uses
+pub const CPU_PERIOD: u64 = 100_000;
+
+/// The limits of the cgroup at `cgroup` below `root`, if it exists. Limits
+/// of controllers that are not enabled for it read as none.
+pub fn read_limits(root: &Path, cgroup: &str) -> Option<Limits> {
+    let dir = cgroup_dir(root, cgroup);
+    if !dir.is_dir() {
+        return None;
+    }
+    let read = |file: &str| fs::read_to_string(dir.join(file)).unwrap_or_default();
+    Some(Limits {
+        memory_max: parse_memory_max(&read("memory.max")),
+        cpu_max: parse_cpu_max(&read("cpu.max")),
+    })
+}
+
+/// The content of `memory.max`: bytes, or `max`
+pub fn parse_memory_max(content: &str) -> Option<u64> {
+    content.trim().parse().ok()
+}
+
+/// The content of `cpu.max`, `<quota> <period>` in microseconds or `max
+/// <period>`, as a percentage of one core
+pub fn parse_cpu_max(content: &str) -> Option<u32> {
+    let mut fields = content.split_whitespace();
+    let quota: u64 = fields.next()?.parse().ok()?;
+    let period: u64 = fields
+        .next()
+        .map_or(Some(CPU_PERIOD), |period| period.parse().ok())?;
+    (period > 0).then(|| (quota * 100 / period) as u32)
+}
+
+fn cgroup_dir(root: &Path, cgroup: &str) -> PathBuf {
+    root.join(cgroup.trim_start_matches('/'))
+}
+
+/// Write `value` to an interface file of an existing cgroup.
+fn write_file(file: &Path, value: &str) -> ProcessResult<()> {
+    // Files of cgroups and controllers that do not exist are never created
+    fs::OpenOptions::new()
+        .write(true)
+        .truncate(true)
+        .open(file)
+        .and_then(|mut file| file.write_all(value.as_bytes()))
+        .map_err(|error| match error.kind() {
+            ErrorKind::PermissionDenied => ProcessError::PermissionDenied,
+            ErrorKind::NotFound => ProcessError::NotFound,
+            _ => ProcessError::Unknown(error.to_string()),
+        })
+}
+
+/// Something that can freeze and thaw cgroups
+pub trait Freezer: Send + Sync {
+    fn set_frozen(&self, cgroup: &str, frozen: bool) -> ProcessResult<()>;
+}
+
+/// Freezes cgroups by writing `cgroup.freeze` below a cgroup v2 mount
+pub struct CgroupFreezer {
+    root: PathBuf,
+}
+
+impl CgroupFreezer {
+    pub fn new() -> Self {
+        Self::with_root(CGROUP_ROOT)
+    }
+
+    /// Freeze cgroups of the hierarchy mounted at, or copied to, `root`.
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl Default for CgroupFreezer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Freezer for CgroupFreezer {
+    fn set_frozen(&self, cgroup: &str, frozen: bool) -> ProcessResult<()> {
+        let file = cgroup_dir(&self.root, cgroup).join("cgroup.freeze");
+        write_file(&file, if frozen { "1" } else { "0" })
+    }
+}
+
+/// Something that can limit cgroups
+pub trait Limiter: Send + Sync {
+    fn set_limits(&self, cgroup: &str, limits: Limits) -> ProcessResult<()>;
+}
+
+/// Limits cgroups by writing `memory.max` and `cpu.max` below a cgroup v2
+/// mount. Both controllers have to be enabled for the cgroup already; the
+/// memory limit is restored when the CPU limit cannot be set.
+pub struct CgroupLimiter {
+    root: PathBuf,
+}
+
+impl CgroupLimiter {
+    pub fn new() -> Self {
+        Self::with_root(CGROUP_ROOT)
+    }
+
+    /// Limit cgroups of the hierarchy mounted at, or copied to, `root`.
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl Default for CgroupLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Limiter for CgroupLimiter {
+    fn set_limits(&self, cgroup: &str, limits: Limits) -> ProcessResult<()> {
+        let dir = cgroup_dir(&self.root, cgroup);
+        let memory_max = limits
+            .memory_max
+            .map_or_else(|| "max".to_string(), |bytes| bytes.to_string());
+        let cpu_max = match limits.cpu_max {
+            // The kernel refuses quotas below a millisecond
+            Some(percent) => (u64::from(percent) * CPU_PERIOD / 100)
+                .max(1000)
+                .to_string(),
+            None => "max".to_string(),
+        };
+        let previous_memory_max = fs::read_to_string(dir.join("memory.max"));
+        write_file(&dir.join("memory.max"), &memory_max)?;
+        let result = write_file(&dir.join("cpu.max"), &format!("{cpu_max} {CPU_PERIOD}"));
+        // Limits are set together or not at all
+        if let (Err(_), Ok(previous)) = (&result, previous_memory_max) {
+            _ = write_file(&dir.join("memory.max"), previous.trim());
+        }
+        result
+    }
+}
@@ -0,0 +1,114 @@
+// SPDX-License-Identifier: MIT
+
+//! Command-line handling for the standalone binary
+
+use crate::export::ExportFormat;
+use crate::process::SortBy;
+use crate::query::Query;
+use crate::standalone::Flags;
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: cosmic-process-killer-standalone [OPTIONS]
+
+Options:
+  --export <jsonl|csv>   Write a process snapshot instead of opening the window
+  --output <file>        Snapshot destination (default: standard output)
+  --search <query>       Only include processes matching the query,
+                         e.g. 'cpu>50 user:me name:/fire.*/'
+  --pid <n>              Open the details of this process
+  --sort <cpu|mem|pid|name|read|write>
+                         Sort order (default: cpu)
+  --show-all             Include every process, not only the top 10
+  --tree                 Show processes nested under their parents
+  -h, --help             Print this help";
+
+/// What the standalone binary was asked to do
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Open the window, or activate the one already running
+    Window(Flags),
+    /// Write a snapshot and exit
+    Export(ExportArgs),
+    /// Print usage and exit
+    Help,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportArgs {
+    pub format: ExportFormat,
+    pub output: Option<PathBuf>,
+    pub search: Query,
+    pub sort_by: SortBy,
+    pub show_all: bool,
+}
+
+/// Parse the arguments following the program name.
+pub fn parse<I>(args: I) -> Result<Command, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+    let mut format = None;
+    let mut output = None;
+    let mut search = None;
+    let mut pid = None;
+    let mut sort_by = None;
+    let mut show_all = false;
+    let mut tree = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--export" => {
+                let value = value_for(&arg, args.next())?;
+                format = Some(
+                    ExportFormat::from_name(&value)
+                        .ok_or_else(|| format!("unknown export format: {value}"))?,
+                );
+            }
+            "--output" => output = Some(PathBuf::from(value_for(&arg, args.next())?)),
+            "--search" => search = Some(value_for(&arg, args.next())?),
+            "--pid" => {
+                let value = value_for(&arg, args.next())?;
+                pid = Some(
+                    value
+                        .parse::<u32>()
+                        .map_err(|_| format!("invalid PID: {value}"))?,
+                );
+            }
+            "--sort" => {
+                let value = value_for(&arg, args.next())?;
+                sort_by = Some(
+                    SortBy::from_name(&value)
+                        .ok_or_else(|| format!("unknown sort key: {value}"))?,
+                );
+            }
+            "--show-all" => show_all = true,
+            "--tree" => tree = true,
+            other => return Err(format!("unexpected argument: {other}")),
+        }
+    }
+
+    Ok(match format {
+        Some(format) => Command::Export(ExportArgs {
+            format,
+            output,
+            search: Query::parse(&search.unwrap_or_default())
+                .map_err(|e| format!("invalid search: {e}"))?,
+            sort_by: sort_by.unwrap_or(SortBy::Cpu),
+            show_all,
+        }),
+        None => Command::Window(Flags {
+            search,
+            pid,
+            sort_by,
+            show_all,
+            tree,
+        }),
+    })
+}
+
+fn value_for(flag: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("{flag} requires a value"))
+}
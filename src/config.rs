@@ -1,6 +1,17 @@
 // SPDX-License-Identifier: MIT
 
-use cosmic::cosmic_config::{self, cosmic_config_derive::CosmicConfigEntry, CosmicConfigEntry};
+use crate::fl;
+use crate::keybindings::KeyBindings;
+use crate::memory::MemoryMetric;
+use crate::query::SearchStore;
+use crate::sampler::{SamplerSettings, DEFAULT_INTERVAL};
+use cosmic::cosmic_config::{
+    self, cosmic_config_derive::CosmicConfigEntry, ConfigSet, CosmicConfigEntry,
+};
+use std::time::Duration;
+
+/// Config ID shared by the applet and the standalone window
+pub const CONFIG_ID: &str = "com.system.CosmicProcessKiller";
 
 #[derive(Debug, Default, Clone, CosmicConfigEntry, Eq, PartialEq)]
 #[version = 1]
@@ -9,4 +20,46 @@ pub struct Config {
     pub cpu_threshold: Option<u32>,
     /// Auto-refresh interval in seconds (default: 2)
     pub refresh_interval: Option<u32>,
+    /// Keyboard shortcuts for the standalone window
+    pub key_bindings: KeyBindings,
+    /// Search queries saved from the search box
+    pub saved_searches: Vec<String>,
+    /// What the memory column shows and memory sorting compares
+    pub memory_metric: MemoryMetric,
+    /// Show disk read and write columns, which are then sampled
+    pub show_disk_io: bool,
+}
+
+impl Config {
+    /// Time between process samples
+    pub fn sample_interval(&self) -> Duration {
+        self.refresh_interval
+            .map(|secs| Duration::from_secs(secs.max(1).into()))
+            .unwrap_or(DEFAULT_INTERVAL)
+    }
+
+    /// What the background sampler reads
+    pub fn sampler_settings(&self) -> SamplerSettings {
+        SamplerSettings {
+            interval: self.sample_interval(),
+            memory_metric: self.memory_metric,
+        }
+    }
+
+    /// Title of the memory column, naming the metric unless it is RSS
+    pub fn memory_header(&self) -> String {
+        match self.memory_metric {
+            MemoryMetric::Rss => fl!("header-mem"),
+            metric => fl!("header-mem-metric", metric = metric.as_str()),
+        }
+    }
+}
+
+/// Saved searches are written to the config shared by the applet and the
+/// window, which both watch it.
+impl SearchStore for cosmic_config::Config {
+    fn save(&self, searches: &[String]) -> Result<(), String> {
+        self.set("saved_searches", searches)
+            .map_err(|why| why.to_string())
+    }
 }
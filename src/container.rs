@@ -0,0 +1,240 @@
+// SPDX-License-Identifier: MIT
+
+//! Processes running in Docker and Podman containers, and stopping them.
+//!
+//! Container runtimes put each container in its own cgroup named after the
+//! container ID (`docker-<id>.scope`, `libpod-<id>.scope`, or `/docker/<id>`
+//! without systemd) and usually in its own PID namespace, where its main
+//! process is PID 1. Killing that process only gets the container restarted
+//! by its restart policy, so containers are stopped through the runtime's
+//! HTTP API on its local socket instead, which also resolves IDs to names.
+//! Both runtimes serve the Docker API, Podman on its compatibility endpoints.
+
+use crate::process::{ProcessError, ProcessResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, ErrorKind, Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Runtime {
+    Docker,
+    Podman,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Container {
+    pub runtime: Runtime,
+    /// Full ID, 64 hexadecimal digits
+    pub id: String,
+    /// Name given to it, if the runtime could be asked
+    pub name: Option<String>,
+}
+
+impl Container {
+    /// The ID as the runtimes show it, shortened to 12 digits
+    pub fn short_id(&self) -> &str {
+        &self.id[..self.id.len().min(12)]
+    }
+
+    /// The name, or the short ID of unnamed containers
+    pub fn label(&self) -> &str {
+        self.name.as_deref().unwrap_or_else(|| self.short_id())
+    }
+}
+
+/// Seconds a container gets to exit after SIGTERM before it is killed
+pub const STOP_TIMEOUT: u64 = 10;
+/// How long listing containers may take; it holds up the refresh
+const LIST_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// The container in a cgroup path, without its name
+pub fn container_of(cgroup: &str) -> Option<Container> {
+    let mut parent = "";
+    let mut container = None;
+    for component in cgroup.split('/') {
+        container = component_container(parent, component).or(container);
+        parent = component;
+    }
+    container
+}
+
+/// The container a component of a cgroup path stands for, given the
+/// component above it
+fn component_container(parent: &str, component: &str) -> Option<Container> {
+    let (runtime, id) = if let Some(scope) = component.strip_suffix(".scope") {
+        if let Some(id) = scope.strip_prefix("docker-") {
+            (Runtime::Docker, id)
+        } else {
+            // `libpod-conmon-<id>.scope` holds the monitor, outside of the
+            // container
+            (Runtime::Podman, scope.strip_prefix("libpod-")?)
+        }
+    } else if let Some(id) = component.strip_prefix("libpod-") {
+        (Runtime::Podman, id)
+    } else if parent == "docker" {
+        (Runtime::Docker, component)
+    } else {
+        return None;
+    };
+    is_container_id(id).then(|| Container {
+        runtime,
+        id: id.to_string(),
+        name: None,
+    })
+}
+
+fn is_container_id(id: &str) -> bool {
+    id.len() == 64 && id.bytes().all(|byte| byte.is_ascii_hexdigit())
+}
+
+/// The PID namespace of the process whose `/proc/<pid>` directory is `dir`,
+/// as the inode number in its `ns/pid` link (`pid:[4026531836]`)
+pub fn pid_namespace(dir: &Path) -> Option<u64> {
+    let link = fs::read_link(dir.join("ns/pid")).ok()?;
+    link.to_str()?
+        .strip_prefix("pid:[")?
+        .strip_suffix(']')?
+        .parse()
+        .ok()
+}
+
+/// Something that can stop containers
+pub trait ContainerManager: Send + Sync {
+    /// Stop `container`, blocking until it has; not to be called on the UI
+    /// thread.
+    fn stop(&self, container: &Container) -> ProcessResult<()>;
+}
+
+/// The API sockets of the container runtimes, tried in order
+#[derive(Debug, Clone, Default)]
+pub struct RuntimeSockets {
+    docker: Vec<PathBuf>,
+    podman: Vec<PathBuf>,
+}
+
+impl RuntimeSockets {
+    /// The sockets the runtimes listen on by default: Docker's, and this
+    /// user's rootless Podman before the system's.
+    pub fn new() -> Self {
+        let mut podman = Vec::new();
+        if let Some(runtime_dir) = std::env::var_os("XDG_RUNTIME_DIR") {
+            podman.push(PathBuf::from(runtime_dir).join("podman/podman.sock"));
+        }
+        podman.push(PathBuf::from("/run/podman/podman.sock"));
+        Self::with_paths(vec![PathBuf::from("/run/docker.sock")], podman)
+    }
+
+    /// Talk to runtimes listening on other sockets, or stand-ins for them.
+    pub fn with_paths(docker: Vec<PathBuf>, podman: Vec<PathBuf>) -> Self {
+        Self { docker, podman }
+    }
+
+    fn sockets(&self, runtime: Runtime) -> &[PathBuf] {
+        match runtime {
+            Runtime::Docker => &self.docker,
+            Runtime::Podman => &self.podman,
+        }
+    }
+
+    /// Names of the running containers of `runtime`, by ID. Sockets that
+    /// cannot be reached are skipped.
+    pub fn names(&self, runtime: Runtime) -> HashMap<String, String> {
+        self.sockets(runtime)
+            .iter()
+            .filter_map(|socket| request(socket, "GET", "/containers/json", LIST_TIMEOUT).ok())
+            .filter(|(status, _)| *status == 200)
+            .filter_map(|(_, body)| serde_json::from_str::<Vec<ApiContainer>>(&body).ok())
+            .flatten()
+            .filter_map(|container| {
+                let name = container.names.first()?.trim_start_matches('/').to_string();
+                Some((container.id, name))
+            })
+            .collect()
+    }
+}
+
+impl ContainerManager for RuntimeSockets {
+    /// Stop a container as `docker stop` does, through the first socket
+    /// whose runtime knows it. Returns once the container has exited.
+    fn stop(&self, container: &Container) -> ProcessResult<()> {
+        let path = format!("/containers/{}/stop?t={STOP_TIMEOUT}", container.id);
+        // Stopping waits for the container to exit
+        let timeout = Duration::from_secs(STOP_TIMEOUT + 5);
+        let mut result = Err(ProcessError::NotFound);
+        for socket in self.sockets(container.runtime) {
+            result = match request(socket, "POST", &path, timeout) {
+                // 304: it was not running anymore
+                Ok((204 | 304, _)) => return Ok(()),
+                Ok((404, _)) => Err(ProcessError::NotFound),
+                Ok((401 | 403, _)) => return Err(ProcessError::PermissionDenied),
+                Ok((status, body)) => {
+                    let message = serde_json::from_str::<ApiError>(&body)
+                        .map_or_else(|_| format!("HTTP {status}"), |error| error.message);
+                    return Err(ProcessError::Unknown(message));
+                }
+                // The socket of a runtime that is not running
+                Err(error)
+                    if matches!(
+                        error.kind(),
+                        ErrorKind::NotFound | ErrorKind::ConnectionRefused
+                    ) =>
+                {
+                    continue
+                }
+                Err(error) if error.kind() == ErrorKind::PermissionDenied => {
+                    Err(ProcessError::PermissionDenied)
+                }
+                Err(error) => Err(ProcessError::Unknown(error.to_string())),
+            };
+        }
+        result
+    }
+}
+
+/// What `GET /containers/json` lists, as far as needed here
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ApiContainer {
+    id: String,
+    #[serde(default)]
+    names: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct ApiError {
+    message: String,
+}
+
+/// Send a request without a body to the API listening on `socket`, and
+/// return the status and body of the response, waiting at most `timeout`
+/// for it. HTTP/1.0 keeps the response plain: never chunked, and the
+/// connection closes after it.
+pub fn request(
+    socket: &Path,
+    method: &str,
+    path: &str,
+    timeout: Duration,
+) -> io::Result<(u16, String)> {
+    let mut stream = UnixStream::connect(socket)?;
+    stream.set_read_timeout(Some(timeout))?;
+    write!(
+        stream,
+        "{method} {path} HTTP/1.0\r\nHost: localhost\r\nContent-Length: 0\r\n\r\n"
+    )?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response)?;
+    let invalid = || io::Error::new(ErrorKind::InvalidData, "invalid HTTP response");
+    let (head, body) = response.split_once("\r\n\r\n").ok_or_else(invalid)?;
+    let status = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .ok_or_else(invalid)?;
+    Ok((status, body.to_string()))
+}
@@ -0,0 +1,1518 @@
+// SPDX-License-Identifier: MIT
+
+//! UI-agnostic state shared by the applet popup and the standalone window.
+//!
+//! [`ProcessController`] owns the process list, search, selection and the
+//! kill confirmation flow. Frontends wrap its [`Message`] in their own message
+//! type, forward it to [`ProcessController::update`] and only take care of
+//! layout, so the behaviour can be tested without a compositor.
+//!
+//! Process data arrives as [`SnapshotDiff`]s from the background
+//! [`sampler`](crate::sampler); the controller never scans /proc itself.
+//! Signals go through a [`SignalSender`], priority changes through a
+//! [`Scheduler`], systemd units through a [`UnitManager`], app cgroups
+//! through a [`Freezer`] and a [`Limiter`] and containers through a
+//! [`ContainerManager`], which tests replace with fakes. Saved searches are
+//! written to a [`SearchStore`].
+//!
+//! Calls that may take a while, such as asking systemd to stop a unit, are
+//! handed to the frontend as [`Job`]s to run on its executor. Each ends with
+//! a message that is passed back to [`ProcessController::update`].
+
+use crate::affinity::CpuMask;
+use crate::cgroup::{self, CgroupFreezer, CgroupLimiter, Freezer, Limiter, Limits};
+use crate::container::{self, Container, ContainerManager, RuntimeSockets};
+use crate::files::{Holder, OpenFile};
+use crate::fl;
+use crate::package::Package;
+use crate::priority::{IoPriority, Scheduler, SystemScheduler};
+use crate::process::{
+    self, filter_processes, ProcessError, ProcessInfo, ProcessKey, ProcessResult, SignalSender,
+    SortBy, SystemSignals, ThreadInfo,
+};
+use crate::query::{Query, QueryError, SearchStore};
+use crate::sampler::{SamplerHandle, SnapshotDiff};
+use crate::sockets::SocketInfo;
+use crate::systemd::{self, SystemdUnits, Unit, UnitAction, UnitManager};
+use futures_util::future::BoxFuture;
+use nix::sys::signal::Signal;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Number of processes listed while "show all" is off
+pub const TOP_PROCESSES: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmationMode {
+    Kill,
+    ForceKill,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Toast {
+    pub message: String,
+    pub is_error: bool,
+}
+
+/// Processes to signal together once confirmed
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupKill {
+    /// What the processes have in common
+    pub target: GroupTarget,
+    pub signal: Signal,
+    /// The processes as sampled when asked. Those that exit are dropped, so
+    /// a PID taken over by another process meanwhile is left alone.
+    pub processes: Vec<ProcessKey>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GroupTarget {
+    /// The processes holding a file or mount point
+    Holders(PathBuf),
+    /// The processes of one running copy of a packaged app
+    Package(Package),
+}
+
+/// A systemd unit or container to act on as a whole once confirmed, as that
+/// takes every process in it along
+#[derive(Debug, Clone, PartialEq)]
+pub enum PendingAction {
+    Unit { unit: Unit, action: UnitAction },
+    StopContainer(Container),
+}
+
+/// Processes found holding a file or mount point
+#[derive(Debug, Clone, PartialEq)]
+pub struct HolderLookup {
+    pub path: PathBuf,
+    /// `None` until the sampler answers
+    pub holders: Option<Vec<Holder>>,
+}
+
+/// Work running in the background, ending with a [`Message`] that tells
+/// how it went
+pub type Job = BoxFuture<'static, Message>;
+
+/// Messages understood by the controller
+#[derive(Debug, Clone)]
+pub enum Message {
+    /// The sampling thread started
+    SamplerReady(SamplerHandle),
+    /// Changes since the previous sample
+    Snapshot(SnapshotDiff),
+    /// Threads of the watched process
+    Threads {
+        pid: u32,
+        threads: Vec<ThreadInfo>,
+    },
+    /// Sockets of the watched process
+    Sockets {
+        pid: u32,
+        sockets: Vec<SocketInfo>,
+    },
+    /// Files held open by the watched process
+    OpenFiles {
+        pid: u32,
+        files: Vec<OpenFile>,
+    },
+    /// Sample the threads, sockets and open files of a process, or stop
+    WatchThreads(Option<u32>),
+    SortThreadsBy(SortBy),
+    /// Send a signal to one thread
+    SignalThread {
+        pid: u32,
+        tid: u32,
+        signal: Signal,
+    },
+    /// Stop, restart or kill the systemd unit of a process, or ask to
+    /// signal the process itself when it has none
+    UnitAction {
+        pid: u32,
+        action: UnitAction,
+    },
+    /// systemd answered a [`Message::UnitAction`]
+    UnitActionDone {
+        unit: String,
+        action: UnitAction,
+        result: ProcessResult<()>,
+    },
+    /// Freeze or thaw every process of the app a process belongs to
+    SetFrozen {
+        pid: u32,
+        frozen: bool,
+    },
+    /// Limit the memory and CPU time of the app or service a process
+    /// belongs to
+    SetLimits {
+        pid: u32,
+        limits: Limits,
+    },
+    /// systemd answered a [`Message::SetLimits`]; `usage` is the memory
+    /// already used beyond the new cap
+    LimitsDone {
+        target: String,
+        usage: Option<u64>,
+        result: ProcessResult<()>,
+    },
+    /// Kill every process of the running Flatpak, Snap or AppImage a process
+    /// belongs to
+    KillPackage(u32),
+    /// Stop the Docker or Podman container a process runs in
+    StopContainer(u32),
+    /// The runtime answered a [`Message::StopContainer`]
+    StopContainerDone {
+        container: String,
+        result: ProcessResult<()>,
+    },
+    /// Look for the processes holding a file or mount point
+    FindHolders(PathBuf),
+    /// Processes found holding `path`
+    Holders {
+        path: PathBuf,
+        holders: Vec<Holder>,
+    },
+    /// Ask to signal every process found holding the path looked for
+    KillHolders {
+        force: bool,
+    },
+    /// Signal the processes of the pending [`GroupKill`]
+    ConfirmGroupKill,
+    /// Carry out the [`PendingAction`]
+    ConfirmPendingAction,
+    CloseHolders,
+    RefreshProcesses,
+    KillProcess(u32),
+    ForceKillProcess(u32),
+    ToggleShowAll(bool),
+    SortBy(SortBy),
+    UpdateSearch(String),
+    SelectProcess(Option<u32>),
+    ConfirmKill,
+    ConfirmForceKill,
+    CancelConfirmation,
+    /// Renice a process
+    SetNice {
+        pid: u32,
+        nice: i32,
+    },
+    SetIoPriority {
+        pid: u32,
+        priority: IoPriority,
+    },
+    /// Pin a process, or all of its threads, to some cores
+    SetAffinity {
+        pid: u32,
+        mask: CpuMask,
+        all_threads: bool,
+    },
+    /// Save the current search text
+    SaveSearch,
+    RemoveSavedSearch(String),
+    ShowToast(String, bool),
+    ClearToast,
+}
+
+pub struct ProcessController {
+    /// Delivers the kill signals
+    signals: Arc<dyn SignalSender>,
+    /// Changes CPU and I/O priorities
+    scheduler: Arc<dyn Scheduler>,
+    /// Stops, restarts and kills systemd units
+    units: Arc<dyn UnitManager>,
+    /// Freezes and thaws app cgroups
+    freezer: Arc<dyn Freezer>,
+    /// Limits app cgroups
+    limiter: Arc<dyn Limiter>,
+    /// Stops containers
+    containers: Arc<dyn ContainerManager>,
+    /// Keeps the saved searches, which are otherwise forgotten on exit
+    search_store: Option<Arc<dyn SearchStore>>,
+    /// Connection to the sampling thread
+    sampler: Option<SamplerHandle>,
+    /// Every running process, as of the latest sample
+    table: HashMap<u32, ProcessInfo>,
+    /// Whether at least one sample has arrived
+    has_snapshot: bool,
+    /// Sorted (and possibly truncated) list of processes
+    processes: Vec<ProcessInfo>,
+    /// Show all processes or only the top consumers
+    show_all: bool,
+    /// Sort order
+    sort_by: SortBy,
+    /// Show the disk I/O columns, which are then sampled
+    show_disk_io: bool,
+    /// Text of the search box
+    search_query: String,
+    /// Last query that parsed, still applied while the text has an error
+    query: Query,
+    query_error: Option<QueryError>,
+    /// Searches saved from the search box
+    saved_searches: Vec<String>,
+    /// Selected process, kept up to date with every sample
+    selected_process: Option<ProcessInfo>,
+    /// Process whose threads are sampled
+    watched_threads: Option<u32>,
+    /// Its threads, as of the latest sample
+    threads: Vec<ThreadInfo>,
+    /// Its sockets, as of the latest sample
+    sockets: Vec<SocketInfo>,
+    /// Its open files, as of the latest sample
+    open_files: Vec<OpenFile>,
+    thread_sort_by: SortBy,
+    /// Last lookup of who holds a file
+    holder_lookup: Option<HolderLookup>,
+    /// Confirmation dialog state
+    confirmation_mode: Option<ConfirmationMode>,
+    /// Processes to signal together, awaiting confirmation
+    group_kill: Option<GroupKill>,
+    /// A unit or container to act on, awaiting confirmation
+    pending_action: Option<PendingAction>,
+    /// Toast notification state
+    toast: Option<Toast>,
+    /// Started background work the frontend has not taken yet
+    jobs: Vec<Job>,
+}
+
+impl Default for ProcessController {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProcessController {
+    pub fn new() -> Self {
+        Self::with_signals(Arc::new(SystemSignals))
+    }
+
+    /// Create a controller that sends signals through `signals`.
+    pub fn with_signals(signals: Arc<dyn SignalSender>) -> Self {
+        Self {
+            signals,
+            scheduler: Arc::new(SystemScheduler),
+            units: Arc::new(SystemdUnits),
+            freezer: Arc::new(CgroupFreezer::new()),
+            limiter: Arc::new(CgroupLimiter::new()),
+            containers: Arc::new(RuntimeSockets::new()),
+            search_store: None,
+            sampler: None,
+            table: HashMap::new(),
+            has_snapshot: false,
+            processes: Vec::new(),
+            show_all: false,
+            sort_by: SortBy::default(),
+            show_disk_io: false,
+            search_query: String::new(),
+            query: Query::default(),
+            query_error: None,
+            saved_searches: Vec::new(),
+            selected_process: None,
+            watched_threads: None,
+            threads: Vec::new(),
+            sockets: Vec::new(),
+            open_files: Vec::new(),
+            thread_sort_by: SortBy::default(),
+            holder_lookup: None,
+            confirmation_mode: None,
+            group_kill: None,
+            pending_action: None,
+            toast: None,
+            jobs: Vec::new(),
+        }
+    }
+
+    /// Change priorities through `scheduler` instead of the system.
+    pub fn with_scheduler(mut self, scheduler: Arc<dyn Scheduler>) -> Self {
+        self.scheduler = scheduler;
+        self
+    }
+
+    /// Act on systemd units through `units` instead of the system.
+    pub fn with_units(mut self, units: Arc<dyn UnitManager>) -> Self {
+        self.units = units;
+        self
+    }
+
+    /// Freeze app cgroups through `freezer` instead of the system.
+    pub fn with_freezer(mut self, freezer: Arc<dyn Freezer>) -> Self {
+        self.freezer = freezer;
+        self
+    }
+
+    /// Limit app cgroups through `limiter` instead of the system.
+    pub fn with_limiter(mut self, limiter: Arc<dyn Limiter>) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    /// Stop containers through `containers` instead of their runtimes.
+    pub fn with_containers(mut self, containers: Arc<dyn ContainerManager>) -> Self {
+        self.containers = containers;
+        self
+    }
+
+    /// Write saved searches to `store`.
+    pub fn with_search_store(mut self, store: Arc<dyn SearchStore>) -> Self {
+        self.search_store = Some(store);
+        self
+    }
+
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::SamplerReady(sampler) => {
+                // A new sampler starts over; rows of a previous one that
+                // exited meanwhile would never be removed
+                self.table.clear();
+                self.has_snapshot = false;
+                self.refresh_processes();
+                if self.needs_disk_io() {
+                    sampler.sample_disk_io(true);
+                }
+                if self.watched_threads.is_some() {
+                    sampler.watch_threads(self.watched_threads);
+                }
+                if self.query.needs_ports() {
+                    sampler.sample_ports(true);
+                }
+                if !self.query.held_paths().is_empty() {
+                    sampler.sample_held_paths(self.query.held_paths());
+                }
+                if let Some(lookup) = self.holder_lookup.as_ref().filter(|l| l.holders.is_none()) {
+                    sampler.find_holders(lookup.path.clone());
+                }
+                self.sampler = Some(sampler);
+            }
+            Message::Threads { pid, mut threads } => {
+                if self.watched_threads == Some(pid) {
+                    process::sort_threads(&mut threads, self.thread_sort_by);
+                    self.threads = threads;
+                }
+            }
+            Message::Sockets { pid, sockets } => {
+                if self.watched_threads == Some(pid) {
+                    self.sockets = sockets;
+                }
+            }
+            Message::OpenFiles { pid, files } => {
+                if self.watched_threads == Some(pid) {
+                    self.open_files = files;
+                }
+            }
+            Message::WatchThreads(pid) => {
+                if self.watched_threads != pid {
+                    self.watched_threads = pid;
+                    self.threads.clear();
+                    self.sockets.clear();
+                    self.open_files.clear();
+                    if let Some(sampler) = &self.sampler {
+                        sampler.watch_threads(pid);
+                    }
+                }
+            }
+            Message::SortThreadsBy(sort_by) => {
+                self.thread_sort_by = sort_by;
+                process::sort_threads(&mut self.threads, sort_by);
+            }
+            Message::SignalThread { pid, tid, signal } => {
+                self.signal_thread(pid, tid, signal);
+            }
+            Message::UnitAction { pid, action } => {
+                self.unit_action(pid, action);
+            }
+            Message::UnitActionDone {
+                unit,
+                action,
+                result,
+            } => {
+                self.unit_action_done(unit, action, result);
+            }
+            Message::SetFrozen { pid, frozen } => {
+                self.set_frozen(pid, frozen);
+            }
+            Message::SetLimits { pid, limits } => {
+                self.set_limits(pid, limits);
+            }
+            Message::LimitsDone {
+                target,
+                usage,
+                result,
+            } => {
+                self.limits_done(target, usage, result);
+            }
+            Message::KillPackage(pid) => {
+                self.kill_package(pid);
+            }
+            Message::StopContainer(pid) => {
+                self.stop_container(pid);
+            }
+            Message::StopContainerDone { container, result } => {
+                self.stop_container_done(container, result);
+            }
+            Message::FindHolders(path) => {
+                if let Some(sampler) = &self.sampler {
+                    sampler.find_holders(path.clone());
+                }
+                self.holder_lookup = Some(HolderLookup {
+                    path,
+                    holders: None,
+                });
+            }
+            Message::Holders { path, holders } => {
+                // Answers to an earlier lookup are dropped
+                if let Some(lookup) = self.holder_lookup.as_mut().filter(|l| l.path == path) {
+                    lookup.holders = Some(holders);
+                }
+            }
+            Message::KillHolders { force } => {
+                self.kill_holders(force);
+            }
+            Message::ConfirmGroupKill => {
+                self.execute_group_kill();
+            }
+            Message::ConfirmPendingAction => {
+                self.execute_pending_action();
+            }
+            Message::CloseHolders => {
+                self.holder_lookup = None;
+            }
+            Message::Snapshot(diff) => {
+                diff.apply_to(&mut self.table);
+                self.has_snapshot = true;
+                self.refresh_processes();
+                self.refresh_selection();
+                self.refresh_group_kill();
+            }
+            Message::RefreshProcesses => {
+                self.request_sample();
+                self.refresh_processes();
+            }
+            Message::KillProcess(pid) => {
+                self.handle_kill_process(pid, false);
+            }
+            Message::ForceKillProcess(pid) => {
+                self.handle_kill_process(pid, true);
+            }
+            Message::ConfirmKill => {
+                if let Some(process) = self.selected_process.clone() {
+                    self.execute_kill(&process, false);
+                }
+                self.confirmation_mode = None;
+                self.selected_process = None;
+            }
+            Message::ConfirmForceKill => {
+                if let Some(process) = self.selected_process.clone() {
+                    self.execute_kill(&process, true);
+                }
+                self.confirmation_mode = None;
+                self.selected_process = None;
+            }
+            Message::CancelConfirmation => {
+                self.confirmation_mode = None;
+                self.selected_process = None;
+                self.group_kill = None;
+                self.pending_action = None;
+            }
+            Message::ToggleShowAll(show_all) => {
+                self.show_all = show_all;
+                self.refresh_processes();
+            }
+            Message::SortBy(sort_by) => {
+                let needed = self.needs_disk_io();
+                self.sort_by = sort_by;
+                self.disk_io_changed(needed);
+                self.refresh_processes();
+            }
+            Message::UpdateSearch(text) => {
+                match Query::parse(&text) {
+                    Ok(query) => {
+                        // Ports and held files are only looked up while a
+                        // search needs them
+                        if let Some(sampler) = &self.sampler {
+                            let ports_changed = query.needs_ports() != self.query.needs_ports();
+                            let paths_changed = query.held_paths() != self.query.held_paths();
+                            if ports_changed {
+                                sampler.sample_ports(query.needs_ports());
+                            }
+                            if paths_changed {
+                                sampler.sample_held_paths(query.held_paths());
+                            }
+                            if ports_changed || paths_changed {
+                                sampler.refresh_now();
+                            }
+                        }
+                        self.query = query;
+                        self.query_error = None;
+                    }
+                    Err(error) => self.query_error = Some(error),
+                }
+                self.search_query = text;
+            }
+            Message::SelectProcess(pid) => {
+                // Changing the selection abandons a pending confirmation
+                self.confirmation_mode = None;
+                self.selected_process = pid.and_then(|pid| self.table.get(&pid).cloned());
+            }
+            Message::SetNice { pid, nice } => {
+                self.change_scheduling(pid, false, |scheduler| scheduler.set_nice(pid, nice));
+            }
+            Message::SetIoPriority { pid, priority } => {
+                self.change_scheduling(pid, false, |scheduler| {
+                    scheduler.set_io_priority(pid, priority)
+                });
+            }
+            Message::SetAffinity {
+                pid,
+                mask,
+                all_threads,
+            } => {
+                self.change_scheduling(pid, true, |scheduler| {
+                    scheduler.set_affinity(pid, &mask, all_threads)
+                });
+            }
+            Message::SaveSearch => {
+                let query = self.search_query.trim();
+                if !query.is_empty() && !self.saved_searches.iter().any(|saved| saved == query) {
+                    let mut saved_searches = self.saved_searches.clone();
+                    saved_searches.push(query.to_string());
+                    self.store_searches(saved_searches);
+                }
+            }
+            Message::RemoveSavedSearch(query) => {
+                let mut saved_searches = self.saved_searches.clone();
+                saved_searches.retain(|saved| *saved != query);
+                self.store_searches(saved_searches);
+            }
+            Message::ShowToast(message, is_error) => {
+                self.toast = Some(Toast { message, is_error });
+            }
+            Message::ClearToast => {
+                self.toast = None;
+            }
+        }
+    }
+
+    /// Rebuild the sorted list from the latest sample.
+    pub fn refresh_processes(&mut self) {
+        let mut processes: Vec<ProcessInfo> = self.table.values().cloned().collect();
+        process::sort_processes(&mut processes, self.sort_by);
+        if !self.show_all {
+            processes.truncate(TOP_PROCESSES);
+        }
+        self.processes = processes;
+    }
+
+    /// Bring the selected process up to date, or drop it if it exited.
+    /// A pending confirmation for a process that exited is cancelled.
+    fn refresh_selection(&mut self) {
+        let Some(key) = self.selected_process.as_ref().map(ProcessInfo::key) else {
+            return;
+        };
+
+        match self.table.get(&key.pid).filter(|p| p.key() == key) {
+            Some(process) => self.selected_process = Some(process.clone()),
+            None => {
+                let exited = self.selected_process.take();
+                if let Some(process) = exited.filter(|_| self.confirmation_mode.is_some()) {
+                    self.confirmation_mode = None;
+                    self.toast = Some(Toast {
+                        message: fl!("notification-target-exited", name = process.name),
+                        is_error: false,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Drop the processes of a pending group kill that exited, and the
+    /// group kill once none are left.
+    fn refresh_group_kill(&mut self) {
+        let Some(group) = self.group_kill.as_mut() else {
+            return;
+        };
+        group
+            .processes
+            .retain(|key| self.table.get(&key.pid).is_some_and(|p| p.key() == *key));
+        if group.processes.is_empty() {
+            self.group_kill = None;
+        }
+    }
+
+    /// Ask the sampling thread for a fresh sample.
+    pub fn request_sample(&self) {
+        if let Some(sampler) = &self.sampler {
+            sampler.refresh_now();
+        }
+    }
+
+    /// Threads of the watched process, sorted by [`Self::thread_sort_by`]
+    pub fn threads(&self) -> &[ThreadInfo] {
+        &self.threads
+    }
+
+    pub fn thread_sort_by(&self) -> SortBy {
+        self.thread_sort_by
+    }
+
+    /// Sockets of the watched process
+    pub fn sockets(&self) -> &[SocketInfo] {
+        &self.sockets
+    }
+
+    /// Files held open by the watched process
+    pub fn open_files(&self) -> &[OpenFile] {
+        &self.open_files
+    }
+
+    /// The last lookup of who holds a file, until it is closed
+    pub fn holder_lookup(&self) -> Option<&HolderLookup> {
+        self.holder_lookup.as_ref()
+    }
+
+    /// A process from the latest sample, listed or not
+    pub fn sampled_process(&self, pid: u32) -> Option<&ProcessInfo> {
+        self.table.get(&pid)
+    }
+
+    /// Cores a process may currently run on
+    pub fn affinity(&self, pid: u32) -> process::ProcessResult<CpuMask> {
+        self.scheduler.affinity(pid)
+    }
+
+    /// Whether process data has arrived yet
+    pub fn has_snapshot(&self) -> bool {
+        self.has_snapshot
+    }
+
+    /// Make sure `pid` is listed, turning on "show all" if needed.
+    /// Returns false if the process does not exist.
+    pub fn reveal_process(&mut self, pid: u32) -> bool {
+        if self.process(pid).is_none() && !self.show_all {
+            self.show_all = true;
+            self.refresh_processes();
+        }
+        self.process(pid).is_some()
+    }
+
+    pub fn processes(&self) -> &[ProcessInfo] {
+        &self.processes
+    }
+
+    pub fn process(&self, pid: u32) -> Option<&ProcessInfo> {
+        self.processes.iter().find(|p| p.pid == pid)
+    }
+
+    pub fn filtered_processes(&self) -> Vec<&ProcessInfo> {
+        filter_processes(&self.processes, &self.query)
+    }
+
+    pub fn show_all(&self) -> bool {
+        self.show_all
+    }
+
+    pub fn sort_by(&self) -> SortBy {
+        self.sort_by
+    }
+
+    pub fn show_disk_io(&self) -> bool {
+        self.show_disk_io
+    }
+
+    /// Show the disk I/O columns, as loaded from, or changed in, the settings.
+    pub fn set_show_disk_io(&mut self, show: bool) {
+        let needed = self.needs_disk_io();
+        self.show_disk_io = show;
+        self.disk_io_changed(needed);
+    }
+
+    /// Whether disk I/O is sampled: for its columns, or to sort by it with
+    /// the columns hidden
+    fn needs_disk_io(&self) -> bool {
+        self.show_disk_io || self.sort_by.needs_disk_io()
+    }
+
+    /// Start or stop sampling disk I/O when that is needed now and was not
+    /// before, or the other way round.
+    fn disk_io_changed(&self, needed: bool) {
+        if let Some(sampler) = self.sampler.as_ref().filter(|_| self.needs_disk_io() != needed) {
+            sampler.sample_disk_io(!needed);
+        }
+    }
+
+    pub fn search_query(&self) -> &str {
+        &self.search_query
+    }
+
+    /// Why the search text is not a valid query, for display under the input
+    pub fn search_error(&self) -> Option<String> {
+        let error = self.query_error.as_ref()?;
+        Some(match error {
+            QueryError::UnknownField(field) => {
+                fl!("query-error-unknown-field", field = field.clone())
+            }
+            QueryError::MissingValue(field) => {
+                fl!("query-error-missing-value", field = field.clone())
+            }
+            QueryError::NotComparable(field) => {
+                fl!("query-error-not-comparable", field = field.clone())
+            }
+            QueryError::InvalidNumber(value) => {
+                fl!("query-error-invalid-number", value = value.clone())
+            }
+            QueryError::InvalidSize(value) => {
+                fl!("query-error-invalid-size", value = value.clone())
+            }
+            QueryError::InvalidRegex(pattern, error) => fl!(
+                "query-error-invalid-regex",
+                pattern = pattern.clone(),
+                error = error.clone()
+            ),
+            QueryError::UnknownUser(user) => {
+                fl!("query-error-unknown-user", user = user.clone())
+            }
+            QueryError::UnknownState(state) => {
+                fl!("query-error-unknown-state", state = state.clone())
+            }
+            QueryError::InvalidPath(path) => {
+                fl!("query-error-invalid-path", path = path.clone())
+            }
+            QueryError::UnclosedQuote => fl!("query-error-unclosed-quote"),
+        })
+    }
+
+    /// Split `text` into pieces, each flagged with whether the current query
+    /// matched it, so the frontends can highlight matches.
+    pub fn search_highlights<'a>(&self, text: &'a str) -> Vec<(&'a str, bool)> {
+        let mut pieces = Vec::new();
+        let mut end = 0;
+        for range in self.query.highlights(text) {
+            if range.start > end {
+                pieces.push((&text[end..range.start], false));
+            }
+            pieces.push((&text[range.clone()], true));
+            end = range.end;
+        }
+        if end < text.len() {
+            pieces.push((&text[end..], false));
+        }
+        pieces
+    }
+
+    /// Whether the search text is a valid query worth saving
+    pub fn can_save_search(&self) -> bool {
+        self.query_error.is_none() && !self.query.is_empty()
+    }
+
+    pub fn saved_searches(&self) -> &[String] {
+        &self.saved_searches
+    }
+
+    /// Take the saved searches as loaded from, or changed in, the settings.
+    pub fn set_saved_searches(&mut self, searches: Vec<String>) {
+        self.saved_searches = searches;
+    }
+
+    fn store_searches(&mut self, searches: Vec<String>) {
+        if let Some(Err(why)) = self.search_store.as_ref().map(|store| store.save(&searches)) {
+            eprintln!("failed to save searches: {why}");
+            return;
+        }
+        self.saved_searches = searches;
+    }
+
+    pub fn selected_process(&self) -> Option<&ProcessInfo> {
+        self.selected_process.as_ref()
+    }
+
+    pub fn selected_key(&self) -> Option<ProcessKey> {
+        self.selected_process.as_ref().map(ProcessInfo::key)
+    }
+
+    pub fn is_selected(&self, process: &ProcessInfo) -> bool {
+        self.selected_key() == Some(process.key())
+    }
+
+    /// The process awaiting confirmation, with its latest values, and how it
+    /// is going to be killed
+    pub fn confirmation(&self) -> Option<(&ProcessInfo, ConfirmationMode)> {
+        self.selected_process.as_ref().zip(self.confirmation_mode)
+    }
+
+    /// The processes awaiting confirmation to be signalled together
+    pub fn group_kill(&self) -> Option<&GroupKill> {
+        self.group_kill.as_ref()
+    }
+
+    /// The unit or container awaiting confirmation to be acted on
+    pub fn pending_action(&self) -> Option<&PendingAction> {
+        self.pending_action.as_ref()
+    }
+
+    /// Whether the list should keep its order, so the row being acted on
+    /// does not move
+    pub fn is_confirming(&self) -> bool {
+        self.confirmation_mode.is_some()
+            || self.group_kill.is_some()
+            || self.pending_action.is_some()
+    }
+
+    pub fn toast(&self) -> Option<&Toast> {
+        self.toast.as_ref()
+    }
+
+    pub fn can_kill(&self, process: &ProcessInfo) -> bool {
+        process::can_kill_process(process).is_ok()
+    }
+
+    fn show_error(&mut self, message: String) {
+        self.toast = Some(Toast {
+            message,
+            is_error: true,
+        });
+    }
+
+    fn handle_kill_process(&mut self, pid: u32, force: bool) {
+        // Find the process (requests from D-Bus may target one outside the list)
+        let process = match self.table.get(&pid) {
+            Some(p) => p.clone(),
+            None => {
+                self.show_error(fl!("error-process-not-found"));
+                return;
+            }
+        };
+
+        // Check permissions before showing confirmation
+        match process::can_kill_process(&process) {
+            Err(ProcessError::PermissionDenied) => {
+                self.show_error(fl!("notification-permission-denied"));
+                return;
+            }
+            Err(ProcessError::Protected(name)) => {
+                self.show_error(fl!("notification-protected", name = name));
+                return;
+            }
+            Err(e) => {
+                self.show_error(fl!("error-unknown-error", error = e.to_string()));
+                return;
+            }
+            Ok(()) => {}
+        }
+
+        // Show confirmation dialog
+        self.group_kill = None;
+        self.pending_action = None;
+        self.selected_process = Some(process);
+        self.confirmation_mode = Some(if force {
+            ConfirmationMode::ForceKill
+        } else {
+            ConfirmationMode::Kill
+        });
+    }
+
+    fn signal_thread(&mut self, pid: u32, tid: u32, signal: Signal) {
+        let Some(process) = self.table.get(&pid).cloned() else {
+            self.show_error(fl!("error-process-not-found"));
+            return;
+        };
+        if let Err(ProcessError::Protected(name)) = process::can_kill_process(&process) {
+            self.show_error(fl!("notification-protected", name = name));
+            return;
+        }
+
+        match self.signals.send_thread_signal(pid, tid, signal) {
+            Ok(()) => {
+                self.toast = Some(Toast {
+                    message: fl!(
+                        "notification-thread-signal-sent",
+                        signal = signal.as_str(),
+                        tid = tid,
+                        name = process.name
+                    ),
+                    is_error: false,
+                });
+            }
+            Err(ProcessError::PermissionDenied) => {
+                self.show_error(fl!("notification-permission-denied"));
+            }
+            Err(ProcessError::NotFound) => self.show_error(fl!("error-thread-not-found")),
+            Err(e) => self.show_error(fl!("error-unknown-error", error = e.to_string())),
+        }
+
+        self.request_sample();
+    }
+
+    /// Background work started since the last call, to be run by the
+    /// frontend, which passes the message each job ends with back.
+    pub fn take_jobs(&mut self) -> Vec<Job> {
+        std::mem::take(&mut self.jobs)
+    }
+
+    /// Ask to act on the unit of a process. Processes outside of a unit, or
+    /// in one that cannot be stopped on its own such as a login session, are
+    /// signalled instead. Either is done once confirmed.
+    fn unit_action(&mut self, pid: u32, action: UnitAction) {
+        let Some(process) = self.table.get(&pid).cloned() else {
+            self.show_error(fl!("error-process-not-found"));
+            return;
+        };
+        if let Err(ProcessError::Protected(name)) = process::can_kill_process(&process) {
+            self.show_error(fl!("notification-protected", name = name));
+            return;
+        }
+
+        let Some(unit) = process.unit.as_ref().filter(|unit| unit.is_manageable()) else {
+            match action {
+                UnitAction::Stop => self.handle_kill_process(pid, false),
+                UnitAction::Kill => self.handle_kill_process(pid, true),
+                UnitAction::Restart => {
+                    self.show_error(fl!("error-unit-restart-no-unit", name = process.name));
+                }
+            }
+            return;
+        };
+
+        // Stopping it would stop this process midway
+        if self.own_process().unit.as_ref() == Some(unit) {
+            self.show_error(fl!("error-unit-self", unit = unit.name.clone()));
+            return;
+        }
+
+        self.confirmation_mode = None;
+        self.selected_process = None;
+        self.group_kill = None;
+        self.pending_action = Some(PendingAction::Unit {
+            unit: unit.clone(),
+            action,
+        });
+    }
+
+    /// Carry out the confirmed action in the background.
+    fn execute_pending_action(&mut self) {
+        match self.pending_action.take() {
+            Some(PendingAction::Unit { unit, action }) => {
+                let run = self.units.run(&unit, action);
+                self.jobs.push(Box::pin(async move {
+                    Message::UnitActionDone {
+                        unit: unit.name,
+                        action,
+                        result: run.await,
+                    }
+                }));
+            }
+            Some(PendingAction::StopContainer(container)) => {
+                let name = container.label().to_string();
+                let containers = self.containers.clone();
+                self.jobs.push(Box::pin(async move {
+                    // Waits for the container to exit, up to its stop timeout
+                    let result = tokio::task::spawn_blocking(move || containers.stop(&container))
+                        .await
+                        .unwrap_or_else(|e| Err(ProcessError::Unknown(e.to_string())));
+                    Message::StopContainerDone {
+                        container: name,
+                        result,
+                    }
+                }));
+            }
+            None => {}
+        }
+    }
+
+    /// Tell how acting on a unit went.
+    fn unit_action_done(&mut self, name: String, action: UnitAction, result: ProcessResult<()>) {
+        match result {
+            Ok(()) => {
+                self.toast = Some(Toast {
+                    message: match action {
+                        UnitAction::Stop => fl!("notification-unit-stopped", unit = name),
+                        UnitAction::Restart => fl!("notification-unit-restarted", unit = name),
+                        UnitAction::Kill => fl!("notification-unit-killed", unit = name),
+                    },
+                    is_error: false,
+                });
+            }
+            Err(ProcessError::PermissionDenied) => {
+                self.show_error(fl!("error-unit-permission", unit = name));
+            }
+            Err(ProcessError::NotFound) => {
+                self.show_error(fl!("error-unit-not-found", unit = name));
+            }
+            Err(e) => {
+                self.show_error(fl!("error-unit-failed", unit = name, error = e.to_string()));
+            }
+        }
+
+        self.request_sample();
+    }
+
+    /// Freeze or thaw the cgroup of the app a process belongs to, which
+    /// stops or resumes all of its processes together.
+    fn set_frozen(&mut self, pid: u32, frozen: bool) {
+        let Some(process) = self.table.get(&pid).cloned() else {
+            self.show_error(fl!("error-process-not-found"));
+            return;
+        };
+        if let Err(ProcessError::Protected(name)) = process::can_kill_process(&process) {
+            self.show_error(fl!("notification-protected", name = name));
+            return;
+        }
+        let Some(app_cgroup) = process.app_cgroup.as_deref() else {
+            self.show_error(fl!("error-freeze-no-app", name = process.name));
+            return;
+        };
+
+        let app = cgroup::app_name(app_cgroup).to_string();
+        // Nothing would be left to thaw it
+        if frozen && self.runs_in(app_cgroup) {
+            self.show_error(fl!("error-freeze-self", app = app));
+            return;
+        }
+        match self.freezer.set_frozen(app_cgroup, frozen) {
+            Ok(()) => {
+                self.toast = Some(Toast {
+                    message: if frozen {
+                        fl!("notification-app-frozen", app = app)
+                    } else {
+                        fl!("notification-app-thawed", app = app)
+                    },
+                    is_error: false,
+                });
+            }
+            Err(ProcessError::PermissionDenied) => {
+                self.show_error(fl!("error-freeze-permission", app = app));
+            }
+            Err(ProcessError::NotFound) => {
+                self.show_error(fl!("error-freeze-not-found", app = app));
+            }
+            Err(e) => {
+                self.show_error(fl!("error-freeze-failed", app = app, error = e.to_string()));
+            }
+        }
+
+        self.request_sample();
+    }
+
+    /// This process as sampled, or else where `/proc/self/cgroup` tells it
+    /// runs
+    fn own_process(&self) -> ProcessInfo {
+        if let Some(process) = self.table.get(&std::process::id()) {
+            return process.clone();
+        }
+        let content = std::fs::read_to_string("/proc/self/cgroup").unwrap_or_default();
+        let path = systemd::parse_cgroup(&content);
+        ProcessInfo {
+            pid: std::process::id(),
+            unit: path.and_then(systemd::unit_of),
+            app_cgroup: path.and_then(cgroup::app_cgroup).map(str::to_string),
+            container: path.and_then(container::container_of),
+            ..Default::default()
+        }
+    }
+
+    /// Whether this process runs in `app_cgroup` or an app cgroup below it
+    fn runs_in(&self, app_cgroup: &str) -> bool {
+        self.own_process().app_cgroup.is_some_and(|own| {
+            own.strip_prefix(app_cgroup)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+        })
+    }
+
+    /// Limit the app of a process through its cgroup, which is delegated to
+    /// the user, or its service through systemd. Any other process is
+    /// limited on its own, in a scope systemd moves it into.
+    fn set_limits(&mut self, pid: u32, limits: Limits) {
+        let Some(process) = self.table.get(&pid).cloned() else {
+            self.show_error(fl!("error-process-not-found"));
+            return;
+        };
+        if let Err(ProcessError::Protected(name)) = process::can_kill_process(&process) {
+            self.show_error(fl!("notification-protected", name = name));
+            return;
+        }
+
+        let unit = process.unit.as_ref().filter(|unit| unit.is_manageable());
+        let target = match (process.app_cgroup.as_deref(), unit) {
+            (Some(app_cgroup), _) => cgroup::app_name(app_cgroup).to_string(),
+            (None, Some(unit)) => unit.name.clone(),
+            (None, None) => process.name.clone(),
+        };
+        let usage = self.usage_over(&process, unit, limits.memory_max);
+
+        if let Some(app_cgroup) = process.app_cgroup.as_deref() {
+            match self.limiter.set_limits(app_cgroup, limits) {
+                // systemd only enables the memory and CPU controllers for a
+                // cgroup once a unit asks for them
+                Err(ProcessError::NotFound) if unit.is_some() => {}
+                result => return self.limits_done(target, usage, result),
+            }
+        }
+        let set = match unit {
+            Some(unit) => self.units.set_limits(unit, limits),
+            // Nothing to limit it along with, such as a program started from
+            // a terminal, which runs in the login session
+            None => self.units.limit_process(pid, process.uid, limits),
+        };
+        self.jobs.push(Box::pin(async move {
+            Message::LimitsDone {
+                target,
+                usage,
+                result: set.await,
+            }
+        }));
+    }
+
+    /// The memory used by the app or service of `process`, or the process
+    /// alone outside of both, as far as the table knows, when it is more
+    /// than `memory_max`
+    fn usage_over(
+        &self,
+        process: &ProcessInfo,
+        unit: Option<&Unit>,
+        memory_max: Option<u64>,
+    ) -> Option<u64> {
+        let memory_max = memory_max?;
+        let usage: u64 = self
+            .table
+            .values()
+            .filter(|other| match (&process.app_cgroup, unit) {
+                (Some(app_cgroup), _) => other.app_cgroup.as_ref() == Some(app_cgroup),
+                (None, Some(unit)) => other.unit.as_ref() == Some(unit),
+                (None, None) => other.pid == process.pid,
+            })
+            .filter_map(|other| other.memory)
+            .sum();
+        (usage > memory_max).then_some(usage)
+    }
+
+    /// Tell how limiting an app or service went, warning when it already
+    /// uses more memory than it was given.
+    fn limits_done(&mut self, target: String, usage: Option<u64>, result: ProcessResult<()>) {
+        match result {
+            Ok(()) => {
+                self.toast = Some(match usage {
+                    Some(usage) => {
+                        let usage = format!("{} MB", usage / 1024 / 1024);
+                        Toast {
+                            message: fl!(
+                                "notification-limits-below-usage",
+                                target = target,
+                                usage = usage
+                            ),
+                            is_error: true,
+                        }
+                    }
+                    None => Toast {
+                        message: fl!("notification-limits-set", target = target),
+                        is_error: false,
+                    },
+                });
+            }
+            Err(ProcessError::PermissionDenied) => {
+                self.show_error(fl!("error-limits-permission", target = target));
+            }
+            Err(ProcessError::NotFound) => {
+                self.show_error(fl!("error-limits-not-found", target = target));
+            }
+            Err(e) => {
+                self.show_error(fl!("error-limits-failed", target = target, error = e.to_string()));
+            }
+        }
+
+        self.request_sample();
+    }
+
+    /// Ask to SIGKILL every process of the same running copy of a packaged
+    /// app, as `flatpak kill` does.
+    fn kill_package(&mut self, pid: u32) {
+        let Some(process) = self.table.get(&pid) else {
+            self.show_error(fl!("error-process-not-found"));
+            return;
+        };
+        let Some(package) = process.package.clone() else {
+            self.show_error(fl!("error-package-none", name = process.name.clone()));
+            return;
+        };
+
+        let pids: Vec<u32> = self
+            .table
+            .values()
+            .filter(|process| {
+                process.package.as_ref().is_some_and(|other| {
+                    other.kind == package.kind && other.instance == package.instance
+                })
+            })
+            .map(|process| process.pid)
+            .collect();
+        self.confirm_group_kill(GroupTarget::Package(package), Signal::SIGKILL, pids);
+    }
+
+    /// Ask to stop the container of a process through its runtime, done in
+    /// the background once confirmed. The runtime signals its main process
+    /// and kills what is left after a timeout; killing the process instead
+    /// would only get the container restarted.
+    fn stop_container(&mut self, pid: u32) {
+        let Some(process) = self.table.get(&pid) else {
+            self.show_error(fl!("error-process-not-found"));
+            return;
+        };
+        let Some(container) = process.container.clone() else {
+            self.show_error(fl!("error-container-none", name = process.name.clone()));
+            return;
+        };
+        // Stopping it would stop this process midway
+        if self
+            .own_process()
+            .container
+            .is_some_and(|own| own.id == container.id)
+        {
+            let name = container.label().to_string();
+            self.show_error(fl!("error-container-self", container = name));
+            return;
+        }
+
+        self.confirmation_mode = None;
+        self.selected_process = None;
+        self.group_kill = None;
+        self.pending_action = Some(PendingAction::StopContainer(container));
+    }
+
+    /// Tell how stopping a container went.
+    fn stop_container_done(&mut self, name: String, result: ProcessResult<()>) {
+        match result {
+            Ok(()) => {
+                self.toast = Some(Toast {
+                    message: fl!("notification-container-stopped", container = name),
+                    is_error: false,
+                });
+            }
+            Err(ProcessError::PermissionDenied) => {
+                self.show_error(fl!("error-container-permission", container = name));
+            }
+            Err(ProcessError::NotFound) => {
+                self.show_error(fl!("error-container-not-found", container = name));
+            }
+            Err(e) => {
+                self.show_error(fl!(
+                    "error-container-failed",
+                    container = name,
+                    error = e.to_string()
+                ));
+            }
+        }
+
+        self.request_sample();
+    }
+
+    /// Ask to signal every holder found by the last lookup.
+    fn kill_holders(&mut self, force: bool) {
+        let Some(lookup) = self.holder_lookup.as_ref() else {
+            return;
+        };
+        let Some(holders) = lookup.holders.as_ref() else {
+            return;
+        };
+        let pids: Vec<u32> = holders.iter().map(|holder| holder.pid).collect();
+        let signal = if force {
+            Signal::SIGKILL
+        } else {
+            Signal::SIGTERM
+        };
+        self.confirm_group_kill(GroupTarget::Holders(lookup.path.clone()), signal, pids);
+    }
+
+    /// Ask to confirm signalling `pids`, except protected processes, this
+    /// one, and those missing from the sample, which could be anything.
+    fn confirm_group_kill(&mut self, target: GroupTarget, signal: Signal, pids: Vec<u32>) {
+        let mut processes: Vec<ProcessKey> = pids
+            .into_iter()
+            .filter(|pid| *pid != std::process::id())
+            .filter_map(|pid| self.table.get(&pid))
+            .filter(|process| process::can_kill_process(process).is_ok())
+            .map(ProcessInfo::key)
+            .collect();
+        processes.sort_unstable_by_key(|key| key.pid);
+        processes.dedup();
+
+        if processes.is_empty() {
+            self.show_error(fl!("error-group-kill-none"));
+            return;
+        }
+        self.confirmation_mode = None;
+        self.selected_process = None;
+        self.pending_action = None;
+        self.group_kill = Some(GroupKill {
+            target,
+            signal,
+            processes,
+        });
+    }
+
+    /// Signal the processes of the confirmed group kill that are still the
+    /// ones it was asked for.
+    fn execute_group_kill(&mut self) {
+        let Some(GroupKill {
+            target,
+            signal,
+            processes,
+        }) = self.group_kill.take()
+        else {
+            return;
+        };
+
+        let mut killed = 0;
+        let mut failed = 0;
+        for key in processes {
+            // Exited, its PID maybe taken by another process since
+            let Some(process) = self.table.get(&key.pid).filter(|p| p.key() == key) else {
+                continue;
+            };
+            let allowed = process::can_kill_process(process);
+            match allowed.and_then(|()| self.signals.send_signal(key.pid, signal)) {
+                Ok(()) => killed += 1,
+                // Exited in the meantime
+                Err(ProcessError::NotFound) => {}
+                Err(_) => failed += 1,
+            }
+        }
+
+        let count = killed + failed;
+        match target {
+            GroupTarget::Holders(path) => {
+                let path_text = path.display().to_string();
+                if failed > 0 {
+                    self.show_error(fl!(
+                        "error-holders-kill-failed",
+                        failed = failed,
+                        count = count,
+                        path = path_text
+                    ));
+                } else {
+                    self.toast = Some(Toast {
+                        message: fl!(
+                            "notification-holders-killed",
+                            signal = signal.as_str(),
+                            count = killed,
+                            path = path_text
+                        ),
+                        is_error: false,
+                    });
+                }
+                // Look again
+                if let Some(sampler) = &self.sampler {
+                    sampler.find_holders(path);
+                }
+            }
+            GroupTarget::Package(package) => {
+                if failed > 0 {
+                    self.show_error(fl!(
+                        "error-package-kill-failed",
+                        failed = failed,
+                        count = count,
+                        app = package.app_id
+                    ));
+                } else {
+                    self.toast = Some(Toast {
+                        message: fl!(
+                            "notification-package-killed",
+                            count = killed,
+                            app = package.app_id
+                        ),
+                        is_error: false,
+                    });
+                }
+            }
+        }
+
+        self.request_sample();
+    }
+
+    /// Change the priority, or with `affinity` the CPU affinity, of a process.
+    fn change_scheduling(
+        &mut self,
+        pid: u32,
+        affinity: bool,
+        change: impl FnOnce(&dyn Scheduler) -> process::ProcessResult<()>,
+    ) {
+        let Some(name) = self.table.get(&pid).map(|p| p.name.clone()) else {
+            self.show_error(fl!("error-process-not-found"));
+            return;
+        };
+
+        match change(self.scheduler.as_ref()) {
+            Ok(()) => {
+                self.toast = Some(Toast {
+                    message: if affinity {
+                        fl!("notification-affinity-changed", name = name)
+                    } else {
+                        fl!("notification-priority-changed", name = name)
+                    },
+                    is_error: false,
+                });
+            }
+            Err(ProcessError::PermissionDenied) if affinity => {
+                self.show_error(fl!("error-affinity-permission", name = name));
+            }
+            Err(ProcessError::PermissionDenied) => {
+                self.show_error(fl!("error-priority-permission", name = name));
+            }
+            Err(ProcessError::NotFound) => self.show_error(fl!("error-process-not-found")),
+            Err(e) => self.show_error(fl!("error-priority-failed", error = e.to_string())),
+        }
+
+        // Show the new values right away
+        self.request_sample();
+    }
+
+    fn execute_kill(&mut self, process: &ProcessInfo, force: bool) {
+        let signal = if force {
+            Signal::SIGKILL
+        } else {
+            Signal::SIGTERM
+        };
+        let result = self.signals.send_signal(process.pid, signal);
+
+        match result {
+            Ok(()) => {
+                self.toast = Some(Toast {
+                    message: if force {
+                        fl!("notification-force-kill-success", name = process.name.clone())
+                    } else {
+                        fl!("notification-kill-success", name = process.name.clone())
+                    },
+                    is_error: false,
+                });
+            }
+            Err(e) => {
+                let error_msg = match e {
+                    ProcessError::SignalFailed(msg) => {
+                        if force {
+                            fl!("error-sigkill-failed", error = msg)
+                        } else {
+                            fl!("error-sigterm-failed", error = msg)
+                        }
+                    }
+                    ProcessError::PermissionDenied => fl!("notification-permission-denied"),
+                    ProcessError::NotFound => fl!("error-process-not-found"),
+                    ProcessError::Protected(name) => {
+                        fl!("notification-protected", name = name)
+                    }
+                    ProcessError::Unknown(msg) => {
+                        fl!("error-unknown-error", error = msg)
+                    }
+                };
+
+                self.show_error(fl!("notification-kill-failed", error = error_msg));
+            }
+        }
+
+        self.request_sample();
+    }
+}
+
+/// Shorten a process name to at most `max_chars` characters for display.
+pub fn truncate_name(name: &str, max_chars: usize) -> String {
+    if name.chars().count() > max_chars {
+        let kept: String = name.chars().take(max_chars.saturating_sub(3)).collect();
+        format!("{kept}...")
+    } else {
+        name.to_string()
+    }
+}
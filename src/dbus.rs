@@ -0,0 +1,175 @@
+// SPDX-License-Identifier: MIT
+
+//! D-Bus service that lets scripts and other tools drive the running applet.
+//!
+//! The applet owns [`BUS_NAME`] on the session bus and exports
+//! [`INTERFACE_NAME`] at [`OBJECT_PATH`]. Read-only queries are answered
+//! from the applet's [`LatestSample`], so no call reads /proc on the bus's
+//! executor; anything that touches the UI is forwarded to the applet as a
+//! [`Request`].
+
+use crate::process::{self, filter_processes, sort_processes, ProcessError, ProcessInfo, SortBy};
+use crate::query::Query;
+use crate::sampler::LatestSample;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::UnboundedSender;
+use zbus::{fdo, zvariant::Type};
+
+pub const BUS_NAME: &str = "com.system.CosmicProcessKiller";
+pub const OBJECT_PATH: &str = "/com/system/CosmicProcessKiller";
+pub const INTERFACE_NAME: &str = "com.system.CosmicProcessKiller1";
+
+/// Requests forwarded from D-Bus callers to the applet's update loop
+#[derive(Debug, Clone, PartialEq)]
+pub enum Request {
+    /// Open the popup with the given search text (empty clears the search)
+    ShowPopup(String),
+    /// Open the popup and ask the user to confirm killing a process
+    ConfirmKill { pid: u32, force: bool },
+}
+
+/// A process as returned over D-Bus, signature `(usdtsb)`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct ProcessEntry {
+    pub pid: u32,
+    pub name: String,
+    pub cpu_usage: f64,
+    /// Bytes, 0 when the configured metric cannot be read
+    pub memory: u64,
+    pub status: String,
+    pub is_system: bool,
+}
+
+impl From<&ProcessInfo> for ProcessEntry {
+    fn from(process: &ProcessInfo) -> Self {
+        Self {
+            pid: process.pid,
+            name: process.name.clone(),
+            cpu_usage: f64::from(process.cpu_usage),
+            memory: process.memory.unwrap_or_default(),
+            status: process.status.clone(),
+            is_system: process.is_system,
+        }
+    }
+}
+
+pub struct ProcessKillerService {
+    latest: LatestSample,
+    requests: UnboundedSender<Request>,
+}
+
+impl ProcessKillerService {
+    pub fn new(latest: LatestSample, requests: UnboundedSender<Request>) -> Self {
+        Self { latest, requests }
+    }
+
+    fn process(&self, pid: u32) -> fdo::Result<ProcessInfo> {
+        self.latest
+            .get()
+            .iter()
+            .find(|process| process.pid == pid)
+            .cloned()
+            .ok_or_else(|| to_fdo_error(ProcessError::NotFound))
+    }
+
+    fn forward(&self, request: Request) -> fdo::Result<()> {
+        self.requests
+            .send(request)
+            .map_err(|_| fdo::Error::Failed("applet is shutting down".to_string()))
+    }
+}
+
+#[zbus::interface(name = "com.system.CosmicProcessKiller1")]
+impl ProcessKillerService {
+    /// Open the popup, pre-filtered with `search`.
+    fn show_popup(&self, search: String) -> fdo::Result<()> {
+        self.forward(Request::ShowPopup(search))
+    }
+
+    /// List processes sorted by `sort_by` (`cpu`, `mem`, `pid`, `name`, `read` or `write`)
+    /// and filtered like the search box.
+    fn list_processes(
+        &self,
+        sort_by: &str,
+        search: &str,
+        show_all: bool,
+    ) -> fdo::Result<Vec<ProcessEntry>> {
+        let sort_by = SortBy::from_name(sort_by)
+            .ok_or_else(|| fdo::Error::InvalidArgs(format!("unknown sort key: {sort_by}")))?;
+
+        let query = Query::parse(search).map_err(|e| fdo::Error::InvalidArgs(e.to_string()))?;
+
+        let mut processes = self.latest.get().to_vec();
+        sort_processes(&mut processes, sort_by);
+        if !show_all {
+            processes.truncate(10);
+        }
+
+        Ok(filter_processes(&processes, &query)
+            .into_iter()
+            .map(ProcessEntry::from)
+            .collect())
+    }
+
+    /// Look up a single process.
+    fn get_process(&self, pid: u32) -> fdo::Result<ProcessEntry> {
+        self.process(pid).map(|process| ProcessEntry::from(&process))
+    }
+
+    /// Ask the user to confirm killing `pid` in the popup.
+    ///
+    /// Fails without showing anything if the process does not exist or is
+    /// protected; the kill itself only happens once the user confirms.
+    fn request_kill(&self, pid: u32, force: bool) -> fdo::Result<()> {
+        process::can_kill_process(&self.process(pid)?).map_err(to_fdo_error)?;
+        self.forward(Request::ConfirmKill { pid, force })
+    }
+}
+
+fn to_fdo_error(error: ProcessError) -> fdo::Error {
+    match error {
+        ProcessError::PermissionDenied | ProcessError::Protected(_) => {
+            fdo::Error::AccessDenied(error.to_string())
+        }
+        ProcessError::NotFound => fdo::Error::InvalidArgs(error.to_string()),
+        _ => fdo::Error::Failed(error.to_string()),
+    }
+}
+
+/// Export the service on the session bus and claim [`BUS_NAME`], answering
+/// from the samples stored in `latest`.
+///
+/// The returned connection must be kept alive for as long as the service
+/// should stay reachable.
+pub async fn serve(
+    latest: LatestSample,
+    requests: UnboundedSender<Request>,
+) -> zbus::Result<zbus::Connection> {
+    zbus::connection::Builder::session()?
+        .name(BUS_NAME)?
+        .serve_at(OBJECT_PATH, ProcessKillerService::new(latest, requests))?
+        .build()
+        .await
+}
+
+/// Client-side proxy for the service, usable from scripts written in Rust
+/// and from tests against a private bus.
+#[zbus::proxy(
+    interface = "com.system.CosmicProcessKiller1",
+    default_service = "com.system.CosmicProcessKiller",
+    default_path = "/com/system/CosmicProcessKiller"
+)]
+pub trait ProcessKiller {
+    fn show_popup(&self, search: &str) -> zbus::Result<()>;
+
+    fn list_processes(
+        &self,
+        sort_by: &str,
+        search: &str,
+        show_all: bool,
+    ) -> zbus::Result<Vec<ProcessEntry>>;
+
+    fn get_process(&self, pid: u32) -> zbus::Result<ProcessEntry>;
+
+    fn request_kill(&self, pid: u32, force: bool) -> zbus::Result<()>;
+}
@@ -0,0 +1,131 @@
+// SPDX-License-Identifier: MIT
+
+//! Export of process snapshots to JSON Lines or CSV for bug reports.
+//!
+//! Every record carries a `schema_version` field so external tooling can
+//! detect layout changes. Bump [`SCHEMA_VERSION`] whenever a field of
+//! [`ProcessInfo`] is renamed, removed or changes meaning; adding fields is
+//! backwards compatible. The `memory_metric` field tells what `memory`
+//! measures, which is empty when that could not be read.
+
+use crate::memory::MemoryMetric;
+use crate::process::ProcessInfo;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::{self, Write};
+
+/// Version of the exported record layout
+pub const SCHEMA_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One JSON object per line
+    JsonLines,
+    /// Comma separated values with a header row
+    Csv,
+}
+
+impl ExportFormat {
+    /// Parse a format name as used on the command line.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "json" | "jsonl" | "jsonlines" => Some(ExportFormat::JsonLines),
+            "csv" => Some(ExportFormat::Csv),
+            _ => None,
+        }
+    }
+
+    /// File extension used for exported snapshots
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::JsonLines => "jsonl",
+            ExportFormat::Csv => "csv",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Record<'a> {
+    schema_version: u32,
+    memory_metric: MemoryMetric,
+    #[serde(flatten)]
+    process: &'a ProcessInfo,
+}
+
+impl<'a> Record<'a> {
+    fn new(process: &'a ProcessInfo, memory_metric: MemoryMetric) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            memory_metric,
+            process,
+        }
+    }
+}
+
+/// Write the given processes, in order, to `writer`, their memory measured
+/// by `memory_metric`.
+pub fn write_snapshot<W: Write>(
+    mut writer: W,
+    format: ExportFormat,
+    memory_metric: MemoryMetric,
+    processes: &[&ProcessInfo],
+) -> io::Result<()> {
+    match format {
+        ExportFormat::JsonLines => {
+            for process in processes {
+                serde_json::to_writer(&mut writer, &Record::new(process, memory_metric))?;
+                writer.write_all(b"\n")?;
+            }
+        }
+        ExportFormat::Csv => {
+            // The header is derived from the serialized record so new
+            // `ProcessInfo` fields show up as columns automatically.
+            let placeholder = ProcessInfo::default();
+            let columns = match to_object(&Record::new(&placeholder, memory_metric))? {
+                Value::Object(map) => map.keys().cloned().collect::<Vec<_>>(),
+                _ => Vec::new(),
+            };
+            write_csv_line(&mut writer, columns.iter().map(String::as_str))?;
+
+            for process in processes {
+                let record = to_object(&Record::new(process, memory_metric))?;
+                let cells = columns
+                    .iter()
+                    .map(|column| csv_cell(record.get(column).unwrap_or(&Value::Null)))
+                    .collect::<Vec<_>>();
+                write_csv_line(&mut writer, cells.iter().map(String::as_str))?;
+            }
+        }
+    }
+
+    writer.flush()
+}
+
+fn to_object(record: &Record<'_>) -> io::Result<Value> {
+    serde_json::to_value(record).map_err(io::Error::from)
+}
+
+/// Render a JSON value as a single CSV cell (nested values stay JSON encoded)
+fn csv_cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn write_csv_line<'a, W: Write>(
+    writer: &mut W,
+    cells: impl Iterator<Item = &'a str>,
+) -> io::Result<()> {
+    let line = cells.map(escape_csv).collect::<Vec<_>>().join(",");
+    writeln!(writer, "{line}")
+}
+
+fn escape_csv(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
@@ -0,0 +1,136 @@
+// SPDX-License-Identifier: MIT
+
+//! Files held open by processes, and who holds a given file.
+//!
+//! A process keeps a file busy through its descriptors (`/proc/<pid>/fd`),
+//! memory mappings (`/proc/<pid>/maps`), working directory, root directory or
+//! executable. Any of these under a mount point makes unmounting it fail with
+//! "device or resource busy". Like `fuser -m`, a lookup by mount point finds
+//! every file below it; only one's own processes can be inspected without
+//! privileges.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How a process holds a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Usage {
+    /// Open as this file descriptor
+    Descriptor(u32),
+    /// Mapped into memory, as libraries are
+    Mapped,
+    WorkingDirectory,
+    RootDirectory,
+    Executable,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpenFile {
+    pub path: PathBuf,
+    pub usage: Usage,
+}
+
+/// A process holding some of the files looked for
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Holder {
+    pub pid: u32,
+    pub files: Vec<OpenFile>,
+}
+
+/// Marks files that were deleted while open
+const DELETED: &str = " (deleted)";
+
+/// Everything a process holds: descriptors in order, then mapped files, then
+/// its directories and executable.
+pub fn open_files(root: &Path, pid: u32) -> Vec<OpenFile> {
+    let dir = root.join(pid.to_string());
+    let mut files = Vec::new();
+
+    if let Ok(fds) = fs::read_dir(dir.join("fd")) {
+        let mut descriptors: Vec<(u32, PathBuf)> = fds
+            .filter_map(|fd| {
+                let fd = fd.ok()?;
+                let number = fd.file_name().to_str()?.parse().ok()?;
+                // Sockets, pipes and anonymous inodes are not paths
+                Some((number, file_path(&fs::read_link(fd.path()).ok()?)?))
+            })
+            .collect();
+        descriptors.sort_by_key(|(number, _)| *number);
+        files.extend(descriptors.into_iter().map(|(number, path)| OpenFile {
+            path,
+            usage: Usage::Descriptor(number),
+        }));
+    }
+
+    if let Ok(maps) = fs::read_to_string(dir.join("maps")) {
+        let mut mapped = parse_maps(&maps);
+        mapped.sort();
+        mapped.dedup();
+        files.extend(mapped.into_iter().map(|path| OpenFile {
+            path,
+            usage: Usage::Mapped,
+        }));
+    }
+
+    for (link, usage) in [
+        ("cwd", Usage::WorkingDirectory),
+        ("root", Usage::RootDirectory),
+        ("exe", Usage::Executable),
+    ] {
+        if let Some(path) = fs::read_link(dir.join(link))
+            .ok()
+            .and_then(|target| file_path(&target))
+        {
+            files.push(OpenFile { path, usage });
+        }
+    }
+
+    files
+}
+
+/// Whether `file` is `target` itself or lies below it
+pub fn is_under(file: &Path, target: &Path) -> bool {
+    file.starts_with(target)
+}
+
+/// Every process holding `target` or, for a directory or mount point,
+/// anything below it, by PID.
+pub fn holders(root: &Path, target: &Path) -> Vec<Holder> {
+    let Ok(dir) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut holders: Vec<Holder> = dir
+        .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<u32>().ok())
+        .filter_map(|pid| {
+            let files: Vec<OpenFile> = open_files(root, pid)
+                .into_iter()
+                .filter(|file| is_under(&file.path, target))
+                .collect();
+            (!files.is_empty()).then_some(Holder { pid, files })
+        })
+        .collect();
+    holders.sort_by_key(|holder| holder.pid);
+    holders
+}
+
+/// Paths of the files mapped in `/proc/<pid>/maps`. The path is the last
+/// column and may contain spaces; the columns before it never contain `/`.
+pub fn parse_maps(content: &str) -> Vec<PathBuf> {
+    content
+        .lines()
+        .filter_map(|line| file_path(Path::new(&line[line.find('/')?..])))
+        .collect()
+}
+
+/// An absolute path, without the mark of a deleted file
+fn file_path(target: &Path) -> Option<PathBuf> {
+    let target = target.to_str()?;
+    if !target.starts_with('/') {
+        return None;
+    }
+    Some(PathBuf::from(
+        target.strip_suffix(DELETED).unwrap_or(target),
+    ))
+}
@@ -0,0 +1,92 @@
+// SPDX-License-Identifier: MIT
+
+//! Keyboard shortcuts for the standalone window.
+//!
+//! Bindings are stored in the config as strings such as `"Shift+Delete"`:
+//! optional `Ctrl`, `Alt` and `Shift` modifiers followed by a key name. Named
+//! keys use their iced names (`Delete`, `ArrowUp`, `Enter`, `Escape`, ...);
+//! other keys are the character they produce.
+
+use serde::{Deserialize, Serialize};
+
+/// Something a key press can do in the process list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction {
+    SelectNext,
+    SelectPrevious,
+    Kill,
+    ForceKill,
+    Confirm,
+    Cancel,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    pub select_next: String,
+    pub select_previous: String,
+    pub kill: String,
+    pub force_kill: String,
+    pub confirm: String,
+    pub cancel: String,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            select_next: "ArrowDown".to_string(),
+            select_previous: "ArrowUp".to_string(),
+            kill: "Delete".to_string(),
+            force_kill: "Shift+Delete".to_string(),
+            confirm: "Enter".to_string(),
+            cancel: "Escape".to_string(),
+        }
+    }
+}
+
+impl KeyBindings {
+    /// Find the action bound to a key press, if any.
+    pub fn action_for(&self, key: &str, modifiers: Modifiers) -> Option<KeyAction> {
+        [
+            (&self.select_next, KeyAction::SelectNext),
+            (&self.select_previous, KeyAction::SelectPrevious),
+            (&self.kill, KeyAction::Kill),
+            (&self.force_kill, KeyAction::ForceKill),
+            (&self.confirm, KeyAction::Confirm),
+            (&self.cancel, KeyAction::Cancel),
+        ]
+        .into_iter()
+        .find(|(binding, _)| {
+            parse_binding(binding).is_some_and(|(bound_key, bound_modifiers)| {
+                bound_modifiers == modifiers && bound_key.eq_ignore_ascii_case(key)
+            })
+        })
+        .map(|(_, action)| action)
+    }
+}
+
+/// Modifier keys held during a key press
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+}
+
+/// Split a binding such as `"Ctrl+Shift+K"` into its key and modifiers.
+fn parse_binding(binding: &str) -> Option<(&str, Modifiers)> {
+    let mut parts = binding.split('+').map(str::trim).collect::<Vec<_>>();
+    let key = parts.pop().filter(|key| !key.is_empty())?;
+    let mut modifiers = Modifiers::default();
+
+    for part in parts {
+        match part.to_lowercase().as_str() {
+            "ctrl" | "control" => modifiers.ctrl = true,
+            "alt" => modifiers.alt = true,
+            "shift" => modifiers.shift = true,
+            _ => return None,
+        }
+    }
+
+    Some((key, modifiers))
+}
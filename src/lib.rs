@@ -2,9 +2,29 @@
 
 //! COSMIC Process Killer - Library
 
+pub mod affinity;
+pub mod backend;
+pub mod cgroup;
+pub mod cli;
 #[macro_use]
 pub mod config;
+pub mod container;
+pub mod controller;
+pub mod dbus;
+pub mod export;
+pub mod files;
 #[macro_use]
 pub mod i18n;
+pub mod keybindings;
+pub mod memory;
+pub mod package;
+pub mod priority;
 pub mod process;
+pub mod procfs;
+pub mod query;
+pub mod sampler;
+pub mod sockets;
 pub mod standalone;
+pub mod systemd;
+pub mod view;
+pub mod virtual_list;
@@ -1,10 +1,8 @@
 // SPDX-License-Identifier: MIT
 
 mod app;
-mod config;
-mod i18n;
-mod process;
-mod standalone;
+
+use cosmic_applet_process_killer::i18n;
 
 fn main() -> cosmic::iced::Result {
     // Get the system's preferred languages.
@@ -0,0 +1,101 @@
+// SPDX-License-Identifier: MIT
+
+//! How much memory a process really uses.
+//!
+//! The resident set size (RSS) counts every page mapped by a process, so
+//! libraries and shared memory are counted once per process using them.
+//! `/proc/<pid>/smaps_rollup` splits it into private and shared pages and
+//! gives the proportional set size (PSS), where shared pages are divided
+//! between the processes sharing them. Reading it walks every mapping of the
+//! process, so it is only done when asked for.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Which figure the memory column and memory sorting use
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MemoryMetric {
+    /// Resident set size, read from `stat`
+    #[default]
+    Rss,
+    /// Proportional set size: private pages plus a share of shared ones
+    Pss,
+    /// Unique set size: private pages only, what killing the process frees
+    Uss,
+}
+
+impl MemoryMetric {
+    pub const ALL: [MemoryMetric; 3] = [MemoryMetric::Rss, MemoryMetric::Pss, MemoryMetric::Uss];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MemoryMetric::Rss => "RSS",
+            MemoryMetric::Pss => "PSS",
+            MemoryMetric::Uss => "USS",
+        }
+    }
+}
+
+/// Memory of a process from `smaps_rollup`, in bytes
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryBreakdown {
+    pub rss: u64,
+    pub pss: u64,
+    /// Pages only this process maps
+    pub private: u64,
+    /// Pages also mapped by other processes
+    pub shared: u64,
+    /// Pages swapped out
+    pub swap: u64,
+}
+
+impl MemoryBreakdown {
+    pub fn get(&self, metric: MemoryMetric) -> u64 {
+        match metric {
+            MemoryMetric::Rss => self.rss,
+            MemoryMetric::Pss => self.pss,
+            MemoryMetric::Uss => self.private,
+        }
+    }
+}
+
+/// Read `<root>/<pid>/smaps_rollup`. Other users' processes can only be read
+/// with `CAP_SYS_PTRACE`, kernel threads have no mappings at all.
+pub fn smaps_rollup(root: &Path, pid: u32) -> Option<MemoryBreakdown> {
+    parse_smaps_rollup(&fs::read_to_string(root.join(pid.to_string()).join("smaps_rollup")).ok()?)
+}
+
+/// Parse the content of `smaps_rollup`: a header line, then one
+/// `Field:   value kB` line per counter.
+pub fn parse_smaps_rollup(content: &str) -> Option<MemoryBreakdown> {
+    let mut breakdown = MemoryBreakdown::default();
+    let mut found = false;
+
+    for line in content.lines() {
+        let Some((field, value)) = line.split_once(':') else {
+            continue;
+        };
+        let Some(kib) = value
+            .trim()
+            .strip_suffix("kB")
+            .and_then(|kib| kib.trim().parse::<u64>().ok())
+        else {
+            continue;
+        };
+        let bytes = kib * 1024;
+
+        match field {
+            "Rss" => breakdown.rss = bytes,
+            "Pss" => breakdown.pss = bytes,
+            "Private_Clean" | "Private_Dirty" => breakdown.private += bytes,
+            "Shared_Clean" | "Shared_Dirty" => breakdown.shared += bytes,
+            "Swap" => breakdown.swap = bytes,
+            _ => continue,
+        }
+        found = true;
+    }
+
+    found.then_some(breakdown)
+}
@@ -0,0 +1,190 @@
+// SPDX-License-Identifier: MIT
+
+//! Apps packaged as Flatpaks, Snaps and AppImages.
+//!
+//! Packaged apps show up under the names of launchers and generic binaries
+//! (`bwrap`, `snap`, `AppRun`), so their processes are matched to the app
+//! they belong to. Processes inside a Flatpak sandbox see the instance's
+//! `/.flatpak-info`, while the `bwrap` process that set the sandbox up is
+//! named in the instance directories below `$XDG_RUNTIME_DIR/.flatpak`.
+//! Confined snaps run under the AppArmor label `snap.<name>.<app>`, and every
+//! snap in a `snap.<name>.<app>` scope or service. An AppImage runs from its
+//! image mounted at `/tmp/.mount_<name><random>`, and its processes find the
+//! image itself in `$APPIMAGE`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How an app is packaged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageKind {
+    Flatpak,
+    Snap,
+    AppImage,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Package {
+    pub kind: PackageKind,
+    /// `org.gnome.Calculator` for a Flatpak, the snap name, or the name of
+    /// the AppImage file without its extension
+    pub app_id: String,
+    /// One running copy of the app, shared by all of its processes: the
+    /// Flatpak instance ID, the snap's scope or the AppImage's mount point
+    pub instance: String,
+    /// Name of the icon in the icon theme, or path of an icon file
+    pub icon: Option<String>,
+}
+
+/// Where AppImages are mounted while they run
+const APPIMAGE_MOUNT_PREFIX: &str = "/tmp/.mount_";
+/// Random characters appended to the AppImage name in its mount point
+const APPIMAGE_MOUNT_SUFFIX_LEN: usize = 6;
+
+/// The package of the process whose `/proc/<pid>` directory is `dir`, given
+/// its executable and cgroup path.
+pub fn detect(dir: &Path, exe: Option<&Path>, cgroup: Option<&str>) -> Option<Package> {
+    if let Some((app_id, instance)) = fs::read_to_string(dir.join("root/.flatpak-info"))
+        .ok()
+        .and_then(|info| parse_flatpak_info(&info))
+    {
+        return Some(flatpak(app_id, instance));
+    }
+
+    let label = fs::read_to_string(dir.join("attr/current")).unwrap_or_default();
+    let scope = cgroup.and_then(snap_unit);
+    if let Some(name) = scope
+        .and_then(snap_name)
+        .or_else(|| parse_snap_label(&label))
+    {
+        let icon = Path::new("/snap")
+            .join(name)
+            .join("current/meta/gui/icon.png");
+        return Some(Package {
+            kind: PackageKind::Snap,
+            app_id: name.to_string(),
+            instance: scope.unwrap_or(label.trim()).to_string(),
+            icon: icon.exists().then(|| icon.display().to_string()),
+        });
+    }
+
+    let mount = appimage_mount(exe?)?;
+    let image = fs::read(dir.join("environ")).ok().and_then(|environ| {
+        environ
+            .split(|byte| *byte == 0)
+            .find_map(|var| var.strip_prefix(b"APPIMAGE="))
+            .map(|image| PathBuf::from(String::from_utf8_lossy(image).into_owned()))
+    });
+    let app_id = match &image {
+        Some(image) => image.file_stem()?.to_string_lossy().into_owned(),
+        None => {
+            let name = mount.strip_prefix(APPIMAGE_MOUNT_PREFIX)?;
+            // The suffix is ASCII, but anyone can name a directory like a
+            // mount point; keep all of a name the suffix would split
+            name.get(..name.len().saturating_sub(APPIMAGE_MOUNT_SUFFIX_LEN))
+                .unwrap_or(name)
+                .to_string()
+        }
+    };
+    let icon = Path::new(mount).join(".DirIcon");
+    Some(Package {
+        kind: PackageKind::AppImage,
+        app_id,
+        instance: mount.to_string(),
+        icon: icon.exists().then(|| icon.display().to_string()),
+    })
+}
+
+/// The app ID and instance ID in a `.flatpak-info` file, whose
+/// `[Application]` group names the app and `[Instance]` group the instance
+pub fn parse_flatpak_info(content: &str) -> Option<(String, String)> {
+    let mut group = "";
+    let mut app_id = None;
+    let mut instance = None;
+    for line in content.lines().map(str::trim) {
+        if let Some(name) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            group = name;
+        } else if let Some((key, value)) = line.split_once('=') {
+            match (group, key.trim()) {
+                ("Application", "name") => app_id = Some(value.trim().to_string()),
+                ("Instance", "instance-id") => instance = Some(value.trim().to_string()),
+                _ => {}
+            }
+        }
+    }
+    Some((app_id?, instance?))
+}
+
+/// The snap name in an AppArmor label such as `snap.firefox.firefox
+/// (enforce)`; `unconfined` and other profiles have none
+pub fn parse_snap_label(label: &str) -> Option<&str> {
+    let profile = label.split_whitespace().next()?;
+    snap_name(profile)
+}
+
+/// The innermost `snap.<name>.<app>` unit in a cgroup path
+fn snap_unit(cgroup: &str) -> Option<&str> {
+    cgroup.rsplit('/').find(|component| {
+        component.starts_with("snap.")
+            && (component.ends_with(".scope") || component.ends_with(".service"))
+    })
+}
+
+/// `<name>` in `snap.<name>.<app>…`
+fn snap_name(snap: &str) -> Option<&str> {
+    let mut parts = snap.strip_prefix("snap.")?.split('.');
+    let name = parts.next()?;
+    // A bare `snap.<name>` is not an app of the snap
+    (!name.is_empty() && parts.next().is_some()).then_some(name)
+}
+
+/// The mount point of the AppImage an executable lies in
+fn appimage_mount(exe: &Path) -> Option<&str> {
+    let exe = exe.to_str()?;
+    let name = exe.strip_prefix(APPIMAGE_MOUNT_PREFIX)?;
+    let name_len = name.find('/').unwrap_or(name.len());
+    Some(&exe[..APPIMAGE_MOUNT_PREFIX.len() + name_len])
+}
+
+/// The package of the `bwrap` process of each running Flatpak instance, by
+/// PID, from the instance directories in `dir`. These processes sit outside
+/// the sandbox, so they cannot see its `.flatpak-info`.
+pub fn flatpak_launchers(dir: &Path) -> HashMap<u32, Package> {
+    let Ok(instances) = fs::read_dir(dir) else {
+        return HashMap::new();
+    };
+    instances
+        .filter_map(|instance| {
+            let path = instance.ok()?.path();
+            let pid = fs::read_to_string(path.join("pid"))
+                .ok()?
+                .trim()
+                .parse()
+                .ok()?;
+            let (app_id, instance) =
+                parse_flatpak_info(&fs::read_to_string(path.join("info")).ok()?)?;
+            Some((pid, flatpak(app_id, instance)))
+        })
+        .collect()
+}
+
+/// Where Flatpak keeps its instance directories for this user
+pub fn flatpak_instance_dir() -> Option<PathBuf> {
+    std::env::var_os("XDG_RUNTIME_DIR").map(|dir| PathBuf::from(dir).join(".flatpak"))
+}
+
+fn flatpak(app_id: String, instance: String) -> Package {
+    Package {
+        kind: PackageKind::Flatpak,
+        // Flatpaks export their icons under their app ID
+        icon: Some(app_id.clone()),
+        app_id,
+        instance,
+    }
+}
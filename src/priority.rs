@@ -0,0 +1,231 @@
+// SPDX-License-Identifier: MIT
+
+//! CPU and I/O scheduling priority.
+//!
+//! The nice value goes through getpriority(2)/setpriority(2) and the I/O
+//! priority through ioprio_get(2)/ioprio_set(2), which libc does not wrap.
+//! On Linux both apply to a single thread, so changes are made to every
+//! thread of the process, as listed in `/proc/<pid>/task`.
+
+use crate::affinity::{self, CpuMask};
+use crate::process::{self, ProcessError, ProcessResult};
+use nix::errno::Errno;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::ops::RangeInclusive;
+
+/// Nice values, from the highest priority to the lowest
+pub const NICE_RANGE: RangeInclusive<i32> = -20..=19;
+/// Levels of the real-time and best-effort I/O classes, 0 being the highest
+pub const IO_LEVELS: RangeInclusive<u8> = 0..=7;
+
+const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+const IOPRIO_CLASS_SHIFT: u32 = 13;
+const IOPRIO_CLASS_RT: libc::c_long = 1;
+const IOPRIO_CLASS_BE: libc::c_long = 2;
+const IOPRIO_CLASS_IDLE: libc::c_long = 3;
+
+/// I/O scheduling class, as in ionice(1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum IoClass {
+    /// Served before anything else; needs root
+    RealTime,
+    BestEffort,
+    /// Only served when no other process needs the disk
+    Idle,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IoPriority {
+    pub class: IoClass,
+    /// Level within the class, ignored for [`IoClass::Idle`]
+    pub level: u8,
+}
+
+impl IoPriority {
+    /// The priority the kernel derives from the nice value when none was set
+    pub fn from_nice(nice: i32) -> Self {
+        Self {
+            class: IoClass::BestEffort,
+            level: ((nice.clamp(*NICE_RANGE.start(), *NICE_RANGE.end()) + 20) / 5) as u8,
+        }
+    }
+
+    fn decode(raw: libc::c_long, nice: i32) -> Self {
+        let level = (raw & ((1 << IOPRIO_CLASS_SHIFT) - 1)) as u8;
+        let class = match raw >> IOPRIO_CLASS_SHIFT {
+            IOPRIO_CLASS_RT => IoClass::RealTime,
+            IOPRIO_CLASS_BE => IoClass::BestEffort,
+            IOPRIO_CLASS_IDLE => IoClass::Idle,
+            // IOPRIO_CLASS_NONE: follows the nice value
+            _ => return Self::from_nice(nice),
+        };
+
+        Self {
+            class,
+            level: level.min(*IO_LEVELS.end()),
+        }
+    }
+
+    fn encode(self) -> libc::c_long {
+        let (class, level) = match self.class {
+            IoClass::RealTime => (IOPRIO_CLASS_RT, self.level),
+            IoClass::BestEffort => (IOPRIO_CLASS_BE, self.level),
+            IoClass::Idle => (IOPRIO_CLASS_IDLE, 0),
+        };
+        class << IOPRIO_CLASS_SHIFT | libc::c_long::from(level.min(*IO_LEVELS.end()))
+    }
+}
+
+/// Something that can change scheduling priorities and CPU affinity
+pub trait Scheduler: Send + Sync {
+    fn set_nice(&self, pid: u32, nice: i32) -> ProcessResult<()>;
+    fn set_io_priority(&self, pid: u32, priority: IoPriority) -> ProcessResult<()>;
+    fn affinity(&self, pid: u32) -> ProcessResult<CpuMask>;
+    fn set_affinity(&self, pid: u32, mask: &CpuMask, all_threads: bool) -> ProcessResult<()>;
+}
+
+/// Changes real priorities with setpriority(2) and ioprio_set(2), and the
+/// affinity with sched_setaffinity(2)
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemScheduler;
+
+impl Scheduler for SystemScheduler {
+    fn set_nice(&self, pid: u32, nice: i32) -> ProcessResult<()> {
+        set_nice(pid, nice)
+    }
+
+    fn set_io_priority(&self, pid: u32, priority: IoPriority) -> ProcessResult<()> {
+        set_io_priority(pid, priority)
+    }
+
+    fn affinity(&self, pid: u32) -> ProcessResult<CpuMask> {
+        affinity::affinity(pid)
+    }
+
+    fn set_affinity(&self, pid: u32, mask: &CpuMask, all_threads: bool) -> ProcessResult<()> {
+        affinity::set_affinity(pid, mask, all_threads)
+    }
+}
+
+/// Nice value of a process (of its main thread)
+pub fn nice(pid: u32) -> ProcessResult<i32> {
+    // 0 would be this very process
+    process::nix_pid(pid)?;
+    // -1 is also a valid nice value, so errors are told apart by errno
+    Errno::clear();
+    let nice = unsafe { libc::getpriority(libc::PRIO_PROCESS, pid) };
+    match Errno::last() {
+        errno if nice == -1 && errno != Errno::UnknownErrno => Err(errno.into()),
+        _ => Ok(nice),
+    }
+}
+
+/// Set the nice value of every thread of a process.
+///
+/// Raising the priority (a lower nice value) needs `CAP_SYS_NICE`.
+pub fn set_nice(pid: u32, nice: i32) -> ProcessResult<()> {
+    let nice = nice.clamp(*NICE_RANGE.start(), *NICE_RANGE.end());
+    for_each_thread(pid, |tid| {
+        Errno::result(unsafe { libc::setpriority(libc::PRIO_PROCESS, tid, nice) }).map(drop)
+    })
+}
+
+/// I/O priority of a process (of its main thread), given its nice value
+pub fn io_priority(pid: u32, nice: i32) -> ProcessResult<IoPriority> {
+    let pid = process::nix_pid(pid)?.as_raw();
+    let raw =
+        Errno::result(unsafe { libc::syscall(libc::SYS_ioprio_get, IOPRIO_WHO_PROCESS, pid) })?;
+    Ok(IoPriority::decode(raw, nice))
+}
+
+/// Set the I/O priority of every thread of a process.
+///
+/// The real-time class needs `CAP_SYS_ADMIN`.
+pub fn set_io_priority(pid: u32, priority: IoPriority) -> ProcessResult<()> {
+    let raw = priority.encode();
+    for_each_thread(pid, |tid| {
+        Errno::result(unsafe {
+            libc::syscall(
+                libc::SYS_ioprio_set,
+                IOPRIO_WHO_PROCESS,
+                tid as libc::c_int,
+                raw,
+            )
+        })
+        .map(drop)
+    })
+}
+
+/// Run `apply` on each thread of `pid`, as listed in `/proc/<pid>/task`,
+/// see [`apply_to_threads`].
+pub(crate) fn for_each_thread(
+    pid: u32,
+    apply: impl Fn(u32) -> Result<(), Errno>,
+) -> ProcessResult<()> {
+    // 0 would be the calling thread
+    process::nix_pid(pid)?;
+    let tids = fs::read_dir(format!("/proc/{pid}/task"))
+        .into_iter()
+        .flatten()
+        .filter_map(|task| task.ok()?.file_name().to_str()?.parse::<u32>().ok());
+    apply_to_threads(pid, tids, apply)
+}
+
+/// Run `apply` on the main thread `pid`, then on each of `tids`. Threads
+/// that exit meanwhile are skipped, and a failure does not keep the other
+/// threads from being tried. When every thread failed the same way, that
+/// is the error; otherwise the failures are reported together.
+pub fn apply_to_threads(
+    pid: u32,
+    tids: impl IntoIterator<Item = u32>,
+    apply: impl Fn(u32) -> Result<(), Errno>,
+) -> ProcessResult<()> {
+    let mut failures: Vec<(u32, Errno)> = Vec::new();
+    match apply(pid) {
+        Ok(()) => {}
+        // Without the main thread there is no process
+        Err(Errno::ESRCH) => return Err(ProcessError::NotFound),
+        Err(errno) => failures.push((pid, errno)),
+    }
+
+    let mut threads = 1;
+    for tid in tids.into_iter().filter(|tid| *tid != pid) {
+        threads += 1;
+        match apply(tid) {
+            Ok(()) | Err(Errno::ESRCH) => {}
+            Err(errno) => failures.push((tid, errno)),
+        }
+    }
+
+    let Some(&(_, first)) = failures.first() else {
+        return Ok(());
+    };
+    if failures.len() == threads && failures.iter().all(|(_, errno)| *errno == first) {
+        return Err(first.into());
+    }
+
+    let mut errnos: Vec<Errno> = Vec::new();
+    for (_, errno) in &failures {
+        if !errnos.contains(errno) {
+            errnos.push(*errno);
+        }
+    }
+    let reasons: Vec<String> = errnos
+        .iter()
+        .map(|errno| {
+            let tids: Vec<String> = failures
+                .iter()
+                .filter(|(_, failed)| failed == errno)
+                .map(|(tid, _)| tid.to_string())
+                .collect();
+            format!("{} ({})", errno.desc(), tids.join(", "))
+        })
+        .collect();
+    Err(ProcessError::Unknown(format!(
+        "{} of {threads} threads failed: {}",
+        failures.len(),
+        reasons.join("; ")
+    )))
+}
@@ -1,8 +1,23 @@
 // SPDX-License-Identifier: MIT
 
+use crate::affinity::{self, CpuMask};
+use crate::backend::{self, ProcessBackend};
+use crate::cgroup::Limits;
+use crate::container::Container;
+use crate::files::{Holder, OpenFile};
+use crate::memory::{MemoryBreakdown, MemoryMetric};
+use crate::package::Package;
+use crate::priority::{self, IoPriority};
+use crate::query::Query;
+use crate::sockets::SocketInfo;
+use crate::systemd::Unit;
+use nix::errno::Errno;
 use nix::sys::signal::{self, Signal};
 use nix::unistd::Pid;
-use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 
 /// Result type for process operations with error context
 pub type ProcessResult<T> = Result<T, ProcessError>;
@@ -35,73 +50,341 @@ impl std::fmt::Display for ProcessError {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+/// Errors of system calls about a process
+impl From<Errno> for ProcessError {
+    fn from(errno: Errno) -> Self {
+        match errno {
+            Errno::EPERM | Errno::EACCES => ProcessError::PermissionDenied,
+            Errno::ESRCH => ProcessError::NotFound,
+            errno => ProcessError::Unknown(errno.desc().to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProcessInfo {
     pub pid: u32,
     pub name: String,
     pub cpu_usage: f32,
-    pub memory: u64,
+    /// Memory in bytes by the sampled [`MemoryMetric`], none when the
+    /// breakdown it comes from cannot be read
+    pub memory: Option<u64>,
     pub status: String,
     pub is_system: bool,
+    /// Parent process, if it is still running
+    pub parent_pid: Option<u32>,
+    /// Real user ID of the owner
+    pub uid: Option<u32>,
+    /// Start time in seconds since the epoch
+    pub start_time: u64,
+    /// Command line arguments, starting with the program
+    pub cmdline: Vec<String>,
+    /// The command line as searches look at it, see [`search_cmdline`].
+    /// Backends fill it in once per process; when left empty it is worked
+    /// out on every search instead.
+    #[serde(skip)]
+    pub search_cmdline: String,
+    /// Path of the executable, if it can be read
+    pub exe: Option<PathBuf>,
+    /// systemd unit it runs in, from its cgroup
+    pub unit: Option<Unit>,
+    /// cgroup of the application it belongs to, which is frozen as a whole
+    pub app_cgroup: Option<String>,
+    /// Whether that cgroup is frozen
+    pub frozen: bool,
+    /// Limits of the cgroup it is limited through, only read for the process
+    /// in the details panel
+    pub limits: Option<Limits>,
+    /// Flatpak, Snap or AppImage it belongs to
+    pub package: Option<Package>,
+    /// Docker or Podman container it runs in
+    pub container: Option<Container>,
+    /// Its PID namespace, when that is not the host's
+    pub pid_namespace: Option<u64>,
+    /// Nice value, from -20 (highest priority) to 19
+    pub nice: i32,
+    /// I/O scheduling priority, only read for the process whose details are
+    /// shown
+    pub io_priority: Option<IoPriority>,
+    /// Private, shared and swapped memory, when read
+    pub memory_breakdown: Option<MemoryBreakdown>,
+    /// Bytes per second read from storage since the previous sample
+    pub disk_read: u64,
+    /// Bytes per second written to storage since the previous sample
+    pub disk_write: u64,
+    /// Local TCP and UDP ports of its sockets, only sampled while a search
+    /// looks for ports
+    pub ports: Vec<u16>,
+    /// Which of the paths watched by a `file:` search it holds, only
+    /// sampled while there are some
+    pub held_paths: Vec<PathBuf>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A thread of a process, as listed in `/proc/<pid>/task`
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThreadInfo {
+    pub tid: u32,
+    pub name: String,
+    /// CPU usage of this thread alone, at most 100%
+    pub cpu_usage: f32,
+    pub status: String,
+    pub nice: i32,
+}
+
+/// Programs that run a script, module or archive given as an argument, so
+/// their name alone does not tell instances apart. Version suffixes such as
+/// `python3.12` are ignored.
+const INTERPRETERS: &[&str] = &[
+    "python", "node", "nodejs", "deno", "bun", "java", "ruby", "perl", "php", "lua", "bash",
+    "sh", "zsh", "dash", "fish", "dotnet", "mono", "gjs", "electron", "wine",
+];
+
+/// Interpreter options whose value is the next argument rather than the script
+const VALUE_OPTIONS: &[&str] = &[
+    "-cp", "-classpath", "--class-path", "-p", "--module-path", "-X", "-W", "-r", "--require",
+    "-I",
+];
+
+/// Identifies a process across refreshes, since PIDs get reused
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ProcessKey {
+    pub pid: u32,
+    pub start_time: u64,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum SortBy {
+    #[default]
     Cpu,
     Memory,
     Pid,
     Name,
+    /// Disk reads per second
+    #[serde(rename = "read")]
+    DiskRead,
+    /// Disk writes per second
+    #[serde(rename = "write")]
+    DiskWrite,
+}
+
+impl ProcessInfo {
+    pub fn key(&self) -> ProcessKey {
+        ProcessKey {
+            pid: self.pid,
+            start_time: self.start_time,
+        }
+    }
+
+    /// Case-insensitive match of the search query against name, PID,
+    /// command line, executable path, app ID or container name. `query` must
+    /// already be lowercase.
+    pub fn matches_search(&self, query: &str) -> bool {
+        self.name.to_lowercase().contains(query)
+            || self.pid.to_string().contains(query)
+            || self.lowercase_cmdline().contains(query)
+            || self
+                .exe
+                .as_ref()
+                .is_some_and(|exe| exe.to_string_lossy().to_lowercase().contains(query))
+            || self
+                .package
+                .as_ref()
+                .is_some_and(|package| package.app_id.to_lowercase().contains(query))
+            || self.container.as_ref().is_some_and(|container| {
+                container.label().to_lowercase().contains(query) || container.id.starts_with(query)
+            })
+    }
+
+    /// The command line joined by spaces and lowercased
+    pub fn lowercase_cmdline(&self) -> Cow<'_, str> {
+        if self.search_cmdline.is_empty() && !self.cmdline.is_empty() {
+            Cow::Owned(search_cmdline(&self.cmdline))
+        } else {
+            Cow::Borrowed(&self.search_cmdline)
+        }
+    }
+
+    /// Name to show in the list: the program followed by what sets this
+    /// instance apart, e.g. `python3 manage.py runserver` or
+    /// `java app.jar --port 80`, with paths shortened to their file name.
+    pub fn label(&self) -> String {
+        let Some((program, args)) = self.cmdline.split_first() else {
+            return self.name.clone();
+        };
+
+        // The kernel cuts names at 15 characters; the program path does not
+        let program_name = file_name(program);
+        let name = if program_name.len() > self.name.len() && program_name.starts_with(&self.name) {
+            program_name
+        } else {
+            &self.name
+        };
+
+        let base = name.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+        let args = if INTERPRETERS.contains(&base) {
+            script_args(args)
+        } else {
+            args
+        };
+
+        match args {
+            [] => name.to_string(),
+            [target, rest @ ..] => std::iter::once(file_name(target))
+                .chain(rest.iter().map(String::as_str))
+                .fold(name.to_string(), |label, arg| label + " " + arg),
+        }
+    }
+}
+
+/// Command line arguments joined by spaces and lowercased, for
+/// [`ProcessInfo::search_cmdline`]
+pub fn search_cmdline(cmdline: &[String]) -> String {
+    cmdline.join(" ").to_lowercase()
+}
+
+/// Arguments of an interpreter starting at the script it runs: the value of
+/// `-jar`, `-m`, `-c` or `-e`, or else the first argument that is not an option.
+fn script_args(args: &[String]) -> &[String] {
+    let mut index = 0;
+    while let Some(arg) = args.get(index) {
+        match arg.as_str() {
+            "-jar" | "-m" | "-c" | "-e" => return &args[(index + 1).min(args.len())..],
+            option if VALUE_OPTIONS.contains(&option) => index += 2,
+            option if option.starts_with('-') => index += 1,
+            _ => return &args[index..],
+        }
+    }
+    args
+}
+
+/// Last component of a path, or the whole argument if it is not a path
+fn file_name(arg: &str) -> &str {
+    if arg.contains('/') && !arg.contains(' ') {
+        Path::new(arg)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(arg)
+    } else {
+        arg
+    }
+}
+
+impl SortBy {
+    /// Parse a sort key as used on the command line (`cpu`, `mem`, `pid`,
+    /// `name`, `read`, `write`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "cpu" => Some(SortBy::Cpu),
+            "mem" | "memory" => Some(SortBy::Memory),
+            "pid" => Some(SortBy::Pid),
+            "name" => Some(SortBy::Name),
+            "read" | "disk-read" => Some(SortBy::DiskRead),
+            "write" | "disk-write" => Some(SortBy::DiskWrite),
+            _ => None,
+        }
+    }
+
+    /// Whether sorting needs disk I/O rates to be sampled
+    pub fn needs_disk_io(self) -> bool {
+        matches!(self, SortBy::DiskRead | SortBy::DiskWrite)
+    }
+}
+
+/// A disk throughput for display, e.g. `12 KB/s` or `3.4 MB/s`
+pub fn format_rate(bytes_per_second: u64) -> String {
+    const UNITS: [&str; 4] = ["B/s", "KB/s", "MB/s", "GB/s"];
+    let mut value = bytes_per_second as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit >= 2 && value < 10.0 {
+        format!("{value:.1} {}", UNITS[unit])
+    } else {
+        format!("{value:.0} {}", UNITS[unit])
+    }
+}
+
+/// Filter processes by the search box query (empty query keeps everything)
+pub fn filter_processes<'a>(processes: &'a [ProcessInfo], query: &Query) -> Vec<&'a ProcessInfo> {
+    if query.is_empty() {
+        processes.iter().collect()
+    } else {
+        processes.iter().filter(|p| query.matches(p)).collect()
+    }
+}
+
+/// Arrange processes as a tree for display, returning each entry with its depth.
+///
+/// Siblings keep their relative order from `processes`, so the current sort
+/// applies within each level. Processes whose parent is not in the list are
+/// shown as roots.
+pub fn tree_order<'a>(processes: &[&'a ProcessInfo]) -> Vec<(usize, &'a ProcessInfo)> {
+    let listed: HashSet<u32> = processes.iter().map(|p| p.pid).collect();
+    let mut children: HashMap<u32, Vec<&'a ProcessInfo>> = HashMap::new();
+    let mut roots = Vec::new();
+
+    for process in processes {
+        match process.parent_pid.filter(|ppid| listed.contains(ppid) && *ppid != process.pid) {
+            Some(ppid) => children.entry(ppid).or_default().push(process),
+            None => roots.push(*process),
+        }
+    }
+
+    let mut ordered = Vec::with_capacity(processes.len());
+    let mut stack: Vec<(usize, &'a ProcessInfo)> =
+        roots.into_iter().rev().map(|p| (0, p)).collect();
+    while let Some((depth, process)) = stack.pop() {
+        ordered.push((depth, process));
+        if let Some(kids) = children.remove(&process.pid) {
+            stack.extend(kids.into_iter().rev().map(|child| (depth + 1, child)));
+        }
+    }
+
+    ordered
+}
+
+/// Keep the processes of each container together, in the order of the
+/// first process of each container in `processes`, processes outside of
+/// containers first. The order within a container is kept.
+pub fn container_order<'a>(processes: &[&'a ProcessInfo]) -> Vec<&'a ProcessInfo> {
+    let mut groups: Vec<(Option<&str>, Vec<&'a ProcessInfo>)> = vec![(None, Vec::new())];
+    for process in processes {
+        let id = process.container.as_ref().map(|container| container.id.as_str());
+        match groups.iter_mut().find(|(group, _)| *group == id) {
+            Some((_, members)) => members.push(process),
+            None => groups.push((id, vec![process])),
+        }
+    }
+    groups.into_iter().flat_map(|(_, members)| members).collect()
 }
 
 pub struct ProcessManager {
-    system: System,
+    backend: Box<dyn ProcessBackend>,
 }
 
 impl ProcessManager {
     pub fn new() -> Self {
-        let mut system = System::new_all();
-        system.refresh_all();
-        Self { system }
+        Self::with_backend(backend::default_backend())
+    }
+
+    /// Read processes through a specific backend.
+    pub fn with_backend(mut backend: Box<dyn ProcessBackend>) -> Self {
+        // Take a first sample so that CPU usage is known on the next one
+        backend.sample();
+        Self { backend }
     }
 
-    pub fn refresh(&mut self) {
-        self.system.refresh_processes_specifics(
-            ProcessesToUpdate::All,
-            true,
-            ProcessRefreshKind::everything(),
-        );
+    /// Refresh and return every process, unsorted.
+    pub fn sample(&mut self) -> Vec<ProcessInfo> {
+        self.backend.sample()
     }
 
     pub fn get_processes(&mut self, sort_by: SortBy) -> Vec<ProcessInfo> {
-        self.refresh();
-        
-        let mut processes: Vec<ProcessInfo> = self
-            .system
-            .processes()
-            .iter()
-            .map(|(pid, process)| {
-                let name = process.name().to_string_lossy().to_string();
-                let is_system = is_system_service(&name) || is_critical_process(&name);
-                
-                ProcessInfo {
-                    pid: pid.as_u32(),
-                    name,
-                    cpu_usage: process.cpu_usage(),
-                    memory: process.memory(),
-                    status: format!("{:?}", process.status()),
-                    is_system,
-                }
-            })
-            .collect();
-
-        // Sort
-        match sort_by {
-            SortBy::Cpu => processes.sort_by(|a, b| b.cpu_usage.partial_cmp(&a.cpu_usage).unwrap()),
-            SortBy::Memory => processes.sort_by(|a, b| b.memory.cmp(&a.memory)),
-            SortBy::Pid => processes.sort_by(|a, b| a.pid.cmp(&b.pid)),
-            SortBy::Name => processes.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase())),
-        }
-        
+        let mut processes = self.sample();
+        sort_processes(&mut processes, sort_by);
         processes
     }
 
@@ -115,53 +398,201 @@ impl ProcessManager {
 
     #[allow(dead_code)]
     pub fn get_process_by_pid(&mut self, pid: u32) -> Option<ProcessInfo> {
-        self.refresh();
-        
-        self.system
-            .processes()
-            .iter()
-            .find(|(p, _)| p.as_u32() == pid)
-            .map(|(p, process)| {
-                let name = process.name().to_string_lossy().to_string();
-                let is_system = is_system_service(&name) || is_critical_process(&name);
-                
-                ProcessInfo {
-                    pid: p.as_u32(),
-                    name,
-                    cpu_usage: process.cpu_usage(),
-                    memory: process.memory(),
-                    status: format!("{:?}", process.status()),
-                    is_system,
-                }
-            })
+        self.backend.process(pid)
+    }
+
+    /// Report memory by `metric` from the next sample on.
+    pub fn set_memory_metric(&mut self, metric: MemoryMetric) {
+        self.backend.set_memory_metric(metric);
+    }
+
+    /// Measure disk reads and writes, or stop. Rates are measured from a
+    /// sample taken right away, so the next one already reports them.
+    pub fn set_disk_io(&mut self, enabled: bool) {
+        self.backend.set_disk_io(enabled);
+        if enabled {
+            self.backend.sample();
+        }
+    }
+
+    /// Find the local ports of every process from the next sample on, or stop.
+    pub fn set_ports(&mut self, enabled: bool) {
+        self.backend.set_ports(enabled);
+    }
+
+    /// Find which of `paths` every process holds from the next sample on,
+    /// or stop with none.
+    pub fn set_held_paths(&mut self, paths: Vec<PathBuf>) {
+        self.backend.set_held_paths(paths);
+    }
+
+    /// Files a process holds through descriptors, memory mappings, its
+    /// directories and executable
+    pub fn open_files(&mut self, pid: u32) -> Vec<OpenFile> {
+        self.backend.open_files(pid)
+    }
+
+    /// Processes holding `path`, or anything below it for a directory or
+    /// mount point
+    pub fn holders(&mut self, path: &Path) -> Vec<Holder> {
+        self.backend.holders(path)
+    }
+
+    /// TCP, UDP and Unix sockets a process has open
+    pub fn sockets(&mut self, pid: u32) -> Vec<SocketInfo> {
+        self.backend.sockets(pid)
+    }
+
+    /// Memory and CPU limits of the cgroup a process is limited through
+    pub fn limits(&mut self, pid: u32) -> Option<Limits> {
+        self.backend.limits(pid)
+    }
+
+    /// Private, shared and swapped memory of a process
+    pub fn memory_breakdown(&mut self, pid: u32) -> Option<MemoryBreakdown> {
+        self.backend.memory_breakdown(pid)
+    }
+
+    /// I/O scheduling priority of a process
+    pub fn io_priority(&mut self, pid: u32, nice: i32) -> Option<IoPriority> {
+        self.backend.io_priority(pid, nice)
+    }
+
+    /// Threads of a process. CPU usage is measured since the previous call
+    /// for the same process.
+    pub fn get_threads(&mut self, pid: u32, sort_by: SortBy) -> Vec<ThreadInfo> {
+        let mut threads = self.backend.threads(pid);
+        sort_threads(&mut threads, sort_by);
+        threads
+    }
+
+    /// Send a signal to one thread of a process
+    pub fn send_thread_signal(&self, pid: u32, tid: u32, signal: Signal) -> ProcessResult<()> {
+        SystemSignals.send_thread_signal(pid, tid, signal)
     }
 
     /// Check if killing a process is allowed
     pub fn can_kill_process(&self, process: &ProcessInfo) -> ProcessResult<()> {
-        // Check if process is a critical system process that should be protected
-        if is_critical_process(&process.name) {
-            return Err(ProcessError::Protected(process.name.clone()));
-        }
-        
-        Ok(())
+        can_kill_process(process)
     }
 
     pub fn kill_process(&self, pid: u32) -> ProcessResult<()> {
-        let nix_pid = Pid::from_raw(pid as i32);
-        
-        signal::kill(nix_pid, Signal::SIGTERM)
-            .map_err(|e| ProcessError::SignalFailed(e.to_string()))?;
-        
-        Ok(())
+        kill_process(pid)
     }
 
     pub fn force_kill_process(&self, pid: u32) -> ProcessResult<()> {
-        let nix_pid = Pid::from_raw(pid as i32);
-        
-        signal::kill(nix_pid, Signal::SIGKILL)
-            .map_err(|e| ProcessError::SignalFailed(e.to_string()))?;
-        
-        Ok(())
+        force_kill_process(pid)
+    }
+
+    /// Change the nice value of a process with setpriority(2)
+    pub fn set_nice(&self, pid: u32, nice: i32) -> ProcessResult<()> {
+        priority::set_nice(pid, nice)
+    }
+
+    /// Change the I/O class and level of a process with ioprio_set(2)
+    pub fn set_io_priority(&self, pid: u32, priority: IoPriority) -> ProcessResult<()> {
+        priority::set_io_priority(pid, priority)
+    }
+
+    /// Cores a process may run on, with sched_getaffinity(2)
+    pub fn affinity(&self, pid: u32) -> ProcessResult<CpuMask> {
+        affinity::affinity(pid)
+    }
+
+    /// Pin a process, or all of its threads, to the cores in `mask`
+    pub fn set_affinity(&self, pid: u32, mask: &CpuMask, all_threads: bool) -> ProcessResult<()> {
+        affinity::set_affinity(pid, mask, all_threads)
+    }
+}
+
+/// Sort processes in place, highest usage first for CPU and memory
+pub fn sort_processes(processes: &mut [ProcessInfo], sort_by: SortBy) {
+    match sort_by {
+        SortBy::Cpu => processes.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage)),
+        SortBy::Memory => processes.sort_by_key(|p| std::cmp::Reverse(p.memory)),
+        SortBy::Pid => processes.sort_by_key(|p| p.pid),
+        SortBy::Name => processes.sort_by_cached_key(|p| p.name.to_lowercase()),
+        SortBy::DiskRead => processes.sort_by_key(|p| std::cmp::Reverse(p.disk_read)),
+        SortBy::DiskWrite => processes.sort_by_key(|p| std::cmp::Reverse(p.disk_write)),
+    }
+}
+
+/// Sort threads in place like processes. Threads share the memory and disk
+/// counters of their process, so those keys sort by CPU usage.
+pub fn sort_threads(threads: &mut [ThreadInfo], sort_by: SortBy) {
+    match sort_by {
+        SortBy::Cpu | SortBy::Memory | SortBy::DiskRead | SortBy::DiskWrite => {
+            threads.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage))
+        }
+        SortBy::Pid => threads.sort_by_key(|t| t.tid),
+        SortBy::Name => threads.sort_by_cached_key(|t| t.name.to_lowercase()),
+    }
+}
+
+/// Check if killing a process is allowed
+pub fn can_kill_process(process: &ProcessInfo) -> ProcessResult<()> {
+    // Check if process is a critical system process that should be protected
+    if is_critical_process(&process.name) {
+        return Err(ProcessError::Protected(process.name.clone()));
+    }
+
+    Ok(())
+}
+
+/// Send SIGTERM to a process
+pub fn kill_process(pid: u32) -> ProcessResult<()> {
+    SystemSignals.send_signal(pid, Signal::SIGTERM)
+}
+
+/// Send SIGKILL to a process
+pub fn force_kill_process(pid: u32) -> ProcessResult<()> {
+    SystemSignals.send_signal(pid, Signal::SIGKILL)
+}
+
+/// Something that can deliver signals to processes
+pub trait SignalSender: Send + Sync {
+    fn send_signal(&self, pid: u32, signal: Signal) -> ProcessResult<()>;
+
+    /// Signal one thread of process `pid`. Stopping, continuing and killing
+    /// still affect the whole process; other signals reach that thread's
+    /// handler.
+    fn send_thread_signal(&self, pid: u32, tid: u32, signal: Signal) -> ProcessResult<()>;
+}
+
+/// A PID as system calls take it. There is no process 0, and PIDs above
+/// `i32::MAX` would turn negative, which kill(2) takes for process groups.
+pub fn nix_pid(pid: u32) -> ProcessResult<Pid> {
+    i32::try_from(pid)
+        .ok()
+        .filter(|pid| *pid > 0)
+        .map(Pid::from_raw)
+        .ok_or(ProcessError::NotFound)
+}
+
+fn signal_error(errno: Errno) -> ProcessError {
+    match errno {
+        Errno::EPERM | Errno::ESRCH => errno.into(),
+        errno => ProcessError::SignalFailed(errno.to_string()),
+    }
+}
+
+/// Delivers real signals with kill(2) and tgkill(2)
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemSignals;
+
+impl SignalSender for SystemSignals {
+    fn send_signal(&self, pid: u32, signal: Signal) -> ProcessResult<()> {
+        signal::kill(nix_pid(pid)?, signal).map_err(signal_error)
+    }
+
+    fn send_thread_signal(&self, pid: u32, tid: u32, signal: Signal) -> ProcessResult<()> {
+        let (pid, tid) = (nix_pid(pid)?.as_raw(), nix_pid(tid)?.as_raw());
+        // tgkill checks that the thread still belongs to the process
+        Errno::result(unsafe {
+            libc::syscall(libc::SYS_tgkill, pid, tid, signal as libc::c_int)
+        })
+        .map(drop)
+        .map_err(signal_error)
     }
 }
 
@@ -171,6 +602,11 @@ impl Default for ProcessManager {
     }
 }
 
+/// Whether a process is a system service or a protected kernel/system process
+pub(crate) fn is_system_process(name: &str) -> bool {
+    is_system_service(name) || is_critical_process(name)
+}
+
 /// Check if a process name matches known system services
 fn is_system_service(name: &str) -> bool {
     let system_services = [
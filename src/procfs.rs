@@ -0,0 +1,653 @@
+// SPDX-License-Identifier: MIT
+
+//! Lean process backend reading /proc directly.
+//!
+//! A refresh reads `/proc/<pid>/stat`, which holds everything the list
+//! shows, and `/proc/<pid>/cgroup`, as processes are moved into other
+//! cgroups, and so other systemd units and app cgroups, while they run.
+//! Data that does not change while a program runs (owner, command line,
+//! executable, package, container, PID namespace) is read once and cached
+//! per PID and start time, so a recycled PID is read afresh. An `exec` keeps
+//! the PID and start time; it is noticed by the name changing, which has the
+//! cached data read again, but not when the new program has the same name as
+//! the old one.
+//! The I/O priority has no file and is asked from the kernel, only for the
+//! process whose details are shown. Unless memory is measured as RSS,
+//! `smaps_rollup` is read as well, `io` when disk I/O is measured, the
+//! descriptors in `fd` when ports are looked for, and `maps` too when a
+//! search looks for who holds a file. Whether an app is frozen is
+//! read from its cgroup, once per app, and limits only when asked for. The
+//! Flatpak instances of the user are listed on every refresh as well. The
+//! names of containers are asked from their runtime when a container is
+//! first seen.
+
+use crate::backend::ProcessBackend;
+use crate::cgroup::{self, Limits};
+use crate::container::{self, Container, RuntimeSockets};
+use crate::files::{self, Holder, OpenFile};
+use crate::memory::{self, MemoryBreakdown, MemoryMetric};
+use crate::package::{self, Package};
+use crate::priority::{self, IoPriority};
+use crate::process::{self, is_system_process, ProcessInfo, ThreadInfo};
+use crate::sockets::{self, SocketInfo};
+use crate::systemd::{self, Unit};
+use nix::unistd::{sysconf, SysconfVar};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Values assumed when sysconf cannot tell
+const DEFAULT_TICKS_PER_SECOND: u64 = 100;
+const DEFAULT_PAGE_SIZE: u64 = 4096;
+
+pub struct ProcfsBackend {
+    /// Mount point of procfs, `/proc` outside of tests and benchmarks
+    root: PathBuf,
+    /// Mount point of the cgroup v2 hierarchy
+    cgroup_root: PathBuf,
+    /// Flatpak's instance directories, naming the app of each `bwrap`
+    flatpak_dir: Option<PathBuf>,
+    /// API sockets of the container runtimes, asked for container names
+    container_sockets: RuntimeSockets,
+    /// Names of the containers seen in the previous refresh, by ID
+    container_names: HashMap<String, Option<String>>,
+    /// PID namespace of the host, that processes outside of containers share
+    host_pid_namespace: Option<u64>,
+    /// Whether `root` is the procfs of this system, so its PIDs can be
+    /// passed to system calls
+    live: bool,
+    /// Kernel clock ticks per second, the unit of CPU times in `stat`
+    ticks_per_second: u64,
+    page_size: u64,
+    /// Boot time in seconds since the epoch
+    boot_time: u64,
+    /// Upper bound for CPU usage: 100% per core
+    max_cpu_usage: f32,
+    /// What [`ProcessInfo::memory`] reports
+    memory_metric: MemoryMetric,
+    /// Whether `io` is read for disk I/O rates
+    disk_io: bool,
+    /// Whether the local ports of processes are looked up
+    ports: bool,
+    /// Paths looked for among the files held by every process
+    held_paths: Vec<PathBuf>,
+    /// Processes seen in the previous refresh
+    entries: HashMap<u32, Entry>,
+    last_refresh: Option<Instant>,
+    /// Process whose threads were read last, and when
+    threads_of: Option<(u32, Instant)>,
+    /// Start and CPU ticks of those threads, by thread ID
+    thread_ticks: HashMap<u32, (u64, u64)>,
+    /// Reused for reading each `stat` file
+    buffer: String,
+    /// Reused for reading each `io` file
+    io_buffer: String,
+    /// Reused for reading each `cgroup` file
+    cgroup_buffer: String,
+}
+
+/// What is remembered about a process between refreshes
+#[derive(Clone)]
+struct Entry {
+    start_ticks: u64,
+    cpu_ticks: u64,
+    /// Bytes read from and written to storage so far, if measured
+    io_bytes: Option<(u64, u64)>,
+    fixed: FixedInfo,
+}
+
+/// Data read once per process
+#[derive(Clone)]
+struct FixedInfo {
+    name: String,
+    is_system: bool,
+    uid: Option<u32>,
+    cmdline: Vec<String>,
+    search_cmdline: String,
+    exe: Option<PathBuf>,
+    /// Path of its cgroup, below the cgroup root
+    cgroup: Option<String>,
+    unit: Option<Unit>,
+    app_cgroup: Option<String>,
+    package: Option<Package>,
+    container: Option<Container>,
+    pid_namespace: Option<u64>,
+}
+
+/// The fields of `/proc/<pid>/stat` used by the process list
+struct Stat<'a> {
+    name: &'a str,
+    state: char,
+    parent_pid: u32,
+    cpu_ticks: u64,
+    nice: i32,
+    start_ticks: u64,
+    rss_pages: u64,
+}
+
+impl ProcfsBackend {
+    pub fn new() -> Self {
+        Self::with_root("/proc")
+    }
+
+    /// Read processes from a procfs mounted at, or copied to, `root`.
+    pub fn with_root(root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        let ticks_per_second = sysconf(SysconfVar::CLK_TCK)
+            .ok()
+            .flatten()
+            .and_then(|ticks| u64::try_from(ticks).ok())
+            .filter(|ticks| *ticks > 0)
+            .unwrap_or(DEFAULT_TICKS_PER_SECOND);
+        let page_size = sysconf(SysconfVar::PAGE_SIZE)
+            .ok()
+            .flatten()
+            .and_then(|size| u64::try_from(size).ok())
+            .unwrap_or(DEFAULT_PAGE_SIZE);
+        let cores = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let live = root == Path::new("/proc");
+        // Our own namespace is the host's unless we run in a container
+        // ourselves; that of PID 1 is only readable by root
+        let host_pid_namespace = container::pid_namespace(&root.join("self"))
+            .or_else(|| container::pid_namespace(&root.join("1")));
+
+        Self {
+            boot_time: read_boot_time(&root).unwrap_or(0),
+            live,
+            root,
+            cgroup_root: PathBuf::from(cgroup::CGROUP_ROOT),
+            flatpak_dir: live.then(package::flatpak_instance_dir).flatten(),
+            container_sockets: if live {
+                RuntimeSockets::new()
+            } else {
+                RuntimeSockets::default()
+            },
+            container_names: HashMap::new(),
+            host_pid_namespace,
+            ticks_per_second,
+            page_size,
+            max_cpu_usage: cores as f32 * 100.0,
+            memory_metric: MemoryMetric::default(),
+            disk_io: false,
+            ports: false,
+            held_paths: Vec::new(),
+            entries: HashMap::new(),
+            last_refresh: None,
+            threads_of: None,
+            thread_ticks: HashMap::new(),
+            buffer: String::with_capacity(512),
+            io_buffer: String::with_capacity(256),
+            cgroup_buffer: String::with_capacity(256),
+        }
+    }
+
+    /// Read cgroups from a hierarchy mounted at, or copied to, `root`.
+    pub fn with_cgroup_root(mut self, root: impl Into<PathBuf>) -> Self {
+        self.cgroup_root = root.into();
+        self
+    }
+
+    /// Find the launchers of Flatpak instances in `dir` rather than in this
+    /// user's runtime directory.
+    pub fn with_flatpak_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.flatpak_dir = Some(dir.into());
+        self
+    }
+
+    /// Ask the container runtimes listening on `sockets` for container names.
+    pub fn with_container_sockets(mut self, sockets: RuntimeSockets) -> Self {
+        self.container_sockets = sockets;
+        self
+    }
+
+    /// Read one process, reusing what the previous refresh knew about it.
+    fn read_process(
+        &mut self,
+        pid: u32,
+        previous: &mut HashMap<u32, Entry>,
+        elapsed_ticks: Option<f32>,
+        socket_table: Option<&HashMap<u64, SocketInfo>>,
+    ) -> Option<ProcessInfo> {
+        let dir = self.root.join(pid.to_string());
+
+        self.buffer.clear();
+        File::open(dir.join("stat"))
+            .and_then(|mut file| file.read_to_string(&mut self.buffer))
+            .ok()?;
+        let stat = parse_stat(&self.buffer)?;
+
+        let (fixed, cpu_usage, previous_io) = match previous.remove(&pid) {
+            Some(entry) if entry.start_ticks == stat.start_ticks => {
+                let used = stat.cpu_ticks.saturating_sub(entry.cpu_ticks) as f32;
+                let cpu_usage = elapsed_ticks
+                    .map_or(0.0, |elapsed| (used / elapsed * 100.0).min(self.max_cpu_usage));
+                // The same process, running another program after an `exec`
+                let fixed = if entry.fixed.name == stat.name {
+                    let mut fixed = entry.fixed;
+                    // Launchers and systemd move running processes into
+                    // other cgroups, and so other units
+                    self.cgroup_buffer.clear();
+                    if File::open(dir.join("cgroup"))
+                        .and_then(|mut file| file.read_to_string(&mut self.cgroup_buffer))
+                        .is_ok()
+                    {
+                        fixed.set_cgroup(systemd::parse_cgroup(&self.cgroup_buffer));
+                    }
+                    fixed
+                } else {
+                    read_fixed_info(&dir, stat.name)
+                };
+                (fixed, cpu_usage, entry.io_bytes)
+            }
+            _ => (read_fixed_info(&dir, stat.name), 0.0, None),
+        };
+
+        // Only readable for processes that could be traced
+        let io_bytes = self.disk_io.then(|| {
+            self.io_buffer.clear();
+            File::open(dir.join("io"))
+                .and_then(|mut file| file.read_to_string(&mut self.io_buffer))
+                .ok()
+                .and_then(|_| parse_io(&self.io_buffer))
+        });
+        let io_bytes = io_bytes.flatten();
+        let (disk_read, disk_write) = match (previous_io, io_bytes, elapsed_ticks) {
+            (Some((read, written)), Some((now_read, now_written)), Some(elapsed)) => {
+                let seconds = elapsed / self.ticks_per_second as f32;
+                let rate = |bytes: u64| (bytes as f32 / seconds) as u64;
+                (
+                    rate(now_read.saturating_sub(read)),
+                    rate(now_written.saturating_sub(written)),
+                )
+            }
+            _ => (0, 0),
+        };
+
+        let rss = stat.rss_pages * self.page_size;
+        let memory_breakdown = match self.memory_metric {
+            MemoryMetric::Rss => None,
+            _ => memory::smaps_rollup(&self.root, pid),
+        };
+
+        let held_paths = if self.held_paths.is_empty() {
+            Vec::new()
+        } else {
+            let open_files = files::open_files(&self.root, pid);
+            self.held_paths
+                .iter()
+                .filter(|path| open_files.iter().any(|file| files::is_under(&file.path, path)))
+                .cloned()
+                .collect()
+        };
+
+        let process = ProcessInfo {
+            pid,
+            name: fixed.name.clone(),
+            cpu_usage,
+            memory: match self.memory_metric {
+                MemoryMetric::Rss => Some(rss),
+                metric => memory_breakdown.map(|breakdown| breakdown.get(metric)),
+            },
+            status: state_name(stat.state).to_string(),
+            is_system: fixed.is_system,
+            parent_pid: Some(stat.parent_pid).filter(|ppid| *ppid != 0),
+            uid: fixed.uid,
+            start_time: self.boot_time + stat.start_ticks / self.ticks_per_second,
+            cmdline: fixed.cmdline.clone(),
+            search_cmdline: fixed.search_cmdline.clone(),
+            exe: fixed.exe.clone(),
+            unit: fixed.unit.clone(),
+            app_cgroup: fixed.app_cgroup.clone(),
+            frozen: false,
+            limits: None,
+            package: fixed.package.clone(),
+            container: fixed.container.clone(),
+            pid_namespace: fixed
+                .pid_namespace
+                .filter(|namespace| Some(*namespace) != self.host_pid_namespace),
+            nice: stat.nice,
+            io_priority: None,
+            memory_breakdown,
+            disk_read,
+            disk_write,
+            ports: socket_table
+                .map(|table| sockets::local_ports(table, &sockets::socket_inodes(&self.root, pid)))
+                .unwrap_or_default(),
+            held_paths,
+        };
+
+        self.entries.insert(
+            pid,
+            Entry {
+                start_ticks: stat.start_ticks,
+                cpu_ticks: stat.cpu_ticks,
+                io_bytes,
+                fixed,
+            },
+        );
+
+        Some(process)
+    }
+
+    /// Fill in the names of containers, asking each runtime once for all of
+    /// its containers when one of them was not seen before.
+    fn name_containers(&mut self, processes: &mut [ProcessInfo]) {
+        let mut names: HashMap<String, Option<String>> = HashMap::new();
+        for process in processes.iter_mut() {
+            let Some(container) = &mut process.container else {
+                continue;
+            };
+            if !names.contains_key(&container.id) {
+                let name = match self.container_names.remove(&container.id) {
+                    Some(name) => name,
+                    None => {
+                        let mut listed = self.container_sockets.names(container.runtime);
+                        let name = listed.remove(&container.id);
+                        // Containers of this runtime that come up further on
+                        self.container_names
+                            .extend(listed.into_iter().map(|(id, name)| (id, Some(name))));
+                        name
+                    }
+                };
+                names.insert(container.id.clone(), name);
+            }
+            container.name = names[&container.id].clone();
+        }
+        self.container_names = names;
+    }
+
+    /// Clock ticks since the previous refresh, if there was one
+    fn ticks_since_refresh(&self, now: Instant) -> Option<f32> {
+        self.last_refresh
+            .map(|last| now.duration_since(last).as_secs_f32() * self.ticks_per_second as f32)
+            .filter(|ticks| *ticks > 0.0)
+    }
+}
+
+impl Default for ProcfsBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProcessBackend for ProcfsBackend {
+    fn sample(&mut self) -> Vec<ProcessInfo> {
+        let now = Instant::now();
+        let elapsed_ticks = self.ticks_since_refresh(now);
+        self.last_refresh = Some(now);
+
+        // Entries left in `previous` afterwards belong to exited processes
+        let mut previous = std::mem::take(&mut self.entries);
+        let Ok(dir) = fs::read_dir(&self.root) else {
+            return Vec::new();
+        };
+
+        let socket_table = self.ports.then(|| sockets::socket_table(&self.root, false));
+        let mut processes: Vec<ProcessInfo> = dir
+            .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<u32>().ok())
+            .filter_map(|pid| {
+                self.read_process(pid, &mut previous, elapsed_ticks, socket_table.as_ref())
+            })
+            .collect();
+
+        // Apps usually have several processes, their cgroup is read once
+        let mut frozen: HashMap<String, bool> = HashMap::new();
+        let mut launchers = self
+            .flatpak_dir
+            .as_deref()
+            .map(package::flatpak_launchers)
+            .unwrap_or_default();
+        for process in &mut processes {
+            if process.package.is_none() {
+                process.package = launchers.remove(&process.pid);
+            }
+            if let Some(app) = &process.app_cgroup {
+                process.frozen = *frozen
+                    .entry(app.clone())
+                    .or_insert_with(|| cgroup::is_frozen(&self.cgroup_root, app));
+            }
+        }
+        self.name_containers(&mut processes);
+        processes
+    }
+
+    /// Read one process, measuring its usage since the previous refresh.
+    /// That refresh stays the baseline of the next one, and ports are not
+    /// looked up.
+    fn process(&mut self, pid: u32) -> Option<ProcessInfo> {
+        let elapsed_ticks = self.ticks_since_refresh(Instant::now());
+        let baseline = self.entries.get(&pid).cloned();
+        let mut previous: HashMap<u32, Entry> =
+            baseline.clone().map(|entry| (pid, entry)).into_iter().collect();
+        let process = self.read_process(pid, &mut previous, elapsed_ticks, None);
+        match baseline {
+            Some(entry) => self.entries.insert(pid, entry),
+            None => self.entries.remove(&pid),
+        };
+
+        let mut process = process?;
+        if process.package.is_none() {
+            process.package = self
+                .flatpak_dir
+                .as_deref()
+                .map(package::flatpak_launchers)
+                .and_then(|mut launchers| launchers.remove(&pid));
+        }
+        if let Some(app) = &process.app_cgroup {
+            process.frozen = cgroup::is_frozen(&self.cgroup_root, app);
+        }
+        // Names as of the previous refresh, without asking the runtime
+        if let Some(container) = &mut process.container {
+            container.name = self.container_names.get(&container.id).cloned().flatten();
+        }
+        Some(process)
+    }
+
+    fn set_memory_metric(&mut self, metric: MemoryMetric) {
+        self.memory_metric = metric;
+    }
+
+    fn set_disk_io(&mut self, enabled: bool) {
+        self.disk_io = enabled;
+    }
+
+    fn set_ports(&mut self, enabled: bool) {
+        self.ports = enabled;
+    }
+
+    fn set_held_paths(&mut self, paths: Vec<PathBuf>) {
+        self.held_paths = paths;
+    }
+
+    fn open_files(&mut self, pid: u32) -> Vec<OpenFile> {
+        files::open_files(&self.root, pid)
+    }
+
+    fn holders(&mut self, path: &Path) -> Vec<Holder> {
+        files::holders(&self.root, path)
+    }
+
+    fn sockets(&mut self, pid: u32) -> Vec<SocketInfo> {
+        sockets::process_sockets(&self.root, pid)
+    }
+
+    fn memory_breakdown(&mut self, pid: u32) -> Option<MemoryBreakdown> {
+        memory::smaps_rollup(&self.root, pid)
+    }
+
+    fn io_priority(&mut self, pid: u32, nice: i32) -> Option<IoPriority> {
+        self.live
+            .then(|| priority::io_priority(pid, nice).ok())
+            .flatten()
+    }
+
+    fn limits(&mut self, pid: u32) -> Option<Limits> {
+        let cgroup = self.entries.get(&pid)?.fixed.cgroup.as_deref()?;
+        cgroup::read_limits(&self.cgroup_root, cgroup::limit_cgroup(cgroup)?)
+    }
+
+    fn threads(&mut self, pid: u32) -> Vec<ThreadInfo> {
+        let now = Instant::now();
+        // Usage is only known when the same process was read before
+        let (elapsed_ticks, mut previous) = match self.threads_of {
+            Some((watched, last)) if watched == pid => (
+                Some(now.duration_since(last).as_secs_f32() * self.ticks_per_second as f32)
+                    .filter(|ticks| *ticks > 0.0),
+                std::mem::take(&mut self.thread_ticks),
+            ),
+            _ => (None, HashMap::new()),
+        };
+        self.threads_of = Some((pid, now));
+        self.thread_ticks.clear();
+
+        let task_dir = self.root.join(pid.to_string()).join("task");
+        let Ok(dir) = fs::read_dir(&task_dir) else {
+            return Vec::new();
+        };
+
+        let mut threads = Vec::new();
+        for tid in dir.filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<u32>().ok()) {
+            self.buffer.clear();
+            let read = File::open(task_dir.join(tid.to_string()).join("stat"))
+                .and_then(|mut file| file.read_to_string(&mut self.buffer));
+            let Some(stat) = read.ok().and_then(|_| parse_stat(&self.buffer)) else {
+                continue;
+            };
+
+            // A single thread runs on one core at a time
+            let cpu_usage = match (previous.remove(&tid), elapsed_ticks) {
+                (Some((start, used)), Some(elapsed)) if start == stat.start_ticks => {
+                    (stat.cpu_ticks.saturating_sub(used) as f32 / elapsed * 100.0).min(100.0)
+                }
+                _ => 0.0,
+            };
+            self.thread_ticks.insert(tid, (stat.start_ticks, stat.cpu_ticks));
+
+            threads.push(ThreadInfo {
+                tid,
+                name: stat.name.to_string(),
+                cpu_usage,
+                status: state_name(stat.state).to_string(),
+                nice: stat.nice,
+            });
+        }
+        threads
+    }
+}
+
+/// Parse `/proc/<pid>/stat`.
+///
+/// The name is enclosed in parentheses and may itself contain spaces and
+/// parentheses, so the remaining fields start after the last `)`.
+fn parse_stat(content: &str) -> Option<Stat<'_>> {
+    let open = content.find('(')?;
+    let close = content.rfind(')')?;
+    let name = content.get(open + 1..close)?;
+    // Fields numbered as in proc(5), starting with field 3 (state)
+    let fields: Vec<&str> = content.get(close + 1..)?.split_whitespace().collect();
+    let field = |number: usize| fields.get(number - 3).copied();
+    let number = |n: usize| field(n)?.parse::<u64>().ok();
+
+    Some(Stat {
+        name,
+        state: field(3)?.chars().next()?,
+        parent_pid: field(4)?.parse().ok()?,
+        cpu_ticks: number(14)? + number(15)?,
+        nice: field(19)?.parse().ok()?,
+        start_ticks: number(22)?,
+        rss_pages: number(24)?,
+    })
+}
+
+/// Parse `/proc/<pid>/io` into the bytes read from and written to storage.
+/// `rchar` and `wchar` also count reads served from the page cache, pipes
+/// and sockets, so they are not used.
+fn parse_io(content: &str) -> Option<(u64, u64)> {
+    let field = |name: &str| {
+        content.lines().find_map(|line| {
+            line.strip_prefix(name)?
+                .strip_prefix(':')?
+                .trim()
+                .parse::<u64>()
+                .ok()
+        })
+    };
+    Some((field("read_bytes")?, field("write_bytes")?))
+}
+
+fn read_fixed_info(dir: &Path, name: &str) -> FixedInfo {
+    let uid = fs::read_to_string(dir.join("status")).ok().and_then(|status| {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix("Uid:"))
+            .and_then(|ids| ids.split_whitespace().next()?.parse().ok())
+    });
+    let cmdline: Vec<String> = fs::read(dir.join("cmdline"))
+        .map(|raw| {
+            raw.split(|byte| *byte == 0)
+                .filter(|arg| !arg.is_empty())
+                .map(|arg| String::from_utf8_lossy(arg).into_owned())
+                .collect()
+        })
+        .unwrap_or_default();
+    let exe = fs::read_link(dir.join("exe")).ok();
+    let cgroup_file = fs::read_to_string(dir.join("cgroup")).unwrap_or_default();
+    let cgroup_path = systemd::parse_cgroup(&cgroup_file);
+
+    FixedInfo {
+        name: name.to_string(),
+        is_system: is_system_process(name),
+        uid,
+        search_cmdline: process::search_cmdline(&cmdline),
+        cmdline,
+        package: package::detect(dir, exe.as_deref(), cgroup_path),
+        exe,
+        cgroup: cgroup_path.map(str::to_string),
+        unit: cgroup_path.and_then(systemd::unit_of),
+        app_cgroup: cgroup_path.and_then(cgroup::app_cgroup).map(str::to_string),
+        container: cgroup_path.and_then(container::container_of),
+        pid_namespace: container::pid_namespace(dir),
+    }
+}
+
+impl FixedInfo {
+    /// Follow the process into the cgroup at `path`.
+    fn set_cgroup(&mut self, path: Option<&str>) {
+        if self.cgroup.as_deref() == path {
+            return;
+        }
+        self.cgroup = path.map(str::to_string);
+        self.unit = path.and_then(systemd::unit_of);
+        self.app_cgroup = path.and_then(cgroup::app_cgroup).map(str::to_string);
+    }
+}
+
+fn read_boot_time(root: &Path) -> Option<u64> {
+    fs::read_to_string(root.join("stat"))
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("btime "))?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Status names, matching how the sysinfo backend reports them
+fn state_name(state: char) -> &'static str {
+    match state {
+        'R' => "Run",
+        'S' => "Sleep",
+        'D' => "UninterruptibleDiskSleep",
+        'Z' => "Zombie",
+        'T' => "Stop",
+        't' => "Tracing",
+        'X' | 'x' => "Dead",
+        'K' => "Wakekill",
+        'W' => "Waking",
+        'P' => "Parked",
+        'I' => "Idle",
+        _ => "Unknown",
+    }
+}
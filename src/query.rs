@@ -0,0 +1,461 @@
+// SPDX-License-Identifier: MIT
+
+//! Query language of the search box.
+//!
+//! A query is a list of terms separated by spaces, and a process has to match
+//! all of them. Plain words match the name, PID, command line, executable
+//! path or app ID; `field:value` and `field>value` terms filter on one property:
+//!
+//! | Term | Matches |
+//! | --- | --- |
+//! | `cpu>50`, `cpu<=5%` | CPU usage in percent |
+//! | `mem>1G`, `mem<200M` | Memory, in `K`, `M`, `G` or `T` (MB without a unit) |
+//! | `pid:1234`, `pid>1000` | Process ID |
+//! | `user:me`, `user:root`, `user:1000` | Owner |
+//! | `name:fire`, `name:/fire.*/` | Name, as text or as a `/regex/` |
+//! | `cmd:--headless` | Command line, as text or as a `/regex/` |
+//! | `state:zombie` | Status: `running`, `sleeping`, `disk`, `zombie`, `stopped` or `idle` |
+//! | `port:8080` | Has a TCP or UDP socket on this local port |
+//! | `file:/mnt/usb` | Holds this file, or anything below this directory or mount point |
+//!
+//! Text is matched case-insensitively. Values containing spaces can be
+//! quoted, as in `cmd:"--profile work"`.
+
+use crate::process::ProcessInfo;
+use nix::unistd::{Uid, User};
+use regex::{Regex, RegexBuilder};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+
+/// UIDs of the user names looked up so far, `None` for unknown names. The
+/// search box parses its text again on every keystroke, and a lookup can
+/// go through NSS to a directory service.
+static USER_IDS: LazyLock<Mutex<HashMap<String, Option<u32>>>> = LazyLock::new(Mutex::default);
+
+/// A parsed search query
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    source: String,
+    terms: Vec<Term>,
+    /// Finds the text the query looks for, to highlight it
+    highlight: Option<Regex>,
+}
+
+/// Why a query could not be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryError {
+    /// `field:` with a field that does not exist
+    UnknownField(String),
+    /// `field:` without a value
+    MissingValue(String),
+    /// `<` or `>` on a field that cannot be compared, such as `name`
+    NotComparable(String),
+    InvalidNumber(String),
+    InvalidSize(String),
+    /// The pattern and the error reported by the regex engine
+    InvalidRegex(String, String),
+    UnknownUser(String),
+    UnknownState(String),
+    /// `file:` with a relative path
+    InvalidPath(String),
+    UnclosedQuote,
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryError::UnknownField(field) => write!(f, "unknown filter: {field}"),
+            QueryError::MissingValue(field) => write!(f, "missing value for {field}"),
+            QueryError::NotComparable(field) => write!(f, "{field} cannot be compared with < or >"),
+            QueryError::InvalidNumber(value) => write!(f, "invalid number: {value}"),
+            QueryError::InvalidSize(value) => write!(f, "invalid size: {value}"),
+            QueryError::InvalidRegex(pattern, error) => {
+                write!(f, "invalid regular expression /{pattern}/: {error}")
+            }
+            QueryError::UnknownUser(user) => write!(f, "unknown user: {user}"),
+            QueryError::UnknownState(state) => write!(f, "unknown state: {state}"),
+            QueryError::InvalidPath(path) => write!(f, "not an absolute path: {path}"),
+            QueryError::UnclosedQuote => write!(f, "unclosed quote"),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+#[derive(Debug, Clone)]
+enum Term {
+    /// Plain word, matched against name and PID (lowercase)
+    Text(String),
+    Cpu(Comparison, f32),
+    Memory(Comparison, u64),
+    Pid(Comparison, u32),
+    User(u32),
+    Name(Pattern),
+    Command(Pattern),
+    /// Status as reported in [`ProcessInfo::status`]
+    State(&'static str),
+    /// Local port, see [`ProcessInfo::ports`]
+    Port(u16),
+    /// Held file or directory, see [`ProcessInfo::held_paths`]
+    File(PathBuf),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+#[derive(Debug, Clone)]
+enum Pattern {
+    /// Lowercase text to look for
+    Text(String),
+    Regex(Regex),
+}
+
+/// Names accepted by `state:`, and the status they stand for
+const STATES: &[(&[&str], &str)] = &[
+    (&["r", "run", "running"], "Run"),
+    (&["s", "sleep", "sleeping"], "Sleep"),
+    (&["d", "disk"], "UninterruptibleDiskSleep"),
+    (&["z", "zombie"], "Zombie"),
+    (&["t", "stop", "stopped"], "Stop"),
+    (&["i", "idle"], "Idle"),
+];
+
+impl Query {
+    pub fn parse(source: &str) -> Result<Self, QueryError> {
+        let terms = tokenize(source)?
+            .into_iter()
+            .map(|(token, quoted)| {
+                if quoted {
+                    Ok(Term::Text(token.to_lowercase()))
+                } else {
+                    parse_term(&token)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            source: source.to_string(),
+            highlight: highlighter(&terms),
+            terms,
+        })
+    }
+
+    /// The text the query was parsed from
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether the query matches every process
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn matches(&self, process: &ProcessInfo) -> bool {
+        self.terms.iter().all(|term| term.matches(process))
+    }
+
+    /// Whether the query looks at [`ProcessInfo::ports`], which are only
+    /// sampled on demand
+    pub fn needs_ports(&self) -> bool {
+        self.terms.iter().any(|term| matches!(term, Term::Port(_)))
+    }
+
+    /// Paths the query looks for in [`ProcessInfo::held_paths`], which are
+    /// only sampled on demand
+    pub fn held_paths(&self) -> Vec<PathBuf> {
+        self.terms
+            .iter()
+            .filter_map(|term| match term {
+                Term::File(path) => Some(path.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Byte ranges of `text` matching the plain words, `name:` or `cmd:`
+    /// terms of the query.
+    pub fn highlights(&self, text: &str) -> Vec<Range<usize>> {
+        let Some(highlight) = &self.highlight else {
+            return Vec::new();
+        };
+
+        highlight
+            .find_iter(text)
+            .map(|found| found.range())
+            .filter(|range| !range.is_empty())
+            .collect()
+    }
+}
+
+/// Where the searches saved from the search box are kept
+pub trait SearchStore: Send + Sync {
+    /// Replace the saved searches with `searches`.
+    fn save(&self, searches: &[String]) -> Result<(), String>;
+}
+
+/// Queries are equal when they were written the same way.
+impl PartialEq for Query {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Term {
+    fn matches(&self, process: &ProcessInfo) -> bool {
+        match self {
+            Term::Text(text) => process.matches_search(text),
+            Term::Cpu(comparison, bound) => comparison.holds(process.cpu_usage, *bound),
+            Term::Memory(comparison, bound) => process
+                .memory
+                .is_some_and(|memory| comparison.holds(memory, *bound)),
+            Term::Pid(comparison, bound) => comparison.holds(process.pid, *bound),
+            Term::User(uid) => process.uid == Some(*uid),
+            Term::Name(pattern) => pattern.is_match(&process.name),
+            Term::Command(pattern) => pattern.is_match_lowercase(&process.lowercase_cmdline()),
+            Term::State(state) => process.status == *state,
+            Term::Port(port) => process.ports.contains(port),
+            Term::File(path) => process.held_paths.contains(path),
+        }
+    }
+}
+
+impl Comparison {
+    fn holds<T: PartialOrd>(self, value: T, bound: T) -> bool {
+        match self {
+            Comparison::Less => value < bound,
+            Comparison::LessOrEqual => value <= bound,
+            Comparison::Equal => value == bound,
+            Comparison::GreaterOrEqual => value >= bound,
+            Comparison::Greater => value > bound,
+        }
+    }
+}
+
+impl Pattern {
+    /// `/regex/` or plain text
+    fn parse(value: &str) -> Result<Self, QueryError> {
+        match value
+            .strip_prefix('/')
+            .and_then(|rest| rest.strip_suffix('/'))
+        {
+            Some(pattern) => RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .map(Pattern::Regex)
+                .map_err(|e| QueryError::InvalidRegex(pattern.to_string(), e.to_string())),
+            None => Ok(Pattern::Text(value.to_lowercase())),
+        }
+    }
+
+    fn is_match(&self, haystack: &str) -> bool {
+        match self {
+            Pattern::Text(text) => haystack.to_lowercase().contains(text),
+            Pattern::Regex(regex) => regex.is_match(haystack),
+        }
+    }
+
+    /// Like [`Self::is_match`], on a haystack that is lowercase already
+    fn is_match_lowercase(&self, haystack: &str) -> bool {
+        match self {
+            Pattern::Text(text) => haystack.contains(text),
+            Pattern::Regex(regex) => regex.is_match(haystack),
+        }
+    }
+}
+
+/// One case-insensitive regex for all the text a query looks for
+fn highlighter(terms: &[Term]) -> Option<Regex> {
+    let patterns: Vec<String> = terms
+        .iter()
+        .filter_map(|term| match term {
+            Term::Text(text)
+            | Term::Name(Pattern::Text(text))
+            | Term::Command(Pattern::Text(text)) => Some(regex::escape(text)),
+            Term::Name(Pattern::Regex(regex)) | Term::Command(Pattern::Regex(regex)) => {
+                Some(format!("(?:{})", regex.as_str()))
+            }
+            _ => None,
+        })
+        .collect();
+    if patterns.is_empty() {
+        return None;
+    }
+
+    RegexBuilder::new(&patterns.join("|"))
+        .case_insensitive(true)
+        .build()
+        .ok()
+}
+
+/// Split a query into words, honouring double quotes. Each word comes with
+/// whether it started with a quote, which makes it plain text.
+fn tokenize(source: &str) -> Result<Vec<(String, bool)>, QueryError> {
+    let mut tokens = Vec::new();
+    let mut current: Option<(String, bool)> = None;
+    let mut in_quotes = false;
+
+    for c in source.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                current.get_or_insert_with(|| (String::new(), true));
+            }
+            c if c.is_whitespace() && !in_quotes => tokens.extend(current.take()),
+            c => current
+                .get_or_insert_with(|| (String::new(), false))
+                .0
+                .push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(QueryError::UnclosedQuote);
+    }
+    tokens.extend(current);
+    Ok(tokens)
+}
+
+fn parse_term(token: &str) -> Result<Term, QueryError> {
+    // A field is a word directly followed by an operator
+    let Some(split) = token.find([':', '<', '>', '=']) else {
+        return Ok(Term::Text(token.to_lowercase()));
+    };
+    let field = &token[..split];
+    if field.is_empty() || !field.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Ok(Term::Text(token.to_lowercase()));
+    }
+
+    let rest = &token[split..];
+    let (comparison, value) = [
+        ("<=", Comparison::LessOrEqual),
+        (">=", Comparison::GreaterOrEqual),
+        ("<", Comparison::Less),
+        (">", Comparison::Greater),
+        (":", Comparison::Equal),
+        ("=", Comparison::Equal),
+    ]
+    .into_iter()
+    .find_map(|(operator, comparison)| Some((comparison, rest.strip_prefix(operator)?)))
+    .unwrap_or((Comparison::Equal, rest));
+
+    let field = field.to_lowercase();
+    if value.is_empty() {
+        return Err(QueryError::MissingValue(field));
+    }
+    let exact = || {
+        if comparison == Comparison::Equal {
+            Ok(())
+        } else {
+            Err(QueryError::NotComparable(field.clone()))
+        }
+    };
+
+    match field.as_str() {
+        "cpu" => Ok(Term::Cpu(comparison, parse_percent(value)?)),
+        "mem" | "memory" => Ok(Term::Memory(comparison, parse_size(value)?)),
+        "pid" => Ok(Term::Pid(
+            comparison,
+            value
+                .parse()
+                .map_err(|_| QueryError::InvalidNumber(value.to_string()))?,
+        )),
+        "user" => {
+            exact()?;
+            Ok(Term::User(parse_user(value)?))
+        }
+        "name" => {
+            exact()?;
+            Ok(Term::Name(Pattern::parse(value)?))
+        }
+        "cmd" => {
+            exact()?;
+            Ok(Term::Command(Pattern::parse(value)?))
+        }
+        "state" => {
+            exact()?;
+            let state = value.to_lowercase();
+            STATES
+                .iter()
+                .find(|(names, _)| names.contains(&state.as_str()))
+                .map(|(_, status)| Term::State(status))
+                .ok_or_else(|| QueryError::UnknownState(value.to_string()))
+        }
+        "port" => {
+            exact()?;
+            Ok(Term::Port(value.parse().map_err(|_| {
+                QueryError::InvalidNumber(value.to_string())
+            })?))
+        }
+        "file" => {
+            exact()?;
+            let path = Path::new(value);
+            if !path.is_absolute() {
+                return Err(QueryError::InvalidPath(value.to_string()));
+            }
+            // Normalized, so `/mnt/usb/` and `/mnt/usb` are the same
+            Ok(Term::File(path.components().collect()))
+        }
+        _ => Err(QueryError::UnknownField(field)),
+    }
+}
+
+fn parse_percent(value: &str) -> Result<f32, QueryError> {
+    value
+        .strip_suffix('%')
+        .unwrap_or(value)
+        .parse()
+        .map_err(|_| QueryError::InvalidNumber(value.to_string()))
+}
+
+/// Parse a memory size such as `1G`, `512MiB` or `300` (MB).
+fn parse_size(value: &str) -> Result<u64, QueryError> {
+    let invalid = || QueryError::InvalidSize(value.to_string());
+
+    let lower = value.to_lowercase();
+    let unit_start = lower
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(lower.len());
+    let (number, unit) = lower.split_at(unit_start);
+    let number: f64 = number.parse().map_err(|_| invalid())?;
+
+    let multiplier: u64 = match unit.trim_end_matches("ib").trim_end_matches('b') {
+        "" if unit.is_empty() => 1024 * 1024,
+        "" => 1,
+        "k" => 1024,
+        "m" => 1024 * 1024,
+        "g" => 1024 * 1024 * 1024,
+        "t" => 1024 * 1024 * 1024 * 1024,
+        _ => return Err(invalid()),
+    };
+
+    Ok((number * multiplier as f64) as u64)
+}
+
+/// `me`, a user name or a numeric UID
+fn parse_user(value: &str) -> Result<u32, QueryError> {
+    if value.eq_ignore_ascii_case("me") {
+        return Ok(Uid::current().as_raw());
+    }
+    if let Ok(uid) = value.parse() {
+        return Ok(uid);
+    }
+
+    let lookup = || match User::from_name(value) {
+        Ok(Some(user)) => Some(user.uid.as_raw()),
+        _ => None,
+    };
+    let uid = match USER_IDS.lock() {
+        Ok(mut user_ids) => *user_ids.entry(value.to_string()).or_insert_with(lookup),
+        Err(_) => lookup(),
+    };
+    uid.ok_or_else(|| QueryError::UnknownUser(value.to_string()))
+}
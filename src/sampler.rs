@@ -0,0 +1,294 @@
+// SPDX-License-Identifier: MIT
+
+//! Background process sampling.
+//!
+//! Scanning /proc is slow on busy machines, so it runs on a dedicated thread
+//! that owns the [`ProcessManager`]. Each tick it compares the new sample with
+//! the previous one and sends only the differences, which the UI applies with
+//! [`ProcessController`](crate::controller::ProcessController). The threads
+//! of one process, the one whose details are open, are sent along each tick,
+//! with its sockets and open files, and its memory breakdown, cgroup
+//! limits and I/O priority are always read.
+//! Looking up who holds a file also happens here, as it reads every process.
+//! The latest sample is also kept in a [`LatestSample`], for readers outside
+//! the UI such as the D-Bus service.
+
+use crate::controller::Message;
+use crate::memory::MemoryMetric;
+use crate::process::{ProcessInfo, ProcessManager, SortBy};
+use futures_util::{stream, Stream, StreamExt};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::mpsc::error::SendError;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+/// Default time between samples
+pub const DEFAULT_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How and what to sample; a change needs a new sampler
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SamplerSettings {
+    /// Time between samples
+    pub interval: Duration,
+    /// What [`ProcessInfo::memory`] reports
+    pub memory_metric: MemoryMetric,
+}
+
+/// Changes between two consecutive samples
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SnapshotDiff {
+    /// Processes that were not in the previous sample
+    pub added: Vec<ProcessInfo>,
+    /// Processes whose values changed
+    pub updated: Vec<ProcessInfo>,
+    /// Processes that exited
+    pub removed: Vec<u32>,
+}
+
+impl SnapshotDiff {
+    /// Compute the changes from `previous` to `current`.
+    pub fn between(previous: &HashMap<u32, ProcessInfo>, current: &[ProcessInfo]) -> Self {
+        let mut diff = SnapshotDiff::default();
+
+        for process in current {
+            match previous.get(&process.pid) {
+                None => diff.added.push(process.clone()),
+                Some(old) if old != process => diff.updated.push(process.clone()),
+                Some(_) => {}
+            }
+        }
+
+        let current_pids: std::collections::HashSet<u32> = current.iter().map(|p| p.pid).collect();
+        diff.removed = previous
+            .keys()
+            .filter(|pid| !current_pids.contains(pid))
+            .copied()
+            .collect();
+
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.updated.is_empty() && self.removed.is_empty()
+    }
+
+    /// Apply the changes to a process table.
+    pub fn apply_to(self, table: &mut HashMap<u32, ProcessInfo>) {
+        for pid in self.removed {
+            table.remove(&pid);
+        }
+        for process in self.added.into_iter().chain(self.updated) {
+            table.insert(process.pid, process);
+        }
+    }
+}
+
+/// The processes of the latest sample, shared with the sampler that takes
+/// them. Empty until the first sample.
+#[derive(Debug, Clone, Default)]
+pub struct LatestSample(Arc<RwLock<Arc<Vec<ProcessInfo>>>>);
+
+impl LatestSample {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The processes, unsorted
+    pub fn get(&self) -> Arc<Vec<ProcessInfo>> {
+        self.0.read().map_or_else(|_| Arc::default(), |processes| processes.clone())
+    }
+
+    pub fn set(&self, processes: Vec<ProcessInfo>) {
+        if let Ok(mut latest) = self.0.write() {
+            *latest = Arc::new(processes);
+        }
+    }
+}
+
+/// Handle used by the UI to talk to the sampling thread
+#[derive(Debug, Clone)]
+pub struct SamplerHandle {
+    commands: mpsc::Sender<Command>,
+}
+
+#[derive(Debug)]
+enum Command {
+    Refresh,
+    WatchThreads(Option<u32>),
+    SamplePorts(bool),
+    SampleDiskIo(bool),
+    SampleHeldPaths(Vec<PathBuf>),
+    FindHolders(PathBuf),
+}
+
+impl SamplerHandle {
+    /// Take a new sample now instead of waiting for the next tick.
+    pub fn refresh_now(&self) {
+        _ = self.commands.send(Command::Refresh);
+    }
+
+    /// Also sample the threads, sockets, open files, memory breakdown and
+    /// limits of this process, or stop doing so. Starts with a sample right away.
+    pub fn watch_threads(&self, pid: Option<u32>) {
+        _ = self.commands.send(Command::WatchThreads(pid));
+    }
+
+    /// Look up the local ports of every process, for `port:` searches, or
+    /// stop doing so.
+    pub fn sample_ports(&self, enabled: bool) {
+        _ = self.commands.send(Command::SamplePorts(enabled));
+    }
+
+    /// Measure the disk reads and writes of every process, or stop doing so.
+    pub fn sample_disk_io(&self, enabled: bool) {
+        _ = self.commands.send(Command::SampleDiskIo(enabled));
+    }
+
+    /// Look for these paths among the files of every process, for `file:`
+    /// searches, or stop doing so with none.
+    pub fn sample_held_paths(&self, paths: Vec<PathBuf>) {
+        _ = self.commands.send(Command::SampleHeldPaths(paths));
+    }
+
+    /// Find the processes holding `path`, answered with a
+    /// [`Message::Holders`].
+    pub fn find_holders(&self, path: PathBuf) {
+        _ = self.commands.send(Command::FindHolders(path));
+    }
+}
+
+/// Start sampling on a background thread, which sends
+/// [`Message::Snapshot`]s, and [`Message::Threads`], [`Message::Sockets`] and
+/// [`Message::OpenFiles`] for the watched process. Every sample is also
+/// stored in `latest`.
+///
+/// The thread stops once the returned receiver is dropped.
+pub fn spawn(
+    settings: SamplerSettings,
+    latest: LatestSample,
+) -> (SamplerHandle, UnboundedReceiver<Message>) {
+    let (commands, command_receiver) = mpsc::channel();
+    let (updates, update_receiver) = unbounded_channel();
+
+    std::thread::Builder::new()
+        .name("process-sampler".to_string())
+        .spawn(move || run(settings, latest, command_receiver, updates))
+        .expect("failed to spawn process sampler thread");
+
+    (SamplerHandle { commands }, update_receiver)
+}
+
+/// Controller messages from a sampler that starts when the stream is first
+/// polled: [`Message::SamplerReady`] followed by one [`Message::Snapshot`] per
+/// sample that changed something, the threads, sockets and open files of the
+/// watched process, and the answers to [`SamplerHandle::find_holders`].
+pub fn messages(
+    settings: SamplerSettings,
+    latest: LatestSample,
+) -> impl Stream<Item = Message> + Send + 'static {
+    stream::once(async move { spawn(settings, latest) }).flat_map(|(handle, updates)| {
+        stream::once(async move { Message::SamplerReady(handle) }).chain(stream::unfold(
+            updates,
+            |mut updates| async move {
+                let message = updates.recv().await?;
+                Some((message, updates))
+            },
+        ))
+    })
+}
+
+fn run(
+    settings: SamplerSettings,
+    latest: LatestSample,
+    commands: mpsc::Receiver<Command>,
+    updates: UnboundedSender<Message>,
+) {
+    let mut process_manager = ProcessManager::new();
+    process_manager.set_memory_metric(settings.memory_metric);
+    let mut previous = HashMap::new();
+    let mut watched = None;
+
+    // CPU usage is computed between two refreshes
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+    // What the UI asked for right away is in the first sample already
+    for command in commands.try_iter() {
+        if handle(command, &mut process_manager, &mut watched, &updates).is_err() {
+            return;
+        }
+    }
+
+    loop {
+        let mut sample = process_manager.sample();
+        // The details panel shows the breakdown whatever the metric
+        if let Some(process) = sample.iter_mut().find(|p| Some(p.pid) == watched) {
+            if process.memory_breakdown.is_none() {
+                process.memory_breakdown = process_manager.memory_breakdown(process.pid);
+            }
+            process.limits = process_manager.limits(process.pid);
+            process.io_priority = process_manager.io_priority(process.pid, process.nice);
+        }
+        latest.set(sample.clone());
+        let diff = SnapshotDiff::between(&previous, &sample);
+        previous = sample.into_iter().map(|p| (p.pid, p)).collect();
+
+        if !diff.is_empty() && updates.send(Message::Snapshot(diff)).is_err() {
+            return;
+        }
+
+        if let Some(pid) = watched {
+            let threads = process_manager.get_threads(pid, SortBy::Pid);
+            let sockets = process_manager.sockets(pid);
+            let files = process_manager.open_files(pid);
+            if updates.send(Message::Threads { pid, threads }).is_err()
+                || updates.send(Message::Sockets { pid, sockets }).is_err()
+                || updates.send(Message::OpenFiles { pid, files }).is_err()
+            {
+                return;
+            }
+        }
+
+        match commands.recv_timeout(settings.interval) {
+            Ok(command) => {
+                if handle(command, &mut process_manager, &mut watched, &updates).is_err() {
+                    return;
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+
+        if updates.is_closed() {
+            return;
+        }
+    }
+}
+
+/// Carry out a command of the UI, failing once the UI is gone.
+fn handle(
+    command: Command,
+    process_manager: &mut ProcessManager,
+    watched: &mut Option<u32>,
+    updates: &UnboundedSender<Message>,
+) -> Result<(), SendError<Message>> {
+    match command {
+        Command::Refresh => {}
+        Command::WatchThreads(pid) => *watched = pid,
+        Command::SamplePorts(enabled) => process_manager.set_ports(enabled),
+        Command::SampleDiskIo(enabled) => {
+            process_manager.set_disk_io(enabled);
+            if enabled {
+                // Rates are measured from the sample just taken
+                std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL);
+            }
+        }
+        Command::SampleHeldPaths(paths) => process_manager.set_held_paths(paths),
+        Command::FindHolders(path) => {
+            let holders = process_manager.holders(&path);
+            updates.send(Message::Holders { path, holders })?;
+        }
+    }
+    Ok(())
+}
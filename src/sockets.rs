@@ -0,0 +1,210 @@
+// SPDX-License-Identifier: MIT
+
+//! Network and Unix sockets held by processes.
+//!
+//! The kernel lists every socket in `/proc/net/{tcp,tcp6,udp,udp6,unix}`,
+//! identified by inode but without its owner. A process's file descriptors
+//! in `/proc/<pid>/fd` link to `socket:[<inode>]`, which ties the two
+//! together. Only the descriptors of one's own processes can be read without
+//! privileges.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    Tcp,
+    Udp,
+    Unix,
+}
+
+impl Protocol {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Protocol::Tcp => "TCP",
+            Protocol::Udp => "UDP",
+            Protocol::Unix => "Unix",
+        }
+    }
+}
+
+/// A socket, as listed in `/proc/net`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SocketInfo {
+    pub inode: u64,
+    pub protocol: Protocol,
+    /// Local address and port, or the path of a Unix socket (empty when
+    /// unnamed)
+    pub local: String,
+    /// Peer address and port of a connected TCP or UDP socket
+    pub remote: Option<String>,
+    /// TCP state such as `LISTEN`, or `LISTEN` for a listening Unix socket
+    pub state: Option<String>,
+    /// Local TCP or UDP port
+    pub port: Option<u16>,
+}
+
+/// Tables of `/proc/net` and their protocol
+const INET_TABLES: [(&str, Protocol); 4] = [
+    ("tcp", Protocol::Tcp),
+    ("tcp6", Protocol::Tcp),
+    ("udp", Protocol::Udp),
+    ("udp6", Protocol::Udp),
+];
+
+/// TCP states by the number in `/proc/net/tcp`, see `include/net/tcp_states.h`
+const TCP_STATES: [&str; 12] = [
+    "",
+    "ESTABLISHED",
+    "SYN_SENT",
+    "SYN_RECV",
+    "FIN_WAIT1",
+    "FIN_WAIT2",
+    "TIME_WAIT",
+    "CLOSE",
+    "CLOSE_WAIT",
+    "LAST_ACK",
+    "LISTEN",
+    "CLOSING",
+];
+
+/// `__SO_ACCEPTCON` in the flags of `/proc/net/unix`
+const UNIX_LISTENING: u32 = 1 << 16;
+
+/// Every TCP and UDP socket of the system, and the Unix ones with
+/// `with_unix`, by inode.
+pub fn socket_table(root: &Path, with_unix: bool) -> HashMap<u64, SocketInfo> {
+    let mut table = HashMap::new();
+    for (file, protocol) in INET_TABLES {
+        if let Ok(content) = fs::read_to_string(root.join("net").join(file)) {
+            table.extend(parse_inet_table(&content, protocol).map(|socket| (socket.inode, socket)));
+        }
+    }
+    if with_unix {
+        if let Ok(content) = fs::read_to_string(root.join("net/unix")) {
+            table.extend(parse_unix_table(&content).map(|socket| (socket.inode, socket)));
+        }
+    }
+    table
+}
+
+/// Inodes of the sockets a process has open
+pub fn socket_inodes(root: &Path, pid: u32) -> Vec<u64> {
+    let Ok(fds) = fs::read_dir(root.join(pid.to_string()).join("fd")) else {
+        return Vec::new();
+    };
+    fds.filter_map(|fd| {
+        let target = fs::read_link(fd.ok()?.path()).ok()?;
+        target
+            .to_str()?
+            .strip_prefix("socket:[")?
+            .strip_suffix(']')?
+            .parse()
+            .ok()
+    })
+    .collect()
+}
+
+/// Sockets of a process, TCP first, then UDP and Unix, each by address
+pub fn process_sockets(root: &Path, pid: u32) -> Vec<SocketInfo> {
+    let inodes = socket_inodes(root, pid);
+    if inodes.is_empty() {
+        return Vec::new();
+    }
+
+    let mut table = socket_table(root, true);
+    let mut sockets: Vec<SocketInfo> = inodes
+        .into_iter()
+        .filter_map(|inode| table.remove(&inode))
+        .collect();
+    sockets.sort_by(|a, b| (a.protocol, &a.local).cmp(&(b.protocol, &b.local)));
+    sockets
+}
+
+/// Local TCP and UDP ports among the given sockets, sorted and without
+/// repetitions
+pub fn local_ports(table: &HashMap<u64, SocketInfo>, inodes: &[u64]) -> Vec<u16> {
+    let mut ports: Vec<u16> = inodes
+        .iter()
+        .filter_map(|inode| table.get(inode)?.port)
+        .collect();
+    ports.sort_unstable();
+    ports.dedup();
+    ports
+}
+
+/// Parse `/proc/net/tcp` and the like: a header line, then one socket per
+/// line with hexadecimal addresses (`0100007F:1F90`) and the inode in the
+/// tenth column.
+pub fn parse_inet_table(
+    content: &str,
+    protocol: Protocol,
+) -> impl Iterator<Item = SocketInfo> + '_ {
+    content.lines().skip(1).filter_map(move |line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let local = parse_address(fields.get(1)?)?;
+        let remote = parse_address(fields.get(2)?)?;
+        let state = u8::from_str_radix(fields.get(3)?, 16).ok()?;
+        let inode = fields.get(9)?.parse().ok()?;
+
+        Some(SocketInfo {
+            inode,
+            protocol,
+            local: local.to_string(),
+            remote: (remote.port() != 0).then(|| remote.to_string()),
+            state: match protocol {
+                Protocol::Tcp => TCP_STATES
+                    .get(usize::from(state))
+                    .filter(|state| !state.is_empty())
+                    .map(|state| state.to_string()),
+                _ => None,
+            },
+            port: Some(local.port()),
+        })
+    })
+}
+
+/// Parse `/proc/net/unix`: a header line, then
+/// `Num RefCount Protocol Flags Type St Inode [Path]` per socket.
+pub fn parse_unix_table(content: &str) -> impl Iterator<Item = SocketInfo> + '_ {
+    content.lines().skip(1).filter_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let flags = u32::from_str_radix(fields.get(3)?, 16).ok()?;
+        let inode = fields.get(6)?.parse().ok()?;
+
+        Some(SocketInfo {
+            inode,
+            protocol: Protocol::Unix,
+            local: fields
+                .get(7)
+                .map(|path| path.to_string())
+                .unwrap_or_default(),
+            remote: None,
+            state: (flags & UNIX_LISTENING != 0).then(|| "LISTEN".to_string()),
+            port: None,
+        })
+    })
+}
+
+/// `0100007F:1F90` or its IPv6 form. Addresses are printed as 32-bit words in
+/// the machine's byte order, ports in the usual order.
+fn parse_address(field: &str) -> Option<SocketAddr> {
+    let (address, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let mut bytes = Vec::with_capacity(16);
+    for word in address.as_bytes().chunks(8) {
+        let word = u32::from_str_radix(std::str::from_utf8(word).ok()?, 16).ok()?;
+        bytes.extend_from_slice(&word.to_ne_bytes());
+    }
+
+    let ip = match bytes.len() {
+        4 => IpAddr::V4(Ipv4Addr::from(<[u8; 4]>::try_from(bytes).ok()?)),
+        16 => IpAddr::V6(Ipv6Addr::from(<[u8; 16]>::try_from(bytes).ok()?)),
+        _ => return None,
+    };
+    Some(SocketAddr::new(ip, port))
+}
@@ -2,64 +2,259 @@
 
 //! Standalone window mode - can be launched independently of the panel
 
-#[allow(unused_imports)]
+use crate::affinity::CpuMask;
+use crate::cgroup::{self, Limits};
+use crate::config::{Config, CONFIG_ID};
+use crate::container::{Container, Runtime};
+use crate::controller::{
+    truncate_name, ConfirmationMode, GroupKill, GroupTarget, Message as ProcessMessage,
+    PendingAction, ProcessController,
+};
+use crate::export::{self, ExportFormat};
+use crate::files::{OpenFile, Usage};
 use crate::fl;
-#[allow(dead_code)]
-use crate::process::{ProcessError, ProcessInfo, ProcessManager, SortBy};
-use cosmic::iced::{Alignment, Length};
+use crate::keybindings::{KeyAction, Modifiers};
+use crate::memory::MemoryMetric;
+use crate::package::{Package, PackageKind};
+use crate::priority::{IoClass, IoPriority, IO_LEVELS, NICE_RANGE};
+use crate::process::{container_order, format_rate, tree_order, ProcessInfo, SortBy};
+use crate::sampler::{self, LatestSample};
+use crate::systemd::{Unit, UnitAction, UnitScope};
+use crate::view;
+use crate::virtual_list::VirtualList;
+use cosmic::app::CosmicFlags;
+use cosmic::dbus_activation::Details as DbusActivationDetails;
+use cosmic::cosmic_config::{self, CosmicConfigEntry};
+use cosmic::iced::keyboard::{self, Key};
+use cosmic::iced::widget::scrollable::{self, AbsoluteOffset};
+use cosmic::iced::widget::{rich_text, span};
+use cosmic::iced::{event, Alignment, Event, Length};
 use cosmic::prelude::*;
 use cosmic::widget;
-use futures_util::SinkExt;
-use std::time::Duration;
+use futures_util::StreamExt;
+use nix::sys::signal::Signal;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+static SEARCH_INPUT_ID: LazyLock<widget::Id> = LazyLock::new(|| widget::Id::new("search"));
+static LIST_ID: LazyLock<widget::Id> = LazyLock::new(|| widget::Id::new("process-list"));
+
+/// Checkboxes per row of the CPU core grid
+const CORES_PER_ROW: usize = 8;
+
+/// I/O classes in the order of the priority editor's dropdown
+const IO_CLASSES: [IoClass; 3] = [IoClass::RealTime, IoClass::BestEffort, IoClass::Idle];
+static IO_CLASS_NAMES: LazyLock<[String; 3]> = LazyLock::new(|| {
+    [
+        fl!("io-class-realtime"),
+        fl!("io-class-best-effort"),
+        fl!("io-class-idle"),
+    ]
+});
+
+static MEMORY_METRIC_NAMES: LazyLock<[String; 3]> = LazyLock::new(|| {
+    MemoryMetric::ALL.map(|metric| match metric {
+        MemoryMetric::Rss => fl!("memory-metric-rss"),
+        MemoryMetric::Pss => fl!("memory-metric-pss"),
+        MemoryMetric::Uss => fl!("memory-metric-uss"),
+    })
+});
+
+const GB: u64 = 1024 * 1024 * 1024;
+/// Memory limits offered by the limits editor, none first
+const MEMORY_LIMITS: [Option<u64>; 7] = [
+    None,
+    Some(GB / 2),
+    Some(GB),
+    Some(2 * GB),
+    Some(4 * GB),
+    Some(8 * GB),
+    Some(16 * GB),
+];
+static MEMORY_LIMIT_NAMES: LazyLock<Vec<String>> =
+    LazyLock::new(|| MEMORY_LIMITS.iter().map(|limit| memory_limit_label(*limit)).collect());
+/// CPU limits offered by the limits editor, in percent of one core
+const CPU_LIMITS: [Option<u32>; 6] = [None, Some(10), Some(25), Some(50), Some(100), Some(200)];
+static CPU_LIMIT_NAMES: LazyLock<Vec<String>> =
+    LazyLock::new(|| CPU_LIMITS.iter().map(|limit| cpu_limit_label(*limit)).collect());
+
+/// Height of a process row, which the virtualized list relies on
+const ROW_HEIGHT: f32 = 44.0;
+const LIST_HEIGHT: f32 = 400.0;
+/// Width of the name column and the characters of a label that fit in it
+const NAME_WIDTH: f32 = 240.0;
+const NAME_CHARS: usize = 32;
 
-#[allow(dead_code)]
 pub struct StandaloneApp {
     core: cosmic::Core,
-    process_manager: ProcessManager,
-    processes: Vec<ProcessInfo>,
-    show_all: bool,
-    sort_by: SortBy,
-    search_query: String,
-    selected_process: Option<ProcessInfo>,
-    confirmation_mode: Option<ConfirmationMode>,
-    toast: Option<Toast>,
+    config: Config,
+    /// Writes settings changed from the window, such as saved searches
+    config_handler: Option<cosmic_config::Config>,
+    /// Process list, search, selection and kill confirmation
+    controller: ProcessController,
+    /// Rows in view and their order
+    list: VirtualList,
+    /// Show processes nested under their parents
+    tree_view: bool,
+    /// Keep the processes of each container together
+    group_by_container: bool,
+    /// Process shown in the details panel
+    details_pid: Option<u32>,
+    /// Process to show once the first sample arrives (from `--pid`)
+    pending_details: Option<u32>,
+    /// Priority being edited in the details panel, not applied yet
+    priority_draft: Option<PriorityDraft>,
+    /// Limits being edited in the details panel, for this process
+    limits_draft: Option<(u32, Limits)>,
+    /// Row whose priority menu is open
+    priority_menu: Option<u32>,
+    /// CPU cores of the process in the details panel
+    affinity: Option<AffinityEditor>,
+    /// Thread whose signal menu is open
+    thread_menu: Option<u32>,
+    /// Path typed in the "who holds this file" dialog, while it is open
+    holders_dialog: Option<String>,
+}
+
+/// Core grid of the details panel
+#[derive(Debug, Clone)]
+struct AffinityEditor {
+    pid: u32,
+    /// Cores the process may run on, or why they could not be read
+    current: Result<CpuMask, String>,
+    /// Cores checked in the grid
+    mask: CpuMask,
+    all_threads: bool,
+}
+
+/// Priorities picked in the details panel for one process
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PriorityDraft {
+    pid: u32,
+    nice: i32,
+    io: IoPriority,
+}
+
+impl PriorityDraft {
+    /// The current priorities of `process`
+    fn of(process: &ProcessInfo) -> Self {
+        Self {
+            pid: process.pid,
+            nice: process.nice,
+            io: process
+                .io_priority
+                .unwrap_or_else(|| IoPriority::from_nice(process.nice)),
+        }
+    }
 }
 
-#[derive(Debug, Clone, PartialEq)]
-#[allow(dead_code)]
-pub enum ConfirmationMode {
-    Kill,
-    ForceKill,
+/// Startup options, passed from the command line or from a second launch
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Flags {
+    /// Pre-fill the search box
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search: Option<String>,
+    /// Open the details panel for this process
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    /// Initial sort order
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort_by: Option<SortBy>,
+    /// List every process instead of the top 10
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub show_all: bool,
+    /// Start in tree view
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub tree: bool,
 }
 
-#[derive(Debug, Clone, PartialEq)]
-#[allow(dead_code)]
-pub struct Toast {
-    message: String,
-    is_error: bool,
+/// Flags travel to an already running instance as the activation action,
+/// encoded as JSON.
+impl fmt::Display for Flags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let json = serde_json::to_string(self).map_err(|_| fmt::Error)?;
+        f.write_str(&json)
+    }
+}
+
+impl CosmicFlags for Flags {
+    type SubCommand = Flags;
+    type Args = Vec<String>;
+
+    fn action(&self) -> Option<&Self::SubCommand> {
+        (*self != Flags::default()).then_some(self)
+    }
 }
 
-#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub enum Message {
-    RefreshProcesses,
-    KillProcess(u32),
-    ForceKillProcess(u32),
-    ToggleShowAll(bool),
-    SortBy(SortBy),
-    UpdateSearch(String),
-    SelectProcess(Option<u32>),
-    ConfirmKill,
-    ConfirmForceKill,
-    CancelConfirmation,
-    ShowToast(String, bool),
-    ClearToast,
+    Process(ProcessMessage),
+    Export(ExportFormat),
+    ToggleTreeView(bool),
+    ToggleContainerGroups(bool),
+    /// Measure memory differently, saved to the config
+    SetMemoryMetric(MemoryMetric),
+    /// Show the disk I/O columns, saved to the config
+    ToggleDiskIo(bool),
+    ShowDetails(Option<u32>),
+    /// Open the priority menu of a row, or close it
+    PriorityMenu(Option<u32>),
+    /// A priority preset picked from a row menu
+    SetPriority(ProcessMessage),
+    /// A systemd unit action picked from a row menu or the details panel
+    UnitAction(ProcessMessage),
+    /// Freezing or thawing an app, from a row or the details panel
+    SetFrozen(ProcessMessage),
+    /// Killing a packaged app, from a row or the details panel
+    KillPackage(ProcessMessage),
+    /// Stopping a container, from a row or the details panel
+    StopContainer(ProcessMessage),
+    EditNice(i32),
+    EditIoClass(IoClass),
+    EditIoLevel(u8),
+    /// Apply the priorities edited in the details panel
+    ApplyPriority,
+    EditMemoryLimit(Option<u64>),
+    EditCpuLimit(Option<u32>),
+    /// Apply the limits edited in the details panel
+    ApplyLimits,
+    ToggleCore(usize, bool),
+    SelectAllCores,
+    ToggleAllThreads(bool),
+    ApplyAffinity,
+    /// Open the signal menu of a thread, or close it
+    ThreadMenu(Option<u32>),
+    /// A signal picked from a thread menu
+    SignalThread(ProcessMessage),
+    /// Open the "who holds this file" dialog, or close it
+    HoldersDialog(bool),
+    HoldersPath(String),
+    /// Look up the path typed in the dialog
+    FindHolders,
+    ListScrolled {
+        offset: f32,
+        height: f32,
+    },
+    /// The pointer entered or left the process list
+    ListHovered(bool),
+    UpdateConfig(Config),
+    KeyPressed {
+        key: Key,
+        modifiers: keyboard::Modifiers,
+        text: Option<String>,
+        /// Whether a widget (e.g. the search input) already handled the key
+        captured: bool,
+    },
     Close,
 }
 
 impl cosmic::Application for StandaloneApp {
     type Executor = cosmic::executor::Default;
-    type Flags = ();
+    type Flags = Flags;
     type Message = Message;
     const APP_ID: &'static str = "io.github.marcossl10.CosmicProcessKiller.Standalone";
 
@@ -73,23 +268,63 @@ impl cosmic::Application for StandaloneApp {
 
     fn init(
         core: cosmic::Core,
-        _flags: Self::Flags,
+        flags: Self::Flags,
     ) -> (Self, Task<cosmic::Action<Self::Message>>) {
+        let config_handler = cosmic_config::Config::new(CONFIG_ID, Config::VERSION).ok();
+        let config = config_handler
+            .as_ref()
+            .map(|context| match Config::get_entry(context) {
+                Ok(config) => config,
+                Err((_errors, config)) => config,
+            })
+            .unwrap_or_default();
+        let mut controller = ProcessController::new();
+        if let Some(handler) = &config_handler {
+            controller = controller.with_search_store(Arc::new(handler.clone()));
+        }
+        controller.set_saved_searches(config.saved_searches.clone());
+        controller.set_show_disk_io(config.show_disk_io);
         let mut app = StandaloneApp {
             core,
-            process_manager: ProcessManager::new(),
-            processes: Vec::new(),
-            show_all: false,
-            sort_by: SortBy::Cpu,
-            search_query: String::new(),
-            selected_process: None,
-            confirmation_mode: None,
-            toast: None,
+            config,
+            config_handler,
+            controller,
+            list: VirtualList::new(ROW_HEIGHT, LIST_HEIGHT),
+            tree_view: false,
+            group_by_container: false,
+            details_pid: None,
+            pending_details: None,
+            priority_draft: None,
+            limits_draft: None,
+            priority_menu: None,
+            thread_menu: None,
+            affinity: None,
+            holders_dialog: None,
         };
 
-        app.refresh_processes();
+        app.apply_flags(flags);
+        let task = app.sync_list();
 
-        (app, Task::none())
+        (app, task)
+    }
+
+    /// A second launch activates this instance instead of opening another window.
+    fn dbus_activation(
+        &mut self,
+        msg: cosmic::dbus_activation::Message,
+    ) -> Task<cosmic::Action<Self::Message>> {
+        if let DbusActivationDetails::ActivateAction { action, .. } = msg.msg {
+            match serde_json::from_str::<Flags>(&action) {
+                Ok(flags) => self.apply_flags(flags),
+                Err(why) => eprintln!("ignoring invalid activation flags {action:?}: {why}"),
+            }
+        }
+
+        let focus = match self.core.main_window_id() {
+            Some(id) => cosmic::iced::window::gain_focus(id),
+            None => Task::none(),
+        };
+        Task::batch([focus, self.sync_list()])
     }
 
     fn view(&self) -> Element<'_, Self::Message> {
@@ -103,7 +338,7 @@ impl cosmic::Application for StandaloneApp {
             .push(widget::horizontal_space())
             .push(widget::tooltip(
                     widget::button::icon(widget::icon::from_name("view-refresh-symbolic"))
-                        .on_press(Message::RefreshProcesses)
+                        .on_press(Message::Process(ProcessMessage::RefreshProcesses))
                         .padding(8),
                     widget::text(fl!("refresh-tooltip")),
                     widget::tooltip::Position::Bottom,
@@ -118,23 +353,87 @@ impl cosmic::Application for StandaloneApp {
         content = content.push(header);
 
         // Search
-        let search = widget::text_input(fl!("search-placeholder"), &self.search_query)
-            .on_input(Message::UpdateSearch)
+        let search = widget::text_input(fl!("search-placeholder"), self.controller.search_query())
+            .id(SEARCH_INPUT_ID.clone())
+            .on_input(|query| Message::Process(ProcessMessage::UpdateSearch(query)))
             .width(Length::Fill);
+        let save_search = widget::tooltip(
+            widget::button::icon(widget::icon::from_name("starred-symbolic"))
+                .on_press_maybe(
+                    self.controller
+                        .can_save_search()
+                        .then_some(Message::Process(ProcessMessage::SaveSearch)),
+                )
+                .padding(8),
+            widget::text(fl!("save-search-tooltip")),
+            widget::tooltip::Position::Bottom,
+        );
+
+        content = content.push(
+            widget::row()
+                .spacing(8)
+                .align_y(Alignment::Center)
+                .push(search)
+                .push(save_search),
+        );
+
+        if let Some(error) = self.controller.search_error() {
+            content = content.push(
+                widget::row()
+                    .spacing(6)
+                    .align_y(Alignment::Center)
+                    .push(widget::icon::from_name("dialog-warning-symbolic").size(16))
+                    .push(widget::text(error).size(12)),
+            );
+        }
 
-        content = content.push(search);
+        if let Some(saved) = view::saved_searches(&self.controller, 6) {
+            content = content.push(saved.map(Message::Process));
+        }
 
         // Filter
         let filter_row = widget::row()
             .spacing(8)
             .align_y(Alignment::Center)
             .push(widget::text(fl!("show-all")))
-            .push(widget::toggler(self.show_all).on_toggle(Message::ToggleShowAll));
+            .push(
+                widget::toggler(self.controller.show_all())
+                    .on_toggle(|show_all| Message::Process(ProcessMessage::ToggleShowAll(show_all))),
+            )
+            .push(widget::text(fl!("tree-view")))
+            .push(widget::toggler(self.tree_view).on_toggle(Message::ToggleTreeView))
+            .push(widget::text(fl!("group-by-container")))
+            .push(
+                widget::toggler(self.group_by_container).on_toggle(Message::ToggleContainerGroups),
+            )
+            .push(widget::text(fl!("disk-io")))
+            .push(widget::toggler(self.config.show_disk_io).on_toggle(Message::ToggleDiskIo))
+            .push(widget::text(fl!("memory-metric")))
+            .push(widget::dropdown(
+                &*MEMORY_METRIC_NAMES,
+                MemoryMetric::ALL
+                    .iter()
+                    .position(|metric| *metric == self.config.memory_metric),
+                |index| Message::SetMemoryMetric(MemoryMetric::ALL[index]),
+            ))
+            .push(widget::horizontal_space())
+            .push(
+                widget::button::standard(fl!("holders-open"))
+                    .on_press(Message::HoldersDialog(true)),
+            )
+            .push(
+                widget::button::standard(fl!("export-json"))
+                    .on_press(Message::Export(ExportFormat::JsonLines)),
+            )
+            .push(
+                widget::button::standard(fl!("export-csv"))
+                    .on_press(Message::Export(ExportFormat::Csv)),
+            );
 
         content = content.push(filter_row);
 
         // Column Headers
-        let header_row = widget::row()
+        let mut header_row = widget::row()
             .spacing(12)
             .padding([0, 5])
             .push(
@@ -143,10 +442,10 @@ impl cosmic::Application for StandaloneApp {
                         .width(Length::Fill)
                         .align_x(cosmic::iced::alignment::Horizontal::Center),
                 )
-                    .on_press(Message::SortBy(SortBy::Name))
+                    .on_press(Message::Process(ProcessMessage::SortBy(SortBy::Name)))
                     .padding(0)
                     .class(cosmic::theme::Button::Text)
-                    .width(Length::Fixed(180.0))
+                    .width(Length::Fixed(NAME_WIDTH))
             )
             .push(
                 widget::button::custom(
@@ -154,7 +453,7 @@ impl cosmic::Application for StandaloneApp {
                         .width(Length::Fill)
                         .align_x(cosmic::iced::alignment::Horizontal::Center),
                 )
-                    .on_press(Message::SortBy(SortBy::Pid))
+                    .on_press(Message::Process(ProcessMessage::SortBy(SortBy::Pid)))
                     .padding(0)
                     .class(cosmic::theme::Button::Text)
                     .width(Length::Fixed(90.0))
@@ -165,29 +464,41 @@ impl cosmic::Application for StandaloneApp {
                         .width(Length::Fill)
                         .align_x(cosmic::iced::alignment::Horizontal::Center),
                 )
-                    .on_press(Message::SortBy(SortBy::Cpu))
+                    .on_press(Message::Process(ProcessMessage::SortBy(SortBy::Cpu)))
                     .padding(0)
                     .class(cosmic::theme::Button::Text)
                     .width(Length::Fixed(80.0))
             )
             .push(
                 widget::button::custom(
-                    widget::text(fl!("header-mem"))
+                    widget::text(self.config.memory_header())
                         .width(Length::Fill)
                         .align_x(cosmic::iced::alignment::Horizontal::Center),
                 )
-                    .on_press(Message::SortBy(SortBy::Memory))
+                    .on_press(Message::Process(ProcessMessage::SortBy(SortBy::Memory)))
                     .padding(0)
                     .class(cosmic::theme::Button::Text)
                     .width(Length::Fixed(90.0))
-            )
+            );
+        for header in view::disk_io_headers(&self.controller, 90.0) {
+            header_row = header_row.push(header.map(Message::Process));
+        }
+        let header_row = header_row
             .push(widget::horizontal_space())
-            .push(widget::text(fl!("header-actions")).size(14).width(Length::Fixed(100.0))); // Placeholder for alignment
+            .push(widget::text(fl!("header-actions")).size(14).width(Length::Fixed(130.0))); // Placeholder for alignment
 
         content = content.push(header_row);
 
+        // Details panel
+        if let Some(process) = self
+            .details_pid
+            .and_then(|pid| self.controller.sampled_process(pid))
+        {
+            content = content.push(self.view_details(process));
+        }
+
         // Confirmation dialog overlay
-        if let (Some(process), Some(mode)) = (&self.selected_process, &self.confirmation_mode) {
+        if let Some((process, mode)) = self.controller.confirmation() {
             let dialog = widget::column()
                 .spacing(12)
                 .padding(16)
@@ -204,57 +515,104 @@ impl cosmic::Application for StandaloneApp {
                     widget::text(format!("{} (PID: {})", process.name, process.pid))
                         .size(12)
                 )
+                // Live values, updated with every sample until confirmed
+                .push(
+                    widget::text(format!(
+                        "{} {:.1}% · {} {}",
+                        fl!("header-cpu"),
+                        process.cpu_usage,
+                        self.config.memory_header(),
+                        view::memory_label(process.memory)
+                    ))
+                    .size(12)
+                )
                 .push(
                     widget::row()
                         .spacing(8)
                         .push(
                             widget::button::destructive(fl!("confirm"))
                                 .on_press(if matches!(mode, ConfirmationMode::ForceKill) {
-                                    Message::ConfirmForceKill
+                                    Message::Process(ProcessMessage::ConfirmForceKill)
                                 } else {
-                                    Message::ConfirmKill
+                                    Message::Process(ProcessMessage::ConfirmKill)
                                 })
                         )
                         .push(
                             widget::button::text(fl!("cancel"))
-                                .on_press(Message::CancelConfirmation)
+                                .on_press(Message::Process(ProcessMessage::CancelConfirmation))
                         )
                 );
 
             content = content.push(dialog);
         }
+        if let Some(group) = self.controller.group_kill() {
+            content = content.push(self.view_group_kill(group));
+        }
+        if let Some(pending) = self.controller.pending_action() {
+            content = content.push(self.view_pending_action(pending));
+        }
 
-        // Process list
-        let mut process_list = widget::list_column().spacing(4);
+        if let Some(path) = &self.holders_dialog {
+            content = content.push(self.view_holders_dialog(path));
+        }
 
-        let filtered_processes = self.get_filtered_processes();
+        // Process list: only the rows in view are built
+        let rows = self.visible_rows();
 
-        if filtered_processes.is_empty() {
-            process_list = process_list.add(
+        let process_list = if rows.is_empty() {
+            widget::column().push(
                 widget::container(widget::text(fl!("no-processes")))
                     .padding(20)
                     .center_x(Length::Fill),
-            );
+            )
         } else {
-            for process in filtered_processes.iter() {
-                let process_row = self.create_process_row(process);
-                process_list = process_list.add(process_row);
+            let by_pid: HashMap<u32, (usize, &ProcessInfo)> = rows
+                .iter()
+                .map(|&(depth, process)| (process.pid, (depth, process)))
+                .collect();
+            let range = self.list.visible_range();
+            let below = self.list.rows().len() - range.end;
+
+            let mut process_list = widget::column().push(widget::Space::with_height(
+                Length::Fixed(self.list.height_of(range.start)),
+            ));
+            for pid in &self.list.rows()[range] {
+                if let Some(&(depth, process)) = by_pid.get(pid) {
+                    process_list = process_list.push(
+                        widget::container(self.create_process_row(process, depth))
+                            .height(Length::Fixed(ROW_HEIGHT))
+                            .align_y(Alignment::Center),
+                    );
+                }
             }
-        }
+            process_list.push(widget::Space::with_height(Length::Fixed(
+                self.list.height_of(below),
+            )))
+        };
 
         let scrollable = widget::scrollable(process_list)
-            .height(Length::Fixed(400.0))
+            .id(LIST_ID.clone())
+            .on_scroll(|viewport| Message::ListScrolled {
+                offset: viewport.absolute_offset().y,
+                height: viewport.bounds().height,
+            })
+            .height(Length::Fixed(LIST_HEIGHT))
             .width(Length::Fill);
 
-        content = content.push(scrollable);
+        // Rows keep their order while the pointer is over them
+        content = content.push(
+            widget::mouse_area(scrollable)
+                .on_enter(Message::ListHovered(true))
+                .on_exit(Message::ListHovered(false)),
+        );
 
         // Footer
-        let count = filtered_processes.len();
+        let count = rows.len();
         let info = widget::text(fl!("process-count", count = count)).size(12);
         content = content.push(info);
 
         // Toast notification
-        if let Some(ref toast) = self.toast {
+        if let Some(toast) = self.controller.toast() {
             let toast_text = widget::text(&toast.message)
                 .size(14);
             
@@ -274,219 +632,1322 @@ impl cosmic::Application for StandaloneApp {
     }
 
     fn subscription(&self) -> cosmic::iced::Subscription<Self::Message> {
-        struct RefreshSubscription;
-
-        cosmic::iced::Subscription::run_with_id(
-            std::any::TypeId::of::<RefreshSubscription>(),
-            cosmic::iced::stream::channel(4, move |mut channel| async move {
-                loop {
-                    tokio::time::sleep(Duration::from_secs(2)).await;
-                    _ = channel.send(Message::RefreshProcesses).await;
-                }
+        struct SamplerSubscription;
+
+        let settings = self.config.sampler_settings();
+
+        cosmic::iced::Subscription::batch(vec![
+            // Sample processes in the background
+            cosmic::iced::Subscription::run_with_id(
+                (std::any::TypeId::of::<SamplerSubscription>(), settings),
+                sampler::messages(settings, LatestSample::new()).map(Message::Process),
+            ),
+            // Keyboard shortcuts
+            event::listen_with(|event, status, _window| match event {
+                Event::Keyboard(keyboard::Event::KeyPressed {
+                    key,
+                    modifiers,
+                    text,
+                    ..
+                }) => Some(Message::KeyPressed {
+                    key,
+                    modifiers,
+                    text: text.map(|text| text.to_string()),
+                    captured: status == event::Status::Captured,
+                }),
+                _ => None,
             }),
-        )
+            // Watch for key binding changes
+            self.core()
+                .watch_config::<Config>(CONFIG_ID)
+                .map(|update| Message::UpdateConfig(update.config)),
+        ])
     }
 
     fn update(&mut self, message: Self::Message) -> Task<cosmic::Action<Self::Message>> {
-        match message {
-            Message::RefreshProcesses => {
-                self.refresh_processes();
+        let task = match message {
+            Message::Process(message) => {
+                self.controller.update(message);
+
+                if self.controller.has_snapshot() {
+                    if let Some(pid) = self.pending_details.take() {
+                        self.show_details(pid);
+                    }
+                }
+                Task::none()
+            }
+            Message::Export(format) => {
+                self.export_processes(format);
+                Task::none()
+            }
+            Message::ToggleTreeView(tree_view) => {
+                self.tree_view = tree_view;
+                Task::none()
             }
-            Message::KillProcess(pid) => {
-                self.handle_kill_process(pid, false);
+            Message::ToggleContainerGroups(group_by_container) => {
+                self.group_by_container = group_by_container;
+                Task::none()
             }
-            Message::ForceKillProcess(pid) => {
-                self.handle_kill_process(pid, true);
+            Message::ShowDetails(pid) => {
+                self.set_details(pid);
+                Task::none()
             }
-            Message::ConfirmKill => {
-                if let Some(process) = self.selected_process.clone() {
-                    self.execute_kill(&process, false);
+            Message::ThreadMenu(tid) => {
+                self.thread_menu = tid;
+                Task::none()
+            }
+            Message::SignalThread(message) => {
+                self.thread_menu = None;
+                self.controller.update(message);
+                Task::none()
+            }
+            Message::HoldersDialog(open) => {
+                if open {
+                    self.holders_dialog.get_or_insert_with(String::new);
+                } else {
+                    self.holders_dialog = None;
+                    self.controller.update(ProcessMessage::CloseHolders);
                 }
-                self.confirmation_mode = None;
-                self.selected_process = None;
+                Task::none()
+            }
+            Message::HoldersPath(path) => {
+                self.holders_dialog = Some(path);
+                Task::none()
             }
-            Message::ConfirmForceKill => {
-                if let Some(process) = self.selected_process.clone() {
-                    self.execute_kill(&process, true);
+            Message::FindHolders => {
+                if let Some(path) = self.holders_dialog.as_deref().map(str::trim) {
+                    if Path::new(path).is_absolute() {
+                        let path = PathBuf::from(path);
+                        self.controller.update(ProcessMessage::FindHolders(path));
+                    }
                 }
-                self.confirmation_mode = None;
-                self.selected_process = None;
+                Task::none()
             }
-            Message::CancelConfirmation => {
-                self.confirmation_mode = None;
-                self.selected_process = None;
+            Message::PriorityMenu(pid) => {
+                self.priority_menu = pid;
+                Task::none()
             }
-            Message::ToggleShowAll(show_all) => {
-                self.show_all = show_all;
-                self.refresh_processes();
+            Message::UnitAction(message)
+            | Message::SetFrozen(message)
+            | Message::KillPackage(message)
+            | Message::StopContainer(message) => {
+                self.priority_menu = None;
+                self.controller.update(message);
+                Task::none()
             }
-            Message::SortBy(sort_by) => {
-                self.sort_by = sort_by;
-                self.refresh_processes();
+            Message::SetPriority(message) => {
+                self.priority_menu = None;
+                self.controller.update(message);
+                Task::none()
             }
-            Message::UpdateSearch(query) => {
-                self.search_query = query;
+            Message::EditNice(nice) => {
+                self.edit_priority(|draft| draft.nice = nice);
+                Task::none()
             }
-            Message::SelectProcess(pid) => {
-                if let Some(pid) = pid {
-                    self.selected_process = self.processes.iter()
-                        .find(|p| p.pid == pid)
-                        .cloned();
-                } else {
-                    self.selected_process = None;
+            Message::EditIoClass(class) => {
+                self.edit_priority(|draft| draft.io.class = class);
+                Task::none()
+            }
+            Message::EditIoLevel(level) => {
+                self.edit_priority(|draft| draft.io.level = level);
+                Task::none()
+            }
+            Message::ApplyPriority => {
+                self.apply_priority();
+                Task::none()
+            }
+            Message::EditMemoryLimit(memory_max) => {
+                self.edit_limits(|limits| limits.memory_max = memory_max);
+                Task::none()
+            }
+            Message::EditCpuLimit(cpu_max) => {
+                self.edit_limits(|limits| limits.cpu_max = cpu_max);
+                Task::none()
+            }
+            Message::ApplyLimits => {
+                if let Some((pid, limits)) = self.limits_draft.take() {
+                    self.controller.update(ProcessMessage::SetLimits { pid, limits });
+                }
+                Task::none()
+            }
+            Message::ToggleCore(cpu, allowed) => {
+                if let Some(editor) = &mut self.affinity {
+                    editor.mask.set(cpu, allowed);
+                }
+                Task::none()
+            }
+            Message::SelectAllCores => {
+                if let Some(editor) = &mut self.affinity {
+                    editor.mask = CpuMask::all();
+                }
+                Task::none()
+            }
+            Message::ToggleAllThreads(all_threads) => {
+                if let Some(editor) = &mut self.affinity {
+                    editor.all_threads = all_threads;
                 }
+                Task::none()
             }
-            Message::ShowToast(message, is_error) => {
-                self.toast = Some(Toast { message, is_error });
+            Message::ApplyAffinity => {
+                if let Some(editor) = &self.affinity {
+                    self.controller.update(ProcessMessage::SetAffinity {
+                        pid: editor.pid,
+                        mask: editor.mask.clone(),
+                        all_threads: editor.all_threads,
+                    });
+                }
+                self.load_affinity();
+                Task::none()
             }
-            Message::ClearToast => {
-                self.toast = None;
+            Message::ListScrolled { offset, height } => {
+                // Scrolling does not change the rows
+                self.list.scrolled(offset, height);
+                return Task::none();
             }
+            Message::ListHovered(hovered) => {
+                self.list.set_hovered(hovered);
+                Task::none()
+            }
+            Message::SetMemoryMetric(metric) => {
+                match &self.config_handler {
+                    Some(handler) => {
+                        if let Err(why) = self.config.set_memory_metric(handler, metric) {
+                            eprintln!("failed to save memory metric: {why}");
+                        }
+                    }
+                    None => self.config.memory_metric = metric,
+                }
+                Task::none()
+            }
+            Message::ToggleDiskIo(show) => {
+                match &self.config_handler {
+                    Some(handler) => {
+                        if let Err(why) = self.config.set_show_disk_io(handler, show) {
+                            eprintln!("failed to save disk I/O columns: {why}");
+                        }
+                    }
+                    None => self.config.show_disk_io = show,
+                }
+                self.controller.set_show_disk_io(show);
+                Task::none()
+            }
+            Message::UpdateConfig(config) => {
+                self.controller.set_saved_searches(config.saved_searches.clone());
+                self.controller.set_show_disk_io(config.show_disk_io);
+                self.config = config;
+                Task::none()
+            }
+            Message::KeyPressed {
+                key,
+                modifiers,
+                text,
+                captured,
+            } => self.handle_key_press(key, modifiers, text, captured),
             Message::Close => {
                 return cosmic::iced::exit();
             }
-        }
-        Task::none()
+        };
+
+        Task::batch([
+            task,
+            self.sync_list(),
+            view::jobs(&mut self.controller, Message::Process),
+        ])
     }
 }
 
 impl StandaloneApp {
-    #[allow(dead_code)]
-    fn refresh_processes(&mut self) {
-        let mut processes = self.process_manager.get_processes(self.sort_by);
-        if !self.show_all {
-            processes.truncate(10);
+    fn apply_flags(&mut self, flags: Flags) {
+        if let Some(sort_by) = flags.sort_by {
+            self.controller.update(ProcessMessage::SortBy(sort_by));
+        }
+        if flags.show_all {
+            self.controller.update(ProcessMessage::ToggleShowAll(true));
+        }
+        if flags.tree {
+            self.tree_view = true;
+        }
+        if let Some(search) = flags.search {
+            self.controller.update(ProcessMessage::UpdateSearch(search));
+        }
+
+        if let Some(pid) = flags.pid {
+            if self.controller.has_snapshot() {
+                self.show_details(pid);
+            } else {
+                self.pending_details = Some(pid);
+            }
         }
-        self.processes = processes;
     }
 
-    #[allow(dead_code)]
-    fn get_filtered_processes(&self) -> Vec<&ProcessInfo> {
-        if self.search_query.is_empty() {
-            self.processes.iter().collect()
+    fn show_details(&mut self, pid: u32) {
+        if self.controller.reveal_process(pid) {
+            self.set_details(Some(pid));
         } else {
-            let query = self.search_query.to_lowercase();
-            self.processes
-                .iter()
-                .filter(|p| {
-                    p.name.to_lowercase().contains(&query)
-                        || p.pid.to_string().contains(&query)
-                })
-                .collect()
+            self.controller.update(ProcessMessage::ShowToast(
+                fl!("error-process-not-found"),
+                true,
+            ));
         }
     }
 
-    #[allow(dead_code)]
-    fn handle_kill_process(&mut self, pid: u32, force: bool) {
-        // Find the process
-        let process = match self.processes.iter().find(|p| p.pid == pid) {
-            Some(p) => p.clone(),
-            None => {
-                self.toast = Some(Toast {
-                    message: fl!("error-process-not-found"),
-                    is_error: true,
-                });
-                return;
+    /// Open the details panel on a process, or close it, and sample the
+    /// threads of whichever process it shows.
+    fn set_details(&mut self, pid: Option<u32>) {
+        self.details_pid = pid;
+        self.priority_draft = None;
+        self.limits_draft = None;
+        self.thread_menu = None;
+        self.load_affinity();
+        self.controller.update(ProcessMessage::WatchThreads(pid));
+    }
+
+    /// Read the cores the process in the details panel may run on.
+    fn load_affinity(&mut self) {
+        let all_threads = self.affinity.as_ref().is_none_or(|editor| editor.all_threads);
+        self.affinity = self.details_pid.map(|pid| {
+            let current = self.controller.affinity(pid).map_err(|e| e.to_string());
+            AffinityEditor {
+                pid,
+                mask: current.clone().unwrap_or_else(|_| CpuMask::all()),
+                current,
+                all_threads,
+            }
+        });
+    }
+
+    /// One checkbox per core, the current mask checked
+    fn view_affinity_editor<'a>(&self, pid: u32) -> Element<'a, Message> {
+        let label = widget::text(fl!("details-affinity"))
+            .size(12)
+            .width(Length::Fixed(120.0));
+        let Some(editor) = self.affinity.as_ref().filter(|editor| editor.pid == pid) else {
+            return widget::row().into();
+        };
+        let current = match &editor.current {
+            Ok(current) => current,
+            Err(error) => {
+                return widget::row()
+                    .spacing(12)
+                    .push(label)
+                    .push(widget::text(fl!("affinity-error", error = error.clone())).size(12))
+                    .into();
             }
         };
 
-        // Check permissions before showing confirmation
-        match self.process_manager.can_kill_process(&process) {
-            Err(ProcessError::PermissionDenied) => {
-                self.toast = Some(Toast {
-                    message: fl!("notification-permission-denied"),
-                    is_error: true,
-                });
-                return;
+        let grid = (0..editor.mask.len()).step_by(CORES_PER_ROW).fold(
+            widget::column().spacing(4),
+            |grid, first| {
+                let last = (first + CORES_PER_ROW).min(editor.mask.len());
+                grid.push((first..last).fold(widget::row().spacing(8), |row, cpu| {
+                    row.push(
+                        widget::checkbox(cpu.to_string(), editor.mask.contains(cpu))
+                            .on_toggle(move |allowed| Message::ToggleCore(cpu, allowed))
+                            .width(Length::Fixed(48.0)),
+                    )
+                }))
+            },
+        );
+
+        let actions = widget::row()
+            .spacing(12)
+            .align_y(Alignment::Center)
+            .push(
+                widget::checkbox(fl!("affinity-all-threads"), editor.all_threads)
+                    .on_toggle(Message::ToggleAllThreads),
+            )
+            .push(widget::button::text(fl!("affinity-all-cores")).on_press(Message::SelectAllCores))
+            .push(
+                widget::button::standard(fl!("priority-apply")).on_press_maybe(
+                    (editor.mask != *current && !editor.mask.is_empty())
+                        .then_some(Message::ApplyAffinity),
+                ),
+            );
+
+        widget::row()
+            .spacing(12)
+            .push(label)
+            .push(widget::column().spacing(6).push(grid).push(actions))
+            .into()
+    }
+
+    /// Change the draft of the process in the details panel, starting from
+    /// its current priorities.
+    fn edit_priority(&mut self, edit: impl FnOnce(&mut PriorityDraft)) {
+        let Some(process) = self
+            .details_pid
+            .and_then(|pid| self.controller.sampled_process(pid))
+        else {
+            return;
+        };
+        let mut draft = self
+            .priority_draft
+            .filter(|draft| draft.pid == process.pid)
+            .unwrap_or_else(|| PriorityDraft::of(process));
+        edit(&mut draft);
+        self.priority_draft = Some(draft);
+    }
+
+    /// Change the limits drafted for the process in the details panel,
+    /// starting from its current ones.
+    fn edit_limits(&mut self, edit: impl FnOnce(&mut Limits)) {
+        let Some(process) = self
+            .details_pid
+            .and_then(|pid| self.controller.sampled_process(pid))
+        else {
+            return;
+        };
+        let mut limits = self
+            .limits_draft
+            .filter(|(pid, _)| *pid == process.pid)
+            .map_or_else(|| process.limits.unwrap_or_default(), |(_, limits)| limits);
+        edit(&mut limits);
+        self.limits_draft = Some((process.pid, limits));
+    }
+
+    /// Send what changed in the draft to the controller.
+    fn apply_priority(&mut self) {
+        let Some(draft) = self.priority_draft.take() else {
+            return;
+        };
+        let Some(current) = self
+            .controller
+            .sampled_process(draft.pid)
+            .map(PriorityDraft::of)
+        else {
+            return;
+        };
+
+        if draft.nice != current.nice {
+            self.controller.update(ProcessMessage::SetNice {
+                pid: draft.pid,
+                nice: draft.nice,
+            });
+        }
+        if draft.io != current.io {
+            self.controller.update(ProcessMessage::SetIoPriority {
+                pid: draft.pid,
+                priority: draft.io,
+            });
+        }
+    }
+
+    /// Nice slider, I/O class and level, and a button applying them
+    fn view_priority_editor<'a>(&self, process: &ProcessInfo) -> Element<'a, Message> {
+        let current = PriorityDraft::of(process);
+        let draft = self
+            .priority_draft
+            .filter(|draft| draft.pid == process.pid)
+            .unwrap_or(current);
+        let label = |text: String| widget::text(text).size(12).width(Length::Fixed(120.0));
+
+        let nice = widget::row()
+            .spacing(12)
+            .align_y(Alignment::Center)
+            .push(label(fl!("details-nice")))
+            .push(
+                widget::slider(NICE_RANGE, draft.nice, Message::EditNice)
+                    .width(Length::Fixed(200.0)),
+            )
+            .push(widget::text(draft.nice.to_string()).size(12));
+
+        let class_index = IO_CLASSES.iter().position(|class| *class == draft.io.class);
+        let mut io = widget::row()
+            .spacing(12)
+            .align_y(Alignment::Center)
+            .push(label(fl!("details-io-priority")))
+            .push(widget::dropdown(&*IO_CLASS_NAMES, class_index, |index| {
+                Message::EditIoClass(IO_CLASSES[index])
+            }));
+        if draft.io.class != IoClass::Idle {
+            io = io
+                .push(
+                    widget::slider(IO_LEVELS, draft.io.level, Message::EditIoLevel)
+                        .width(Length::Fixed(100.0)),
+                )
+                .push(widget::text(draft.io.level.to_string()).size(12));
+        }
+
+        widget::column()
+            .spacing(6)
+            .push(nice)
+            .push(io)
+            .push(
+                widget::button::standard(fl!("priority-apply"))
+                    .on_press_maybe((draft != current).then_some(Message::ApplyPriority)),
+            )
+            .into()
+    }
+
+    /// The Flatpak, Snap or AppImage of a process, with a button killing
+    /// every process of it
+    fn view_package<'a>(process: &ProcessInfo) -> Element<'a, Message> {
+        let mut row = widget::row()
+            .spacing(12)
+            .align_y(Alignment::Center)
+            .push(widget::text(fl!("details-package")).size(12).width(Length::Fixed(120.0)));
+        let Some(package) = &process.package else {
+            return row.push(widget::text(fl!("package-none")).size(12)).into();
+        };
+
+        if let Some(icon) = package_icon(package) {
+            row = row.push(icon);
+        }
+        row.push(widget::text(package_label(package)).size(12))
+            .push(
+                widget::button::destructive(fl!("package-kill", app = package.app_id.clone()))
+                    .on_press(Message::KillPackage(ProcessMessage::KillPackage(process.pid))),
+            )
+            .into()
+    }
+
+    /// The container of a process, with a button stopping it
+    fn view_container<'a>(process: &ProcessInfo) -> Element<'a, Message> {
+        let row = widget::row()
+            .spacing(12)
+            .align_y(Alignment::Center)
+            .push(widget::text(fl!("details-container")).size(12).width(Length::Fixed(120.0)));
+        let Some(container) = &process.container else {
+            return row.push(widget::text(fl!("container-none")).size(12)).into();
+        };
+
+        row.push(
+            widget::tooltip(
+                widget::text(container_label(container)).size(12),
+                widget::text(container.id.clone()),
+                widget::tooltip::Position::Top,
+            ),
+        )
+        .push(
+            widget::button::destructive(fl!("container-stop"))
+                .on_press(Message::StopContainer(ProcessMessage::StopContainer(process.pid))),
+        )
+        .into()
+    }
+
+    /// Current memory and CPU limits, with dropdowns changing them. Apps and
+    /// services are limited as a whole, other processes on their own.
+    fn view_limits_editor<'a>(&self, process: &ProcessInfo) -> Element<'a, Message> {
+        let label = |text: String| widget::text(text).size(12).width(Length::Fixed(120.0));
+        let current = process.limits.unwrap_or_default();
+        let draft = self
+            .limits_draft
+            .filter(|(pid, _)| *pid == process.pid)
+            .map_or(current, |(_, limits)| limits);
+        let memory_index = MEMORY_LIMITS.iter().position(|limit| *limit == draft.memory_max);
+        let cpu_index = CPU_LIMITS.iter().position(|limit| *limit == draft.cpu_max);
+
+        let limits = widget::row()
+            .spacing(12)
+            .align_y(Alignment::Center)
+            .push(label(fl!("details-limits")))
+            .push(widget::text(describe_limits(process.limits)).size(12));
+        let editor = widget::row()
+            .spacing(12)
+            .align_y(Alignment::Center)
+            .push(label(String::new()))
+            .push(widget::text(fl!("limits-memory")).size(12))
+            .push(widget::dropdown(&*MEMORY_LIMIT_NAMES, memory_index, |index| {
+                Message::EditMemoryLimit(MEMORY_LIMITS[index])
+            }))
+            .push(widget::text(fl!("limits-cpu")).size(12))
+            .push(widget::dropdown(&*CPU_LIMIT_NAMES, cpu_index, |index| {
+                Message::EditCpuLimit(CPU_LIMITS[index])
+            }))
+            .push(
+                widget::button::standard(fl!("limits-apply"))
+                    .on_press_maybe((draft != current).then_some(Message::ApplyLimits)),
+            );
+
+        widget::column().spacing(6).push(limits).push(editor).into()
+    }
+
+    /// Presets offered by the priority menu of a row
+    fn view_priority_menu<'a>(&self, process: &ProcessInfo) -> Element<'a, Message> {
+        let pid = process.pid;
+        let preset = |label: String, message: ProcessMessage| {
+            widget::button::text(label)
+                .on_press(Message::SetPriority(message))
+                .width(Length::Fill)
+        };
+
+        let mut menu = widget::column()
+            .spacing(2)
+            .width(Length::Fixed(220.0))
+            .push(preset(fl!("priority-lower"), ProcessMessage::SetNice { pid, nice: 10 }))
+            .push(preset(fl!("priority-lowest"), ProcessMessage::SetNice { pid, nice: 19 }))
+            .push(preset(fl!("priority-normal"), ProcessMessage::SetNice { pid, nice: 0 }))
+            .push(preset(
+                fl!("priority-io-idle"),
+                ProcessMessage::SetIoPriority {
+                    pid,
+                    priority: IoPriority {
+                        class: IoClass::Idle,
+                        level: 0,
+                    },
+                },
+            ));
+        // Killing a service's process only gets it restarted
+        if process.unit.as_ref().is_some_and(Unit::is_manageable) {
+            menu = menu.push(widget::divider::horizontal::light());
+            for (label, action) in unit_actions() {
+                menu = menu.push(
+                    widget::button::text(label)
+                        .on_press(Message::UnitAction(ProcessMessage::UnitAction { pid, action }))
+                        .width(Length::Fill),
+                );
             }
-            Err(ProcessError::Protected(name)) => {
-                self.toast = Some(Toast {
-                    message: fl!("notification-protected", name = name),
-                    is_error: true,
-                });
-                return;
+        }
+        // Killing a container's process only gets it restarted as well
+        if process.container.is_some() {
+            menu = menu.push(widget::divider::horizontal::light()).push(
+                widget::button::text(fl!("container-stop"))
+                    .on_press(Message::StopContainer(ProcessMessage::StopContainer(pid)))
+                    .width(Length::Fill),
+            );
+        }
+        if let Some(package) = &process.package {
+            menu = menu.push(widget::divider::horizontal::light()).push(
+                widget::button::text(fl!("package-kill", app = package.app_id.clone()))
+                    .on_press(Message::KillPackage(ProcessMessage::KillPackage(pid)))
+                    .width(Length::Fill),
+            );
+        }
+        if process.app_cgroup.is_some() {
+            menu = menu.push(widget::divider::horizontal::light()).push(
+                widget::button::text(freeze_label(process.frozen))
+                    .on_press(freeze_message(process))
+                    .width(Length::Fill),
+            );
+        }
+
+        widget::container(menu)
+            .padding(4)
+            .class(cosmic::theme::Container::Dropdown)
+            .into()
+    }
+
+    fn view_details<'a>(&self, process: &'a ProcessInfo) -> Element<'a, Message> {
+        let field = |label: String, value: String| {
+            widget::row()
+                .spacing(12)
+                .push(widget::text(label).size(12).width(Length::Fixed(120.0)))
+                .push(widget::text(value).size(12))
+        };
+
+        let parent = process
+            .parent_pid
+            .map(|ppid| ppid.to_string())
+            .unwrap_or_else(|| "-".to_string());
+
+        let title = widget::row()
+            .spacing(8)
+            .align_y(Alignment::Center)
+            .push(widget::text(format!("{} (PID: {})", process.name, process.pid)).size(16))
+            .push(widget::horizontal_space())
+            .push(
+                widget::button::icon(widget::icon::from_name("window-close-symbolic"))
+                    .on_press(Message::ShowDetails(None))
+                    .padding(4),
+            );
+
+        let details = widget::column()
+            .spacing(6)
+            .padding(16)
+            .push(title)
+            .push(field(fl!("details-parent"), parent))
+            .push(field(fl!("details-status"), process.status.clone()))
+            .push(Self::view_unit(process))
+            .push(Self::view_app(process))
+            .push(Self::view_package(process))
+            .push(Self::view_container(process))
+            .push(field(
+                fl!("details-pid-namespace"),
+                process
+                    .pid_namespace
+                    .map_or_else(|| fl!("pid-namespace-host"), |namespace| namespace.to_string()),
+            ))
+            .push(field(
+                fl!("details-command"),
+                if process.cmdline.is_empty() {
+                    "-".to_string()
+                } else {
+                    process.cmdline.join(" ")
+                },
+            ))
+            .push(field(
+                fl!("details-executable"),
+                process
+                    .exe
+                    .as_ref()
+                    .map(|exe| exe.display().to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+            ))
+            .push(field(fl!("header-cpu"), format!("{:.1}%", process.cpu_usage)))
+            .push(field(
+                self.config.memory_header(),
+                view::memory_label(process.memory),
+            ))
+            .push(Self::view_memory_breakdown(process))
+            .push(field(
+                fl!("details-disk-io"),
+                fl!(
+                    "details-disk-rates",
+                    read = format_rate(process.disk_read),
+                    write = format_rate(process.disk_write)
+                ),
+            ))
+            .push(self.view_priority_editor(process))
+            .push(self.view_limits_editor(process))
+            .push(self.view_affinity_editor(process.pid))
+            .push(self.view_sockets())
+            .push(self.view_open_files())
+            .push(self.view_threads(process.pid));
+
+        widget::container(details)
+            .class(cosmic::theme::Container::Card)
+            .width(Length::Fill)
+            .into()
+    }
+
+    /// The systemd unit of a process, with buttons acting on it
+    fn view_unit<'a>(process: &ProcessInfo) -> Element<'a, Message> {
+        let mut row = widget::row()
+            .spacing(12)
+            .align_y(Alignment::Center)
+            .push(widget::text(fl!("details-unit")).size(12).width(Length::Fixed(120.0)))
+            .push(
+                widget::text(process.unit.as_ref().map_or_else(|| fl!("unit-none"), unit_label))
+                    .size(12),
+            );
+        if process.unit.as_ref().is_some_and(Unit::is_manageable) {
+            for (label, action) in unit_actions() {
+                let pid = process.pid;
+                row = row.push(
+                    widget::button::text(label)
+                        .on_press(Message::UnitAction(ProcessMessage::UnitAction { pid, action })),
+                );
             }
-            Err(e) => {
-                self.toast = Some(Toast {
-                    message: format!("{}: {:?}", fl!("error-unknown-error"), e),
-                    is_error: true,
+        }
+        row.into()
+    }
+
+    /// The app cgroup of a process and whether it is frozen, with a button
+    /// freezing or thawing it
+    fn view_app<'a>(process: &ProcessInfo) -> Element<'a, Message> {
+        let mut row = widget::row()
+            .spacing(12)
+            .align_y(Alignment::Center)
+            .push(widget::text(fl!("details-app")).size(12).width(Length::Fixed(120.0)));
+        let Some(app_cgroup) = &process.app_cgroup else {
+            return row.push(widget::text(fl!("app-none")).size(12)).into();
+        };
+
+        let app = cgroup::app_name(app_cgroup).to_string();
+        row = row.push(
+            widget::text(if process.frozen {
+                fl!("app-frozen", app = app)
+            } else {
+                app
+            })
+            .size(12),
+        );
+        row.push(
+            widget::button::text(freeze_label(process.frozen)).on_press(freeze_message(process)),
+        )
+        .into()
+    }
+
+    /// Where the memory of a process goes, from `smaps_rollup`
+    fn view_memory_breakdown<'a>(process: &ProcessInfo) -> Element<'a, Message> {
+        let field = |label: String, bytes: u64| {
+            widget::row()
+                .spacing(12)
+                .push(widget::text(label).size(12).width(Length::Fixed(120.0)))
+                .push(widget::text(format!("{} MB", bytes / 1024 / 1024)).size(12))
+        };
+
+        let Some(breakdown) = process.memory_breakdown else {
+            return widget::row()
+                .spacing(12)
+                .push(widget::text(fl!("details-memory")).size(12).width(Length::Fixed(120.0)))
+                .push(widget::text(fl!("details-memory-unavailable")).size(12))
+                .into();
+        };
+
+        widget::column()
+            .spacing(6)
+            .push(field(fl!("details-memory-rss"), breakdown.rss))
+            .push(field(fl!("details-memory-pss"), breakdown.pss))
+            .push(field(fl!("details-memory-private"), breakdown.private))
+            .push(field(fl!("details-memory-shared"), breakdown.shared))
+            .push(field(fl!("details-memory-swap"), breakdown.swap))
+            .into()
+    }
+
+    /// Sockets of the process in the details panel
+    fn view_sockets<'a>(&self) -> Element<'a, Message> {
+        let sockets = self.controller.sockets();
+        let mut list = widget::column()
+            .spacing(4)
+            .push(widget::text(fl!("details-sockets")).size(14));
+        if sockets.is_empty() {
+            return list
+                .push(widget::text(fl!("sockets-none")).size(12))
+                .into();
+        }
+
+        let mut rows = widget::column().spacing(2);
+        for socket in sockets {
+            let peer = match (&socket.remote, &socket.state) {
+                (Some(remote), Some(state)) => format!("→ {remote} {state}"),
+                (Some(remote), None) => format!("→ {remote}"),
+                (None, Some(state)) => state.clone(),
+                (None, None) => String::new(),
+            };
+            rows = rows.push(
+                widget::row()
+                    .spacing(12)
+                    .push(
+                        widget::text(socket.protocol.as_str())
+                            .size(12)
+                            .width(Length::Fixed(40.0)),
+                    )
+                    .push(
+                        widget::text(if socket.local.is_empty() {
+                            fl!("socket-unnamed")
+                        } else {
+                            socket.local.clone()
+                        })
+                        .size(12)
+                        .width(Length::Fixed(260.0)),
+                    )
+                    .push(widget::text(peer).size(12)),
+            );
+        }
+        list = list.push(widget::scrollable(rows).height(Length::Fixed(120.0)));
+        list.into()
+    }
+
+    /// Files held open by the process in the details panel
+    fn view_open_files<'a>(&self) -> Element<'a, Message> {
+        let files = self.controller.open_files();
+        let list = widget::column()
+            .spacing(4)
+            .push(widget::text(fl!("details-open-files")).size(14));
+        if files.is_empty() {
+            return list.push(widget::text(fl!("open-files-none")).size(12)).into();
+        }
+
+        let rows = files.iter().fold(widget::column().spacing(2), |rows, file| {
+            rows.push(
+                widget::row()
+                    .spacing(12)
+                    .push(
+                        widget::text(usage_label(file.usage))
+                            .size(12)
+                            .width(Length::Fixed(100.0)),
+                    )
+                    .push(widget::text(file.path.display().to_string()).size(12)),
+            )
+        });
+        list.push(widget::scrollable(rows).height(Length::Fixed(120.0)))
+            .into()
+    }
+
+    /// Confirmation of signalling several processes together
+    fn view_group_kill<'a>(&self, group: &GroupKill) -> Element<'a, Message> {
+        let signal = group.signal.as_str();
+        let count = group.processes.len();
+        let message = match &group.target {
+            GroupTarget::Holders(path) => {
+                let path = path.display().to_string();
+                fl!("confirm-holders-message", signal = signal, count = count, path = path)
+            }
+            GroupTarget::Package(package) => {
+                let app = package.app_id.clone();
+                fl!("confirm-package-message", count = count, app = app)
+            }
+        };
+        let names = group
+            .processes
+            .iter()
+            .filter_map(|key| self.controller.sampled_process(key.pid))
+            .map(|process| format!("{} (PID: {})", process.name, process.pid))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        widget::column()
+            .spacing(12)
+            .padding(16)
+            .push(widget::text(message).size(14))
+            .push(widget::text(names).size(12))
+            .push(
+                widget::row()
+                    .spacing(8)
+                    .push(
+                        widget::button::destructive(fl!("confirm"))
+                            .on_press(Message::Process(ProcessMessage::ConfirmGroupKill)),
+                    )
+                    .push(
+                        widget::button::text(fl!("cancel"))
+                            .on_press(Message::Process(ProcessMessage::CancelConfirmation)),
+                    ),
+            )
+            .into()
+    }
+
+    /// Confirmation of acting on a whole unit or container
+    fn view_pending_action<'a>(&self, pending: &PendingAction) -> Element<'a, Message> {
+        let message = match pending {
+            PendingAction::Unit { unit, action } => {
+                let unit = unit.name.clone();
+                match action {
+                    UnitAction::Stop => fl!("confirm-unit-stop", unit = unit),
+                    UnitAction::Restart => fl!("confirm-unit-restart", unit = unit),
+                    UnitAction::Kill => fl!("confirm-unit-kill", unit = unit),
+                }
+            }
+            PendingAction::StopContainer(container) => {
+                let container = container.label().to_string();
+                fl!("confirm-container-stop", container = container)
+            }
+        };
+
+        widget::column()
+            .spacing(12)
+            .padding(16)
+            .push(widget::text(message).size(14))
+            .push(
+                widget::row()
+                    .spacing(8)
+                    .push(
+                        widget::button::destructive(fl!("confirm"))
+                            .on_press(Message::Process(ProcessMessage::ConfirmPendingAction)),
+                    )
+                    .push(
+                        widget::button::text(fl!("cancel"))
+                            .on_press(Message::Process(ProcessMessage::CancelConfirmation)),
+                    ),
+            )
+            .into()
+    }
+
+    /// Dialog finding the processes that keep a file or mount point busy
+    fn view_holders_dialog<'a>(&self, path: &str) -> Element<'a, Message> {
+        let valid = Path::new(path.trim()).is_absolute();
+        let title = widget::row()
+            .spacing(8)
+            .align_y(Alignment::Center)
+            .push(widget::text(fl!("holders-title")).size(16))
+            .push(widget::horizontal_space())
+            .push(
+                widget::button::icon(widget::icon::from_name("window-close-symbolic"))
+                    .on_press(Message::HoldersDialog(false))
+                    .padding(4),
+            );
+        let input = widget::row()
+            .spacing(8)
+            .align_y(Alignment::Center)
+            .push(
+                widget::text_input(fl!("holders-placeholder"), path.to_string())
+                    .on_input(Message::HoldersPath)
+                    .width(Length::Fill),
+            )
+            .push(
+                widget::button::standard(fl!("holders-find"))
+                    .on_press_maybe(valid.then_some(Message::FindHolders)),
+            );
+
+        let mut dialog = widget::column()
+            .spacing(12)
+            .padding(16)
+            .push(title)
+            .push(input);
+
+        let Some(lookup) = self.controller.holder_lookup() else {
+            return widget::container(dialog)
+                .class(cosmic::theme::Container::Card)
+                .width(Length::Fill)
+                .into();
+        };
+        let looked_for = lookup.path.display().to_string();
+
+        match &lookup.holders {
+            None => {
+                dialog = dialog.push(widget::text(fl!("holders-searching")).size(12));
+            }
+            Some(holders) if holders.is_empty() => {
+                dialog = dialog.push(
+                    widget::text(fl!("holders-none", path = looked_for)).size(12),
+                );
+            }
+            Some(holders) => {
+                let rows = holders.iter().fold(widget::column().spacing(4), |rows, holder| {
+                    let name = self
+                        .controller
+                        .sampled_process(holder.pid)
+                        .map(|process| process.name.clone())
+                        .unwrap_or_else(|| "?".to_string());
+                    rows.push(
+                        widget::row()
+                            .spacing(12)
+                            .push(
+                                widget::text(format!("{name} (PID: {})", holder.pid))
+                                    .size(12)
+                                    .width(Length::Fixed(NAME_WIDTH)),
+                            )
+                            .push(widget::text(describe_files(&holder.files)).size(12)),
+                    )
                 });
-                return;
+                // Also keeps the list of matching processes in view
+                let filter = if looked_for.contains(char::is_whitespace) {
+                    format!("file:\"{looked_for}\"")
+                } else {
+                    format!("file:{looked_for}")
+                };
+
+                dialog = dialog
+                    .push(
+                        widget::text(fl!(
+                            "holders-found",
+                            count = holders.len(),
+                            path = looked_for.clone()
+                        ))
+                        .size(12),
+                    )
+                    .push(widget::scrollable(rows).height(Length::Fixed(160.0)))
+                    .push(
+                        widget::row()
+                            .spacing(8)
+                            .push(
+                                widget::button::destructive(fl!("holders-kill"))
+                                    .on_press(Message::Process(ProcessMessage::KillHolders {
+                                        force: false,
+                                    })),
+                            )
+                            .push(
+                                widget::button::destructive(fl!("holders-force-kill"))
+                                    .on_press(Message::Process(ProcessMessage::KillHolders {
+                                        force: true,
+                                    })),
+                            )
+                            .push(
+                                widget::button::text(fl!("holders-filter"))
+                                    .on_press(Message::Process(ProcessMessage::UpdateSearch(
+                                        filter,
+                                    ))),
+                            ),
+                    );
             }
-            Ok(()) => {}
         }
 
-        // Show confirmation dialog
-        self.selected_process = Some(process);
-        self.confirmation_mode = Some(if force {
-            ConfirmationMode::ForceKill
-        } else {
-            ConfirmationMode::Kill
-        });
+        widget::container(dialog)
+            .class(cosmic::theme::Container::Card)
+            .width(Length::Fill)
+            .into()
     }
 
-    #[allow(dead_code)]
-    fn execute_kill(&mut self, process: &ProcessInfo, force: bool) {
-        let result = if force {
-            self.process_manager.force_kill_process(process.pid)
+    /// Threads of the process in the details panel, each with a signal menu
+    fn view_threads<'a>(&self, pid: u32) -> Element<'a, Message> {
+        let header = |label: String, sort_by: SortBy, width: f32| {
+            widget::button::custom(widget::text(label).size(12))
+                .on_press(Message::Process(ProcessMessage::SortThreadsBy(sort_by)))
+                .padding(0)
+                .class(cosmic::theme::Button::Text)
+                .width(Length::Fixed(width))
+        };
+        let header_row = widget::row()
+            .spacing(12)
+            .push(header(fl!("header-tid"), SortBy::Pid, 70.0))
+            .push(header(fl!("header-name"), SortBy::Name, 180.0))
+            .push(header(fl!("header-cpu"), SortBy::Cpu, 60.0))
+            .push(widget::text(fl!("header-state")).size(12));
+
+        let mut rows = widget::column().spacing(2);
+        for thread in self.controller.threads() {
+            let tid = thread.tid;
+            let menu_open = self.thread_menu == Some(tid);
+            let mut menu_button = widget::popover(widget::tooltip(
+                widget::button::custom(widget::icon::from_name("view-more-symbolic"))
+                    .on_press(Message::ThreadMenu((!menu_open).then_some(tid)))
+                    .padding(2)
+                    .class(cosmic::theme::Button::Text),
+                widget::text(fl!("thread-signal-tooltip")),
+                widget::tooltip::Position::Top,
+            ));
+            if menu_open {
+                menu_button = menu_button
+                    .popup(Self::view_thread_menu(pid, tid))
+                    .on_close(Message::ThreadMenu(None));
+            }
+
+            rows = rows.push(
+                widget::row()
+                    .spacing(12)
+                    .align_y(Alignment::Center)
+                    .push(widget::text(tid.to_string()).size(12).width(Length::Fixed(70.0)))
+                    .push(widget::text(thread.name.clone()).size(12).width(Length::Fixed(180.0)))
+                    .push(
+                        widget::text(format!("{:.1}%", thread.cpu_usage))
+                            .size(12)
+                            .width(Length::Fixed(60.0)),
+                    )
+                    .push(widget::text(thread.status.clone()).size(12))
+                    .push(widget::horizontal_space())
+                    .push(menu_button),
+            );
+        }
+
+        widget::column()
+            .spacing(4)
+            .push(widget::text(fl!("details-threads")).size(14))
+            .push(header_row)
+            .push(widget::scrollable(rows).height(Length::Fixed(180.0)))
+            .into()
+    }
+
+    /// Signals offered by the menu of a thread
+    fn view_thread_menu<'a>(pid: u32, tid: u32) -> Element<'a, Message> {
+        let signal = |label: String, signal: Signal| {
+            widget::button::text(label)
+                .on_press(Message::SignalThread(ProcessMessage::SignalThread {
+                    pid,
+                    tid,
+                    signal,
+                }))
+                .width(Length::Fill)
+        };
+
+        let menu = widget::column()
+            .spacing(2)
+            .width(Length::Fixed(260.0))
+            .push(signal(fl!("thread-signal-usr1"), Signal::SIGUSR1))
+            .push(signal(fl!("thread-signal-usr2"), Signal::SIGUSR2));
+
+        widget::container(menu)
+            .padding(4)
+            .class(cosmic::theme::Container::Dropdown)
+            .into()
+    }
+
+    /// Hand the current rows to the virtualized list, scrolling to keep the
+    /// same process at the top if the order changed.
+    fn sync_list(&mut self) -> Task<cosmic::Action<Message>> {
+        // Rows stay put while a kill is being confirmed
+        self.list.set_paused(self.controller.is_confirming());
+        let pids: Vec<u32> = self.visible_rows().iter().map(|(_, p)| p.pid).collect();
+        self.scroll_list(self.list.set_rows(pids))
+    }
+
+    fn scroll_list(&self, offset: Option<f32>) -> Task<cosmic::Action<Message>> {
+        match offset {
+            Some(y) => scrollable::scroll_to(LIST_ID.clone(), AbsoluteOffset { x: 0.0, y }),
+            None => Task::none(),
+        }
+    }
+
+    /// Rows in the order the controller gives them, with their depth in tree view.
+    /// Grouped by container, each container's processes follow each other.
+    fn visible_rows(&self) -> Vec<(usize, &ProcessInfo)> {
+        let mut filtered_processes = self.controller.filtered_processes();
+        if self.group_by_container {
+            filtered_processes = container_order(&filtered_processes);
+        }
+        if self.tree_view {
+            tree_order(&filtered_processes)
         } else {
-            self.process_manager.kill_process(process.pid)
+            filtered_processes.into_iter().map(|p| (0, p)).collect()
+        }
+    }
+
+    fn handle_key_press(
+        &mut self,
+        key: Key,
+        modifiers: keyboard::Modifiers,
+        text: Option<String>,
+        captured: bool,
+    ) -> Task<cosmic::Action<Message>> {
+        let name = match &key {
+            Key::Named(named) => format!("{named:?}"),
+            Key::Character(c) => c.to_string(),
+            Key::Unidentified => return Task::none(),
+        };
+        let pressed = Modifiers {
+            ctrl: modifiers.control(),
+            alt: modifiers.alt(),
+            shift: modifiers.shift(),
         };
 
-        match result {
-            Ok(()) => {
-                self.toast = Some(Toast {
-                    message: if force {
-                        fl!("notification-force-kill-success", name = process.name.clone())
-                    } else {
-                        fl!("notification-kill-success", name = process.name.clone())
-                    },
-                    is_error: false,
-                });
-                self.refresh_processes();
+        match self.config.key_bindings.action_for(&name, pressed) {
+            // A pending confirmation can be answered even from the search box
+            Some(action @ (KeyAction::Confirm | KeyAction::Cancel))
+                if self.controller.is_confirming() =>
+            {
+                self.handle_key_action(action)
             }
-            Err(e) => {
-                let error_msg = match e {
-                    ProcessError::SignalFailed(msg) => {
-                        if force {
-                            fl!("error-sigkill-failed", error = msg)
-                        } else {
-                            fl!("error-sigterm-failed", error = msg)
-                        }
-                    }
-                    ProcessError::PermissionDenied => fl!("notification-permission-denied"),
-                    ProcessError::NotFound => fl!("error-process-not-found"),
-                    ProcessError::Protected(name) => {
-                        fl!("notification-protected", name = name)
-                    }
-                    ProcessError::Unknown(msg) => {
-                        fl!("error-unknown-error", error = msg)
+            // Other keys typed into the search box are not shortcuts
+            Some(_) if captured => Task::none(),
+            Some(action) => self.handle_key_action(action),
+            None if captured || pressed.ctrl || pressed.alt => Task::none(),
+            None => {
+                // Type-to-search: start typing anywhere to filter the list
+                match text.filter(|text| text.chars().all(|c| !c.is_control())) {
+                    Some(text) if !text.is_empty() => {
+                        let query = format!("{}{}", self.controller.search_query(), text);
+                        self.controller.update(ProcessMessage::UpdateSearch(query));
+                        widget::text_input::focus(SEARCH_INPUT_ID.clone())
                     }
+                    _ => Task::none(),
+                }
+            }
+        }
+    }
+
+    fn handle_key_action(&mut self, action: KeyAction) -> Task<cosmic::Action<Message>> {
+        match action {
+            KeyAction::SelectNext | KeyAction::SelectPrevious => {
+                let pids = self.list.rows();
+                let current = self
+                    .controller
+                    .selected_process()
+                    .and_then(|selected| pids.iter().position(|pid| *pid == selected.pid));
+                let next = match (action, current) {
+                    (_, None) => 0,
+                    (KeyAction::SelectNext, Some(i)) => (i + 1).min(pids.len().saturating_sub(1)),
+                    (_, Some(i)) => i.saturating_sub(1),
                 };
-                
-                self.toast = Some(Toast {
-                    message: fl!("notification-kill-failed", error = error_msg),
-                    is_error: true,
-                });
-                self.refresh_processes();
+                if let Some(&pid) = pids.get(next) {
+                    self.controller
+                        .update(ProcessMessage::SelectProcess(Some(pid)));
+                    let offset = self.list.reveal(pid);
+                    return self.scroll_list(offset);
+                }
+            }
+            KeyAction::Kill | KeyAction::ForceKill => {
+                if !self.controller.is_confirming() {
+                    if let Some(pid) = self.controller.selected_process().map(|p| p.pid) {
+                        self.controller.update(if action == KeyAction::ForceKill {
+                            ProcessMessage::ForceKillProcess(pid)
+                        } else {
+                            ProcessMessage::KillProcess(pid)
+                        });
+                    }
+                }
+            }
+            KeyAction::Confirm => match self.controller.confirmation() {
+                Some((_, ConfirmationMode::Kill)) => {
+                    self.controller.update(ProcessMessage::ConfirmKill);
+                }
+                Some((_, ConfirmationMode::ForceKill)) => {
+                    self.controller.update(ProcessMessage::ConfirmForceKill);
+                }
+                None if self.controller.group_kill().is_some() => {
+                    self.controller.update(ProcessMessage::ConfirmGroupKill);
+                }
+                None if self.controller.pending_action().is_some() => {
+                    self.controller.update(ProcessMessage::ConfirmPendingAction);
+                }
+                None => {}
+            },
+            KeyAction::Cancel => {
+                if self.controller.is_confirming() {
+                    self.controller.update(ProcessMessage::CancelConfirmation);
+                } else if self.details_pid.is_some() {
+                    self.set_details(None);
+                } else {
+                    self.controller
+                        .update(ProcessMessage::UpdateSearch(String::new()));
+                }
             }
         }
+
+        Task::none()
+    }
+
+    /// Write the visible list to a snapshot file in the home directory.
+    fn export_processes(&mut self, format: ExportFormat) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let directory = std::env::var_os("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("."));
+        let path = directory.join(format!(
+            "process-snapshot-{}.{}",
+            timestamp,
+            format.extension()
+        ));
+
+        let result = std::fs::File::create(&path).and_then(|file| {
+            export::write_snapshot(
+                std::io::BufWriter::new(file),
+                format,
+                self.config.memory_metric,
+                &self.controller.filtered_processes(),
+            )
+        });
+
+        self.controller.update(match result {
+            Ok(()) => ProcessMessage::ShowToast(
+                fl!("notification-export-success", path = path.display().to_string()),
+                false,
+            ),
+            Err(e) => ProcessMessage::ShowToast(
+                fl!("error-export-failed", error = e.to_string()),
+                true,
+            ),
+        });
     }
 
-    #[allow(dead_code)]
-    fn create_process_row<'a>(&self, process: &'a ProcessInfo) -> Element<'a, Message> {
-        let is_selected = self.selected_process.as_ref().map(|p| p.pid) == Some(process.pid);
+    fn create_process_row<'a>(
+        &self,
+        process: &'a ProcessInfo,
+        depth: usize,
+    ) -> Element<'a, Message> {
+        let is_selected = self.controller.is_selected(process);
 
-        // Truncate name if too long
-        let display_name = if process.name.len() > 25 {
-            format!("{}...", &process.name[..22])
+        // Program and arguments, with the search matches in bold
+        let label = truncate_name(&process.label(), NAME_CHARS.saturating_sub(2 * depth));
+
+        // Indent children in tree view
+        let indent = if depth > 0 {
+            format!("{}└ ", "  ".repeat(depth - 1))
         } else {
-            process.name.clone()
+            String::new()
         };
 
-        let name_text = widget::text(display_name.clone())
-            .size(14)
-            .width(Length::Fixed(180.0));
+        let name_text = rich_text(
+            std::iter::once(span(indent))
+                .chain(view::search_spans(&self.controller, &label))
+                .collect::<Vec<_>>(),
+        )
+        .size(14);
+        // Packaged apps show their icon, frozen apps a mark after the name
+        let mut name_line = widget::row().spacing(4).align_y(Alignment::Center);
+        if let Some(icon) = process.package.as_ref().and_then(package_icon) {
+            name_line = name_line.push(icon);
+        }
+        name_line = name_line.push(name_text);
+        if process.frozen {
+            name_line = name_line.push(frozen_indicator(process));
+        }
+        // Containers show their name below the name, packaged apps their ID
+        // and services their unit
+        let subtitle = match (&process.container, &process.package, &process.unit) {
+            (Some(container), _, _) => Some(container_label(container)),
+            (None, Some(package), _) => Some(package_label(package)),
+            (None, None, Some(unit)) => Some(unit.name.clone()),
+            (None, None, None) => None,
+        };
+        let name_column = match subtitle {
+            Some(subtitle) => widget::column()
+                .push(name_line)
+                .push(widget::text(subtitle).size(10)),
+            None => widget::column().push(name_line),
+        }
+        .width(Length::Fixed(NAME_WIDTH));
 
         let pid_text = widget::text(format!("PID: {}", process.pid))
             .size(12)
@@ -498,18 +1959,18 @@ impl StandaloneApp {
             .width(Length::Fixed(80.0))
             .align_x(cosmic::iced::alignment::Horizontal::Center);
 
-        let memory_text = widget::text(format!("{} MB", process.memory / 1024 / 1024))
+        let memory_text = widget::text(view::memory_label(process.memory))
             .size(12)
             .width(Length::Fixed(90.0))
             .align_x(cosmic::iced::alignment::Horizontal::Center);
 
         // Check if process can be killed
-        let can_kill = self.process_manager.can_kill_process(process).is_ok();
+        let can_kill = self.controller.can_kill(process);
 
         // Action buttons
         let kill_button = widget::tooltip(
             widget::button::custom(widget::icon::from_name("process-stop-symbolic"))
-                .on_press(Message::KillProcess(process.pid))
+                .on_press(Message::Process(ProcessMessage::KillProcess(process.pid)))
                 .padding(4)
                 .class(cosmic::theme::Button::Text),
             widget::text(fl!("kill-tooltip")),
@@ -518,38 +1979,69 @@ impl StandaloneApp {
 
         let force_kill_button = widget::tooltip(
             widget::button::custom(widget::icon::from_name("edit-delete-symbolic"))
-                .on_press(Message::ForceKillProcess(process.pid))
+                .on_press(Message::Process(ProcessMessage::ForceKillProcess(process.pid)))
                 .padding(4)
                 .class(cosmic::theme::Button::Text),
             widget::text(fl!("force-kill-tooltip")),
             widget::tooltip::Position::Top,
         );
 
+        let details_button = widget::tooltip(
+            widget::button::custom(widget::icon::from_name("dialog-information-symbolic"))
+                .on_press(Message::ShowDetails(Some(process.pid)))
+                .padding(4)
+                .class(cosmic::theme::Button::Text),
+            widget::text(fl!("details-tooltip")),
+            widget::tooltip::Position::Top,
+        );
+
+        let menu_open = self.priority_menu == Some(process.pid);
+        let mut priority_button = widget::popover(widget::tooltip(
+            widget::button::custom(widget::icon::from_name("view-more-symbolic"))
+                .on_press(Message::PriorityMenu((!menu_open).then_some(process.pid)))
+                .padding(4)
+                .class(cosmic::theme::Button::Text),
+            widget::text(fl!("priority-tooltip")),
+            widget::tooltip::Position::Top,
+        ));
+        if menu_open {
+            priority_button = priority_button
+                .popup(self.view_priority_menu(process))
+                .on_close(Message::PriorityMenu(None));
+        }
+
         let buttons: cosmic::widget::Row<'_, Message> = if can_kill {
             widget::row()
                 .spacing(6)
+                .push(details_button)
+                .push(priority_button)
                 .push(kill_button)
                 .push(force_kill_button)
         } else {
             widget::row()
                 .spacing(6)
+                .push(details_button)
+                .push(priority_button)
                 .push(
                     widget::button::icon(widget::icon::from_name("lock-symbolic"))
                         .padding(4)
                 )
         };
 
-        let info_row = widget::row()
+        let mut info_row = widget::row()
             .spacing(12)
             .align_y(Alignment::Center)
-            .push(name_text)
+            .push(name_column)
             .push(pid_text)
             .push(cpu_text)
-            .push(memory_text)
-            .push(widget::horizontal_space());
+            .push(memory_text);
+        for rate in view::disk_io_rates(&self.controller, process, 12, 90.0) {
+            info_row = info_row.push(rate);
+        }
+        let info_row = info_row.push(widget::horizontal_space());
 
         let info_button = widget::button::custom(info_row)
-            .on_press(Message::SelectProcess(Some(process.pid)))
+            .on_press(Message::Process(ProcessMessage::SelectProcess(Some(process.pid))))
             .padding([10, 5])
             .width(Length::Fill)
             .class(if is_selected {
@@ -566,3 +2058,137 @@ impl StandaloneApp {
             .into()
     }
 }
+
+/// How a process holds a file, as listed in the details panel
+fn usage_label(usage: Usage) -> String {
+    match usage {
+        Usage::Descriptor(fd) => fl!("open-file-fd", fd = fd),
+        Usage::Mapped => fl!("open-file-mapped"),
+        Usage::WorkingDirectory => fl!("open-file-cwd"),
+        Usage::RootDirectory => fl!("open-file-root"),
+        Usage::Executable => fl!("open-file-exe"),
+    }
+}
+
+/// The files a holder keeps busy, e.g. "fd 3: /mnt/usb/notes.txt"
+fn describe_files(files: &[OpenFile]) -> String {
+    files
+        .iter()
+        .map(|file| format!("{}: {}", usage_label(file.usage), file.path.display()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A unit and the systemd manager it belongs to
+fn unit_label(unit: &Unit) -> String {
+    match unit.scope {
+        UnitScope::System => fl!("unit-scope-system", unit = unit.name.clone()),
+        UnitScope::User => fl!("unit-scope-user", unit = unit.name.clone()),
+    }
+}
+
+/// The app ID of a package and how it is packaged
+fn package_label(package: &Package) -> String {
+    let app_id = package.app_id.clone();
+    match package.kind {
+        PackageKind::Flatpak => fl!("package-flatpak", app = app_id),
+        PackageKind::Snap => fl!("package-snap", app = app_id),
+        PackageKind::AppImage => fl!("package-appimage", app = app_id),
+    }
+}
+
+/// The name or short ID of a container and its runtime
+fn container_label(container: &Container) -> String {
+    let name = container.label().to_string();
+    match container.runtime {
+        Runtime::Docker => fl!("container-docker", container = name),
+        Runtime::Podman => fl!("container-podman", container = name),
+    }
+}
+
+/// The icon of a packaged app, from the icon theme or its own file
+fn package_icon<'a>(package: &Package) -> Option<Element<'a, Message>> {
+    let icon = package.icon.as_deref()?;
+    let handle = if icon.starts_with('/') {
+        widget::icon::from_path(PathBuf::from(icon))
+    } else {
+        widget::icon::from_name(icon).handle()
+    };
+    Some(widget::icon(handle).size(16).into())
+}
+
+fn memory_limit_label(limit: Option<u64>) -> String {
+    match limit {
+        None => fl!("limit-none"),
+        Some(bytes) if bytes >= GB => format!("{} GB", bytes as f64 / GB as f64),
+        Some(bytes) => format!("{} MB", bytes / 1024 / 1024),
+    }
+}
+
+fn cpu_limit_label(limit: Option<u32>) -> String {
+    match limit {
+        None => fl!("limit-none"),
+        Some(percent) => format!("{percent}%"),
+    }
+}
+
+/// The limits of a process as read, e.g. "Memory: 2 GB, CPU: 50%"
+fn describe_limits(limits: Option<Limits>) -> String {
+    match limits {
+        None => fl!("limits-unknown"),
+        Some(Limits {
+            memory_max: None,
+            cpu_max: None,
+        }) => fl!("limit-none"),
+        Some(limits) => fl!(
+            "limits-current",
+            memory = memory_limit_label(limits.memory_max),
+            cpu = cpu_limit_label(limits.cpu_max)
+        ),
+    }
+}
+
+/// What freezing would do to a process's app, given whether it is frozen
+fn freeze_label(frozen: bool) -> String {
+    if frozen {
+        fl!("app-thaw")
+    } else {
+        fl!("app-freeze")
+    }
+}
+
+/// Freeze the app of a process, or thaw it if it is frozen
+fn freeze_message(process: &ProcessInfo) -> Message {
+    Message::SetFrozen(ProcessMessage::SetFrozen {
+        pid: process.pid,
+        frozen: !process.frozen,
+    })
+}
+
+/// Marks a process of a frozen app; clicking thaws it
+fn frozen_indicator<'a>(process: &ProcessInfo) -> Element<'a, Message> {
+    let app = process
+        .app_cgroup
+        .as_deref()
+        .map(cgroup::app_name)
+        .unwrap_or_default()
+        .to_string();
+    widget::tooltip(
+        widget::button::icon(widget::icon::from_name("media-playback-pause-symbolic"))
+            .icon_size(12)
+            .padding(0)
+            .on_press(freeze_message(process)),
+        widget::text(fl!("frozen-tooltip", app = app)),
+        widget::tooltip::Position::Top,
+    )
+    .into()
+}
+
+/// Actions on a unit, in menu order
+fn unit_actions() -> [(String, UnitAction); 3] {
+    [
+        (fl!("unit-stop"), UnitAction::Stop),
+        (fl!("unit-restart"), UnitAction::Restart),
+        (fl!("unit-kill"), UnitAction::Kill),
+    ]
+}
@@ -0,0 +1,324 @@
+// SPDX-License-Identifier: MIT
+
+//! systemd units of processes, and acting on whole units.
+//!
+//! Killing the main process of a service only makes systemd restart it, so
+//! services are stopped, restarted or killed through the systemd manager's
+//! D-Bus API instead. The unit of a process is the innermost unit named in
+//! its cgroup (`/proc/<pid>/cgroup`). Units below `user@<uid>.service`
+//! belong to that user's manager, reached on the session bus for our own
+//! units and not at all for other users'; the others to the system manager,
+//! which asks for administrator rights through polkit.
+//! Resource limits of units are set the same way, as runtime properties
+//! that last until the unit stops; a process outside of any unit that can
+//! be limited is moved into a new scope with the limits first. The calls
+//! run in the background, so that prompt does not freeze the window while
+//! it waits.
+
+use crate::cgroup::Limits;
+use crate::process::{ProcessError, ProcessResult};
+use futures_util::future::BoxFuture;
+use nix::sys::signal::Signal;
+use serde::{Deserialize, Serialize};
+use zbus::zvariant::{OwnedObjectPath, Value};
+
+/// Which systemd manager a unit belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitScope {
+    System,
+    User,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Unit {
+    /// Full unit name, such as `nginx.service` or `app-firefox.scope`
+    pub name: String,
+    pub scope: UnitScope,
+    /// The user whose manager runs it, for units of [`UnitScope::User`]
+    #[serde(default)]
+    pub uid: Option<u32>,
+}
+
+impl Unit {
+    /// Whether the unit can be acted on from here without taking the whole
+    /// session or system down: login sessions, user managers and init
+    /// cannot be, nor units of other users, whose managers are not on our
+    /// session bus.
+    pub fn is_manageable(&self) -> bool {
+        !(self.name == "init.scope"
+            || (self.name.starts_with("session-") && self.name.ends_with(".scope"))
+            || (self.name.starts_with("user@") && self.name.ends_with(".service"))
+            || !self.is_own())
+    }
+
+    /// Whether the unit belongs to the system manager or our own
+    fn is_own(&self) -> bool {
+        match self.scope {
+            UnitScope::System => true,
+            UnitScope::User => self.uid == Some(nix::unistd::getuid().as_raw()),
+        }
+    }
+}
+
+/// What to do with a unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitAction {
+    Stop,
+    Restart,
+    /// SIGKILL to every process of the unit
+    Kill,
+}
+
+/// Unit types that hold processes; slices only group other units
+const UNIT_SUFFIXES: [&str; 5] = [".service", ".scope", ".socket", ".mount", ".swap"];
+
+/// The cgroup path in the content of `/proc/<pid>/cgroup`: the unified
+/// hierarchy (`0::/path`), or systemd's own on legacy systems.
+pub fn parse_cgroup(content: &str) -> Option<&str> {
+    let path_of = |prefix: &str| {
+        content
+            .lines()
+            .find_map(|line| line.split_once(':')?.1.strip_prefix(prefix))
+    };
+    path_of(":").or_else(|| path_of("name=systemd:"))
+}
+
+/// The innermost unit of a cgroup path such as
+/// `/user.slice/user-1000.slice/user@1000.service/app.slice/app-firefox.scope`
+pub fn unit_of(cgroup: &str) -> Option<Unit> {
+    let mut unit = None;
+    let mut scope = UnitScope::System;
+    let mut uid = None;
+    for component in cgroup.split('/') {
+        if !UNIT_SUFFIXES
+            .iter()
+            .any(|suffix| component.ends_with(suffix))
+        {
+            continue;
+        }
+        unit = Some(Unit {
+            name: component.to_string(),
+            scope,
+            uid,
+        });
+        // Units below it are managed by the user's own systemd
+        if let Some(user) = component
+            .strip_prefix("user@")
+            .and_then(|rest| rest.strip_suffix(".service"))
+        {
+            scope = UnitScope::User;
+            uid = user.parse().ok();
+        }
+    }
+    unit
+}
+
+/// The cgroup of the innermost unit in a cgroup path: the path up to and
+/// including that unit
+pub fn unit_cgroup(cgroup: &str) -> Option<&str> {
+    let mut end = None;
+    let mut offset = 0;
+    for component in cgroup.split('/') {
+        offset += component.len();
+        if UNIT_SUFFIXES
+            .iter()
+            .any(|suffix| component.ends_with(suffix))
+        {
+            end = Some(offset);
+        }
+        offset += 1;
+    }
+    end.map(|end| &cgroup[..end])
+}
+
+/// Something that can act on systemd units
+pub trait UnitManager: Send + Sync {
+    /// Act on `unit`, done once the returned future is.
+    fn run(&self, unit: &Unit, action: UnitAction) -> BoxFuture<'static, ProcessResult<()>>;
+
+    /// Limit the memory and CPU time of every process of a unit until it
+    /// stops, done once the returned future is.
+    fn set_limits(&self, unit: &Unit, limits: Limits) -> BoxFuture<'static, ProcessResult<()>>;
+
+    /// Move process `pid` of user `uid` out of its unit into a scope of its
+    /// own, limited to `limits`, done once the returned future is.
+    fn limit_process(
+        &self,
+        pid: u32,
+        uid: Option<u32>,
+        limits: Limits,
+    ) -> BoxFuture<'static, ProcessResult<()>>;
+}
+
+/// Acts on units through the system or session bus
+pub struct SystemdUnits;
+
+impl SystemdUnits {
+    /// Connect to the bus of the manager of `unit`. Our session bus only
+    /// reaches our own manager, never that of another user.
+    async fn connect(unit: &Unit) -> ProcessResult<zbus::Connection> {
+        if !unit.is_own() {
+            return Err(ProcessError::PermissionDenied);
+        }
+        match unit.scope {
+            UnitScope::System => zbus::Connection::system().await,
+            UnitScope::User => zbus::Connection::session().await,
+        }
+        .map_err(action_error)
+    }
+}
+
+impl UnitManager for SystemdUnits {
+    fn run(&self, unit: &Unit, action: UnitAction) -> BoxFuture<'static, ProcessResult<()>> {
+        let unit = unit.clone();
+        Box::pin(async move {
+            let connection = Self::connect(&unit).await?;
+            run_action(&connection, &unit.name, action).await
+        })
+    }
+
+    fn set_limits(&self, unit: &Unit, limits: Limits) -> BoxFuture<'static, ProcessResult<()>> {
+        let unit = unit.clone();
+        Box::pin(async move {
+            let connection = Self::connect(&unit).await?;
+            set_unit_limits(&connection, &unit.name, limits).await
+        })
+    }
+
+    /// Only the system manager can move processes out of any unit, such as
+    /// a login session, so it asks for administrator rights.
+    fn limit_process(
+        &self,
+        pid: u32,
+        uid: Option<u32>,
+        limits: Limits,
+    ) -> BoxFuture<'static, ProcessResult<()>> {
+        Box::pin(async move {
+            let connection = zbus::Connection::system().await.map_err(action_error)?;
+            start_limited_scope(&connection, pid, uid, limits)
+                .await
+                .map(drop)
+        })
+    }
+}
+
+/// The part of `org.freedesktop.systemd1.Manager` used here
+#[zbus::proxy(
+    interface = "org.freedesktop.systemd1.Manager",
+    default_service = "org.freedesktop.systemd1",
+    default_path = "/org/freedesktop/systemd1"
+)]
+pub trait SystemdManager {
+    // Units of the system manager need polkit to ask for a password
+    #[zbus(allow_interactive_auth)]
+    fn stop_unit(&self, name: &str, mode: &str) -> zbus::Result<OwnedObjectPath>;
+
+    #[zbus(allow_interactive_auth)]
+    fn restart_unit(&self, name: &str, mode: &str) -> zbus::Result<OwnedObjectPath>;
+
+    #[zbus(allow_interactive_auth)]
+    fn kill_unit(&self, name: &str, whom: &str, signal: i32) -> zbus::Result<()>;
+
+    #[zbus(allow_interactive_auth)]
+    fn set_unit_properties(
+        &self,
+        name: &str,
+        runtime: bool,
+        properties: &[(&str, Value<'_>)],
+    ) -> zbus::Result<()>;
+
+    #[zbus(allow_interactive_auth)]
+    fn start_transient_unit(
+        &self,
+        name: &str,
+        mode: &str,
+        properties: &[(&str, Value<'_>)],
+        aux: &[(&str, &[(&str, Value<'_>)])],
+    ) -> zbus::Result<OwnedObjectPath>;
+}
+
+/// Ask the manager on `connection` to act on `unit`. Stopping and restarting
+/// only queue a job, so this returns before the unit is done.
+pub async fn run_action(
+    connection: &zbus::Connection,
+    unit: &str,
+    action: UnitAction,
+) -> ProcessResult<()> {
+    let manager = SystemdManagerProxy::new(connection)
+        .await
+        .map_err(action_error)?;
+    let result = match action {
+        UnitAction::Stop => manager.stop_unit(unit, "replace").await.map(drop),
+        UnitAction::Restart => manager.restart_unit(unit, "replace").await.map(drop),
+        UnitAction::Kill => manager.kill_unit(unit, "all", Signal::SIGKILL as i32).await,
+    };
+    result.map_err(action_error)
+}
+
+/// Ask the manager on `connection` to limit `unit`, as `systemctl
+/// set-property --runtime` does. No limit is `u64::MAX`, systemd's infinity.
+pub async fn set_unit_limits(
+    connection: &zbus::Connection,
+    unit: &str,
+    limits: Limits,
+) -> ProcessResult<()> {
+    SystemdManagerProxy::new(connection)
+        .await
+        .map_err(action_error)?
+        .set_unit_properties(unit, true, &limit_properties(limits))
+        .await
+        .map_err(action_error)
+}
+
+/// Ask the manager on `connection` to move process `pid` into a new scope
+/// limited to `limits`, as `systemd-run --scope` does for a new process.
+/// The scope goes in the slice of user `uid`, or `system.slice` for root.
+/// Processes it starts later stay in the scope. Returns the scope's name.
+pub async fn start_limited_scope(
+    connection: &zbus::Connection,
+    pid: u32,
+    uid: Option<u32>,
+    limits: Limits,
+) -> ProcessResult<String> {
+    let name = format!("cosmic-process-killer-{pid}.scope");
+    let mut properties = limit_properties(limits).to_vec();
+    properties.push(("PIDs", Value::from(vec![pid])));
+    if let Some(uid) = uid.filter(|uid| *uid != 0) {
+        properties.push(("Slice", Value::from(format!("user-{uid}.slice"))));
+    }
+    SystemdManagerProxy::new(connection)
+        .await
+        .map_err(action_error)?
+        .start_transient_unit(&name, "fail", &properties, &[])
+        .await
+        .map_err(action_error)?;
+    Ok(name)
+}
+
+/// `limits` as the unit properties systemd takes them
+fn limit_properties(limits: Limits) -> [(&'static str, Value<'static>); 2] {
+    let memory_max = limits.memory_max.unwrap_or(u64::MAX);
+    // Microseconds of CPU time per second of wall clock time
+    let cpu_quota = limits
+        .cpu_max
+        .map_or(u64::MAX, |percent| u64::from(percent) * 10_000);
+    [
+        ("MemoryMax", Value::from(memory_max)),
+        ("CPUQuotaPerSecUSec", Value::from(cpu_quota)),
+    ]
+}
+
+fn action_error(error: zbus::Error) -> ProcessError {
+    match &error {
+        zbus::Error::MethodError(name, detail, _) => match name.as_str() {
+            "org.freedesktop.DBus.Error.AccessDenied"
+            | "org.freedesktop.DBus.Error.InteractiveAuthorizationRequired" => {
+                ProcessError::PermissionDenied
+            }
+            "org.freedesktop.systemd1.NoSuchUnit" => ProcessError::NotFound,
+            _ => ProcessError::Unknown(detail.clone().unwrap_or_else(|| name.to_string())),
+        },
+        _ => ProcessError::Unknown(error.to_string()),
+    }
+}
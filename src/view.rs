@@ -0,0 +1,132 @@
+// SPDX-License-Identifier: MIT
+
+//! Widgets shared by the applet popup and the standalone window, which lay
+//! them out with their own spacing.
+
+use crate::controller::{Message, ProcessController};
+use crate::fl;
+use crate::process::{format_rate, ProcessInfo, SortBy};
+use cosmic::iced::alignment::Horizontal;
+use cosmic::iced::widget::span;
+use cosmic::iced::widget::text::Span;
+use cosmic::iced::{Alignment, Length};
+use cosmic::prelude::*;
+use cosmic::widget;
+
+/// `text` in pieces, with what the search matched in bold and underlined
+pub fn search_spans(controller: &ProcessController, text: &str) -> Vec<Span<'static>> {
+    controller
+        .search_highlights(text)
+        .into_iter()
+        .map(|(piece, matched)| {
+            let piece = span(piece.to_string());
+            if matched {
+                piece.font(cosmic::font::bold()).underline(true)
+            } else {
+                piece
+            }
+        })
+        .collect()
+}
+
+/// The background work the controller started, whose messages come back
+/// wrapped by `wrap`
+pub fn jobs<M: Send + 'static>(
+    controller: &mut ProcessController,
+    wrap: fn(Message) -> M,
+) -> Task<cosmic::Action<M>> {
+    Task::batch(
+        controller
+            .take_jobs()
+            .into_iter()
+            .map(|job| cosmic::task::future(async move { wrap(job.await) })),
+    )
+}
+
+/// Memory of a process in MB, or a dash when its metric cannot be read
+pub fn memory_label(memory: Option<u64>) -> String {
+    memory.map_or_else(
+        || "-".to_string(),
+        |bytes| format!("{} MB", bytes / 1024 / 1024),
+    )
+}
+
+/// The saved searches, each a button searching for it again next to one
+/// removing it, or nothing while none are saved
+pub fn saved_searches(
+    controller: &ProcessController,
+    spacing: u16,
+) -> Option<Element<'_, Message>> {
+    if controller.saved_searches().is_empty() {
+        return None;
+    }
+
+    let saved = controller.saved_searches().iter().fold(
+        widget::row().spacing(spacing).align_y(Alignment::Center),
+        |row, query| {
+            row.push(
+                widget::button::text(query.clone()).on_press(Message::UpdateSearch(query.clone())),
+            )
+            .push(widget::tooltip(
+                widget::button::icon(widget::icon::from_name("window-close-symbolic"))
+                    .on_press(Message::RemoveSavedSearch(query.clone()))
+                    .padding(spacing / 2),
+                widget::text(fl!("remove-search-tooltip")),
+                widget::tooltip::Position::Bottom,
+            ))
+        },
+    );
+    Some(widget::scrollable::horizontal(saved).into())
+}
+
+/// Headers of the disk I/O columns, each sorting by its column, or none
+/// while the columns are hidden
+pub fn disk_io_headers<'a>(
+    controller: &ProcessController,
+    width: f32,
+) -> Vec<Element<'a, Message>> {
+    if !controller.show_disk_io() {
+        return Vec::new();
+    }
+    [
+        (fl!("header-disk-read"), SortBy::DiskRead),
+        (fl!("header-disk-write"), SortBy::DiskWrite),
+    ]
+    .into_iter()
+    .map(|(label, sort_by)| {
+        widget::button::custom(
+            widget::text(label)
+                .width(Length::Fill)
+                .align_x(Horizontal::Center),
+        )
+        .on_press(Message::SortBy(sort_by))
+        .padding(0)
+        .class(cosmic::theme::Button::Text)
+        .width(Length::Fixed(width))
+        .into()
+    })
+    .collect()
+}
+
+/// Disk read and write rates of `process` for the disk I/O columns, or none
+/// while the columns are hidden
+pub fn disk_io_rates<'a, M: 'a>(
+    controller: &ProcessController,
+    process: &ProcessInfo,
+    size: u16,
+    width: f32,
+) -> Vec<Element<'a, M>> {
+    if !controller.show_disk_io() {
+        return Vec::new();
+    }
+    [process.disk_read, process.disk_write]
+        .into_iter()
+        .map(|rate| {
+            widget::text(format_rate(rate))
+                .size(size)
+                .width(Length::Fixed(width))
+                .align_x(Horizontal::Center)
+                .into()
+        })
+        .collect()
+}
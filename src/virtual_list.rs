@@ -0,0 +1,170 @@
+// SPDX-License-Identifier: MIT
+
+//! Virtualized process list.
+//!
+//! With "show all" on there can be thousands of processes, far more than fit
+//! in the scrollable. [`VirtualList`] follows the scroll position and works
+//! out which rows are in view, so the frontends only build widgets for those
+//! and stand in for the rest with empty space of the same height.
+//!
+//! Rows are identified by PID. When the list is re-sorted the view stays on
+//! the process that was at the top. While the pointer is over the list, or a
+//! kill is being confirmed, the order is frozen so a row does not move away
+//! just before it is clicked.
+
+use std::collections::HashSet;
+use std::ops::Range;
+
+/// Rows built beyond each edge of the viewport, hiding pop-in while scrolling
+const OVERSCAN: usize = 4;
+
+#[derive(Debug, Clone)]
+pub struct VirtualList {
+    /// Height of every row, spacing included
+    row_height: f32,
+    /// Scroll offset of the top of the viewport
+    offset: f32,
+    /// Height of the viewport
+    height: f32,
+    /// PIDs in display order
+    rows: Vec<u32>,
+    /// The pointer is over the list
+    hovered: bool,
+    /// Sorting is paused by the frontend, e.g. during a confirmation
+    paused: bool,
+}
+
+impl VirtualList {
+    /// Create a list of `row_height` rows in a viewport of `height`, until
+    /// the scrollable reports its actual size.
+    pub fn new(row_height: f32, height: f32) -> Self {
+        Self {
+            row_height,
+            offset: 0.0,
+            height,
+            rows: Vec::new(),
+            hovered: false,
+            paused: false,
+        }
+    }
+
+    /// Replace the rows with `pids`, given in their new display order.
+    ///
+    /// While frozen, rows that are still present keep their place and new
+    /// ones are added at the end. Returns the offset to scroll to when the
+    /// first visible row moved, so the view stays on the same process.
+    pub fn set_rows(&mut self, pids: impl IntoIterator<Item = u32>) -> Option<f32> {
+        let anchor = self.anchor();
+        let pids: Vec<u32> = pids.into_iter().collect();
+
+        self.rows = if self.is_frozen() {
+            let present: HashSet<u32> = pids.iter().copied().collect();
+            let mut rows: Vec<u32> = self
+                .rows
+                .iter()
+                .copied()
+                .filter(|pid| present.contains(pid))
+                .collect();
+            let kept: HashSet<u32> = rows.iter().copied().collect();
+            rows.extend(pids.into_iter().filter(|pid| !kept.contains(pid)));
+            rows
+        } else {
+            pids
+        };
+
+        let offset = match anchor.and_then(|(pid, into_row)| Some((self.index_of(pid)?, into_row)))
+        {
+            Some((index, into_row)) => index as f32 * self.row_height + into_row,
+            // The anchored process is gone; stay where we are if possible
+            None => self.offset,
+        }
+        .min(self.max_offset());
+
+        self.move_to(offset)
+    }
+
+    /// Record the scroll position reported by the scrollable.
+    pub fn scrolled(&mut self, offset: f32, height: f32) {
+        self.offset = offset.max(0.0);
+        self.height = height;
+    }
+
+    /// Freeze the order while the pointer is over the list.
+    pub fn set_hovered(&mut self, hovered: bool) {
+        self.hovered = hovered;
+    }
+
+    /// Freeze the order regardless of the pointer.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    /// Whether refreshes keep the current order
+    pub fn is_frozen(&self) -> bool {
+        self.hovered || self.paused
+    }
+
+    /// Every row, in display order
+    pub fn rows(&self) -> &[u32] {
+        &self.rows
+    }
+
+    /// Indices of the rows to build, including a few beyond each edge
+    pub fn visible_range(&self) -> Range<usize> {
+        let first = (self.offset / self.row_height) as usize;
+        let count = (self.height / self.row_height).ceil() as usize + 1;
+
+        let start = first.saturating_sub(OVERSCAN).min(self.rows.len());
+        let end = (first + count + OVERSCAN).min(self.rows.len());
+        start..end
+    }
+
+    /// Height taken by `rows` rows
+    pub fn height_of(&self, rows: usize) -> f32 {
+        rows as f32 * self.row_height
+    }
+
+    /// Offset to scroll to so the row of `pid` is fully visible, if it is not
+    /// already.
+    pub fn reveal(&mut self, pid: u32) -> Option<f32> {
+        let top = self.index_of(pid)? as f32 * self.row_height;
+        let bottom = top + self.row_height;
+
+        if top < self.offset {
+            self.move_to(top)
+        } else if bottom > self.offset + self.height {
+            self.move_to(bottom - self.height)
+        } else {
+            None
+        }
+    }
+
+    /// The first visible process and how far it is scrolled out of view.
+    ///
+    /// At the very top nothing is anchored, so new processes sorting first
+    /// come into view instead of pushing the list down.
+    fn anchor(&self) -> Option<(u32, f32)> {
+        if self.offset <= 0.0 {
+            return None;
+        }
+        let index = (self.offset / self.row_height) as usize;
+        let pid = *self.rows.get(index)?;
+        Some((pid, self.offset - index as f32 * self.row_height))
+    }
+
+    fn index_of(&self, pid: u32) -> Option<usize> {
+        self.rows.iter().position(|row| *row == pid)
+    }
+
+    fn max_offset(&self) -> f32 {
+        (self.height_of(self.rows.len()) - self.height).max(0.0)
+    }
+
+    fn move_to(&mut self, offset: f32) -> Option<f32> {
+        if (offset - self.offset).abs() < 0.5 {
+            return None;
+        }
+        self.offset = offset;
+        Some(offset)
+    }
+}
@@ -0,0 +1,65 @@
+// SPDX-License-Identifier: MIT
+
+//! CPU affinity changes on this test process.
+
+use cosmic_applet_process_killer::affinity::{self, CpuMask};
+use cosmic_applet_process_killer::process::ProcessError;
+use nix::unistd::gettid;
+use std::sync::mpsc;
+
+#[test]
+fn mask_covers_the_system_cores() {
+    let all = CpuMask::all();
+    assert_eq!(all.len(), affinity::cpu_count());
+    assert_eq!(all.cpus().count(), all.len());
+
+    let mut mask = CpuMask::from_cpus([0]);
+    assert!(mask.contains(0));
+    assert_eq!(mask.cpus().collect::<Vec<_>>(), [0]);
+
+    // Cores the system does not have are ignored
+    mask.set(usize::MAX, true);
+    mask.set(0, false);
+    assert!(mask.is_empty());
+}
+
+#[test]
+fn pinning_applies_to_the_chosen_threads() {
+    let pid = std::process::id();
+    let original = affinity::affinity(pid).unwrap();
+    let pinned = CpuMask::from_cpus(original.cpus().take(1));
+
+    let (tid_sender, tid_receiver) = mpsc::channel();
+    let (done_sender, done_receiver) = mpsc::channel::<()>();
+    let worker = std::thread::spawn(move || {
+        tid_sender.send(gettid().as_raw() as u32).unwrap();
+        done_receiver.recv().unwrap();
+    });
+    let tid = tid_receiver.recv().unwrap();
+
+    // Main thread only
+    affinity::set_affinity(pid, &pinned, false).unwrap();
+    assert_eq!(affinity::affinity(pid).unwrap(), pinned);
+    assert_eq!(affinity::affinity(tid).unwrap(), original);
+
+    // Every thread
+    affinity::set_affinity(pid, &pinned, true).unwrap();
+    assert_eq!(affinity::affinity(tid).unwrap(), pinned);
+
+    affinity::set_affinity(pid, &original, true).unwrap();
+    done_sender.send(()).unwrap();
+    worker.join().unwrap();
+}
+
+#[test]
+fn an_empty_mask_is_refused() {
+    let empty = CpuMask::from_cpus([]);
+    assert!(matches!(
+        affinity::set_affinity(std::process::id(), &empty, false),
+        Err(ProcessError::Unknown(_))
+    ));
+    assert_eq!(
+        affinity::affinity(i32::MAX as u32),
+        Err(ProcessError::NotFound)
+    );
+}
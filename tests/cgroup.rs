@@ -0,0 +1,200 @@
+// SPDX-License-Identifier: MIT
+
+//! App cgroups and freezing them.
+
+use cosmic_applet_process_killer::backend::ProcessBackend;
+use cosmic_applet_process_killer::cgroup::{
+    app_cgroup, app_name, is_frozen, limit_cgroup, parse_cpu_max, parse_frozen, parse_memory_max,
+    read_limits, CgroupFreezer, CgroupLimiter, Freezer, Limiter, Limits,
+};
+use cosmic_applet_process_killer::process::ProcessError;
+use cosmic_applet_process_killer::procfs::ProcfsBackend;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const EDITOR_SCOPE: &str =
+    "/user.slice/user-1000.slice/user@1000.service/app.slice/app-org.example.Editor-1234.scope";
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+#[test]
+fn apps_are_found_in_the_cgroup_path() {
+    assert_eq!(app_cgroup(EDITOR_SCOPE), Some(EDITOR_SCOPE));
+    // Processes in a sub-cgroup of the app belong to it too
+    assert_eq!(
+        app_cgroup(&format!("{EDITOR_SCOPE}/renderer")),
+        Some(EDITOR_SCOPE)
+    );
+    assert_eq!(
+        app_cgroup("/user.slice/user-1000.slice/user@1000.service/app.slice/app-foot.service"),
+        Some("/user.slice/user-1000.slice/user@1000.service/app.slice/app-foot.service")
+    );
+    assert_eq!(app_name(EDITOR_SCOPE), "app-org.example.Editor-1234.scope");
+
+    // Services and sessions are not apps
+    assert_eq!(app_cgroup("/system.slice/nginx.service"), None);
+    assert_eq!(
+        app_cgroup("/user.slice/user-1000.slice/session-2.scope"),
+        None
+    );
+    assert_eq!(app_cgroup("/"), None);
+}
+
+#[test]
+fn frozen_state_comes_from_the_events_file() {
+    assert!(parse_frozen("populated 1\nfrozen 1\n"));
+    assert!(!parse_frozen("populated 1\nfrozen 0\n"));
+    assert!(!parse_frozen("populated 0\n"));
+
+    let root = fixture("cgroup");
+    assert!(is_frozen(&root, EDITOR_SCOPE));
+    assert!(!is_frozen(&root, "/system.slice/gone.service"));
+}
+
+#[test]
+fn freezing_writes_the_freeze_file() {
+    let root = std::env::temp_dir().join(format!("cgroup-freeze-{}", std::process::id()));
+    _ = fs::remove_dir_all(&root);
+    let scope = root.join(EDITOR_SCOPE.trim_start_matches('/'));
+    fs::create_dir_all(&scope).unwrap();
+    fs::write(scope.join("cgroup.freeze"), "0").unwrap();
+
+    let freezer = CgroupFreezer::with_root(&root);
+    freezer.set_frozen(EDITOR_SCOPE, true).unwrap();
+    assert_eq!(
+        fs::read_to_string(scope.join("cgroup.freeze")).unwrap(),
+        "1"
+    );
+    freezer.set_frozen(EDITOR_SCOPE, false).unwrap();
+    assert_eq!(
+        fs::read_to_string(scope.join("cgroup.freeze")).unwrap(),
+        "0"
+    );
+
+    // Cgroups that do not exist are not created
+    assert_eq!(
+        freezer.set_frozen("/app.slice/app-gone.scope", true),
+        Err(ProcessError::NotFound)
+    );
+    assert!(!root.join("app.slice").exists());
+    _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn processes_of_a_frozen_app_are_marked() {
+    let processes = ProcfsBackend::with_root(fixture("proc"))
+        .with_cgroup_root(fixture("cgroup"))
+        .sample();
+
+    let editor = processes.iter().find(|p| p.pid == 1300).unwrap();
+    assert_eq!(editor.app_cgroup.as_deref(), Some(EDITOR_SCOPE));
+    assert!(editor.frozen);
+
+    // A service is not an app, and nothing is frozen
+    let tricky = processes.iter().find(|p| p.pid == 1200).unwrap();
+    assert_eq!(tricky.app_cgroup, None);
+    assert!(!tricky.frozen);
+}
+
+#[test]
+fn limits_are_read_from_the_app_or_service_cgroup() {
+    assert_eq!(parse_memory_max("max\n"), None);
+    assert_eq!(parse_memory_max("536870912\n"), Some(512 * 1024 * 1024));
+    assert_eq!(parse_cpu_max("max 100000\n"), None);
+    assert_eq!(parse_cpu_max("50000 100000\n"), Some(50));
+    assert_eq!(parse_cpu_max("200000 100000"), Some(200));
+
+    assert_eq!(
+        limit_cgroup(&format!("{EDITOR_SCOPE}/renderer")),
+        Some(EDITOR_SCOPE)
+    );
+    assert_eq!(
+        limit_cgroup("/system.slice/docker.service/payload"),
+        Some("/system.slice/docker.service")
+    );
+    // A login session is not limited as a whole
+    assert_eq!(
+        limit_cgroup("/user.slice/user-1000.slice/session-2.scope"),
+        None
+    );
+
+    assert_eq!(
+        read_limits(&fixture("cgroup"), EDITOR_SCOPE),
+        Some(Limits {
+            memory_max: Some(2 * 1024 * 1024 * 1024),
+            cpu_max: Some(50),
+        })
+    );
+    assert_eq!(
+        read_limits(&fixture("cgroup"), "/system.slice/gone.service"),
+        None
+    );
+}
+
+#[test]
+fn limiting_writes_the_max_files() {
+    let root = std::env::temp_dir().join(format!("cgroup-limit-{}", std::process::id()));
+    _ = fs::remove_dir_all(&root);
+    let scope = root.join(EDITOR_SCOPE.trim_start_matches('/'));
+    fs::create_dir_all(&scope).unwrap();
+    fs::write(scope.join("memory.max"), "max").unwrap();
+    fs::write(scope.join("cpu.max"), "max 100000").unwrap();
+
+    let limiter = CgroupLimiter::with_root(&root);
+    let limits = Limits {
+        memory_max: Some(1024 * 1024 * 1024),
+        cpu_max: Some(25),
+    };
+    limiter.set_limits(EDITOR_SCOPE, limits).unwrap();
+    assert_eq!(
+        fs::read_to_string(scope.join("memory.max")).unwrap(),
+        "1073741824"
+    );
+    assert_eq!(
+        fs::read_to_string(scope.join("cpu.max")).unwrap(),
+        "25000 100000"
+    );
+    assert_eq!(read_limits(&root, EDITOR_SCOPE), Some(limits));
+
+    limiter.set_limits(EDITOR_SCOPE, Limits::default()).unwrap();
+    assert_eq!(read_limits(&root, EDITOR_SCOPE), Some(Limits::default()));
+    _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn memory_is_not_limited_when_cpu_cannot_be() {
+    let root = std::env::temp_dir().join(format!("cgroup-rollback-{}", std::process::id()));
+    _ = fs::remove_dir_all(&root);
+    let scope = root.join(EDITOR_SCOPE.trim_start_matches('/'));
+    fs::create_dir_all(&scope).unwrap();
+    // The cpu controller is not enabled for the cgroup
+    fs::write(scope.join("memory.max"), "max\n").unwrap();
+
+    let limiter = CgroupLimiter::with_root(&root);
+    let limits = Limits {
+        memory_max: Some(1024 * 1024 * 1024),
+        cpu_max: Some(25),
+    };
+    assert_eq!(
+        limiter.set_limits(EDITOR_SCOPE, limits),
+        Err(ProcessError::NotFound)
+    );
+    assert_eq!(fs::read_to_string(scope.join("memory.max")).unwrap(), "max");
+    _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn limits_of_the_watched_process_are_read() {
+    let mut backend = ProcfsBackend::with_root(fixture("proc")).with_cgroup_root(fixture("cgroup"));
+    backend.sample();
+
+    assert_eq!(backend.limits(1300).unwrap().cpu_max, Some(50));
+    // tricky.service has no cgroup in the fixture
+    assert_eq!(backend.limits(1200), None);
+    // init cannot be limited
+    assert_eq!(backend.limits(1), None);
+}
@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: MIT
+
+//! Command-line arguments of the standalone binary.
+
+use cosmic_applet_process_killer::cli::{parse, Command, ExportArgs};
+use cosmic_applet_process_killer::export::ExportFormat;
+use cosmic_applet_process_killer::process::SortBy;
+use cosmic_applet_process_killer::query::Query;
+use cosmic_applet_process_killer::standalone::Flags;
+use std::path::PathBuf;
+
+fn parse_args(args: &[&str]) -> Result<Command, String> {
+    parse(args.iter().map(|arg| arg.to_string()))
+}
+
+#[test]
+fn no_arguments_open_the_window() {
+    assert_eq!(parse_args(&[]), Ok(Command::Window(Flags::default())));
+}
+
+#[test]
+fn window_flags_are_passed_on() {
+    assert_eq!(
+        parse_args(&["--search", "fire", "--pid", "42", "--sort", "mem", "--show-all", "--tree"]),
+        Ok(Command::Window(Flags {
+            search: Some("fire".to_string()),
+            pid: Some(42),
+            sort_by: Some(SortBy::Memory),
+            show_all: true,
+            tree: true,
+        }))
+    );
+}
+
+#[test]
+fn help_wins_over_other_arguments() {
+    assert_eq!(parse_args(&["-h"]), Ok(Command::Help));
+    assert_eq!(parse_args(&["--export", "csv", "--help"]), Ok(Command::Help));
+}
+
+#[test]
+fn export_defaults_to_cpu_order_on_standard_output() {
+    assert_eq!(
+        parse_args(&["--export", "jsonl"]),
+        Ok(Command::Export(ExportArgs {
+            format: ExportFormat::JsonLines,
+            output: None,
+            search: Query::default(),
+            sort_by: SortBy::Cpu,
+            show_all: false,
+        }))
+    );
+}
+
+#[test]
+fn export_takes_output_search_and_sort() {
+    let Ok(Command::Export(args)) = parse_args(&[
+        "--export",
+        "CSV",
+        "--output",
+        "/tmp/snapshot.csv",
+        "--search",
+        "cpu>50",
+        "--sort",
+        "write",
+        "--show-all",
+    ]) else {
+        panic!("expected an export");
+    };
+
+    assert_eq!(args.format, ExportFormat::Csv);
+    assert_eq!(args.output, Some(PathBuf::from("/tmp/snapshot.csv")));
+    assert_eq!(args.search, Query::parse("cpu>50").unwrap());
+    assert_eq!(args.sort_by, SortBy::DiskWrite);
+    assert!(args.show_all);
+}
+
+#[test]
+fn bad_arguments_are_reported() {
+    assert_eq!(
+        parse_args(&["--export", "xml"]),
+        Err("unknown export format: xml".to_string())
+    );
+    assert_eq!(parse_args(&["--pid", "-1"]), Err("invalid PID: -1".to_string()));
+    assert_eq!(parse_args(&["--sort", "size"]), Err("unknown sort key: size".to_string()));
+    assert_eq!(parse_args(&["--verbose"]), Err("unexpected argument: --verbose".to_string()));
+    assert_eq!(parse_args(&["--search"]), Err("--search requires a value".to_string()));
+    assert!(parse_args(&["--export", "csv", "--search", "bogus:x"])
+        .unwrap_err()
+        .starts_with("invalid search: "));
+}
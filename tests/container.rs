@@ -0,0 +1,274 @@
+// SPDX-License-Identifier: MIT
+
+//! Docker and Podman containers, from the captured tree in
+//! `tests/fixtures/proc`, and their runtimes' API stood in for by a local
+//! socket.
+
+use cosmic_applet_process_killer::backend::ProcessBackend;
+use cosmic_applet_process_killer::container::{
+    container_of, Container, ContainerManager, Runtime, RuntimeSockets,
+};
+use cosmic_applet_process_killer::process::{container_order, ProcessError, ProcessInfo};
+use cosmic_applet_process_killer::procfs::ProcfsBackend;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixListener;
+use std::path::{Path, PathBuf};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+const NGINX_ID: &str = "3f5a9c1e7b2d4f6a8c0e1b3d5f7a9c2e4b6d8f0a1c3e5b7d9f2a4c6e8b0d1f3a";
+const POSTGRES_ID: &str = "8e2c4a6b1d3f5e7a9c0b2d4f6a8e1c3b5d7f9a0e2c4b6d8f1a3c5e7b9d0f2a4c";
+
+fn fixture_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/proc")
+}
+
+fn socket_dir(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("container-{test}-{}", std::process::id()));
+    _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// A runtime API on `socket` answering one request per response, in order,
+/// with `(status, body)`. Returns the request lines it got.
+fn stand_in(socket: &Path, responses: Vec<(u16, &'static str)>) -> JoinHandle<Vec<String>> {
+    let listener = UnixListener::bind(socket).unwrap();
+    thread::spawn(move || {
+        let mut requests = Vec::new();
+        for (status, body) in responses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            requests.push(line.trim_end().to_string());
+            // Headers end with an empty line
+            while line != "\r\n" {
+                line.clear();
+                reader.read_line(&mut line).unwrap();
+            }
+            write!(
+                reader.get_mut(),
+                "HTTP/1.0 {status} Whatever\r\nContent-Type: application/json\r\n\r\n{body}"
+            )
+            .unwrap();
+        }
+        requests
+    })
+}
+
+fn container(runtime: Runtime, id: &str) -> Container {
+    Container {
+        runtime,
+        id: id.to_string(),
+        name: None,
+    }
+}
+
+fn by_pid(processes: &[ProcessInfo], pid: u32) -> &ProcessInfo {
+    processes.iter().find(|p| p.pid == pid).unwrap()
+}
+
+#[test]
+fn containers_are_found_in_cgroup_paths() {
+    assert_eq!(
+        container_of(&format!("/system.slice/docker-{NGINX_ID}.scope")),
+        Some(container(Runtime::Docker, NGINX_ID))
+    );
+    // Without systemd, the cgroupfs driver
+    assert_eq!(
+        container_of(&format!("/docker/{NGINX_ID}")),
+        Some(container(Runtime::Docker, NGINX_ID))
+    );
+    assert_eq!(
+        container_of(&format!(
+            "/user.slice/user-1000.slice/user@1000.service/user.slice/libpod-{POSTGRES_ID}.scope/container"
+        )),
+        Some(container(Runtime::Podman, POSTGRES_ID))
+    );
+    assert_eq!(
+        container_of(&format!("/machine.slice/libpod-{POSTGRES_ID}.scope")),
+        Some(container(Runtime::Podman, POSTGRES_ID))
+    );
+
+    // Podman's monitor runs next to the container, not in it
+    assert_eq!(
+        container_of(&format!("/machine.slice/libpod-conmon-{POSTGRES_ID}.scope")),
+        None
+    );
+    assert_eq!(container_of("/system.slice/docker.service"), None);
+    assert_eq!(container_of("/docker/not-an-id"), None);
+}
+
+#[test]
+fn unnamed_containers_go_by_their_short_id() {
+    let mut nginx = container(Runtime::Docker, NGINX_ID);
+    assert_eq!(nginx.short_id(), "3f5a9c1e7b2d");
+    assert_eq!(nginx.label(), "3f5a9c1e7b2d");
+
+    nginx.name = Some("web".to_string());
+    assert_eq!(nginx.label(), "web");
+}
+
+#[test]
+fn processes_are_grouped_by_container() {
+    let process = |pid: u32, id: Option<&str>| ProcessInfo {
+        pid,
+        container: id.map(|id| container(Runtime::Docker, id)),
+        ..Default::default()
+    };
+    let processes = [
+        process(1, Some("b")),
+        process(2, None),
+        process(3, Some("a")),
+        process(4, Some("b")),
+        process(5, None),
+    ];
+    let listed: Vec<&ProcessInfo> = processes.iter().collect();
+
+    // Host processes first, then containers in the order they come up
+    let pids: Vec<u32> = container_order(&listed).iter().map(|p| p.pid).collect();
+    assert_eq!(pids, [2, 5, 1, 4, 3]);
+}
+
+#[test]
+fn container_processes_are_labelled_with_their_runtime_names() {
+    let dir = socket_dir("names");
+    let docker = dir.join("docker.sock");
+    let server = stand_in(
+        &docker,
+        vec![(
+            200,
+            r#"[{"Id":"3f5a9c1e7b2d4f6a8c0e1b3d5f7a9c2e4b6d8f0a1c3e5b7d9f2a4c6e8b0d1f3a","Names":["/web"]},
+                {"Id":"0000","Names":["/other"]}]"#,
+        )],
+    );
+    let mut backend = ProcfsBackend::with_root(fixture_root()).with_container_sockets(
+        RuntimeSockets::with_paths(vec![docker], vec![dir.join("missing.sock")]),
+    );
+
+    let processes = backend.sample();
+    assert_eq!(
+        server.join().unwrap(),
+        vec!["GET /containers/json HTTP/1.0"]
+    );
+
+    let nginx = by_pid(&processes, 1800);
+    assert_eq!(
+        nginx.container,
+        Some(Container {
+            name: Some("web".to_string()),
+            ..container(Runtime::Docker, NGINX_ID)
+        })
+    );
+    assert_eq!(nginx.pid_namespace, Some(4026532555));
+
+    // Podman is not running, so its container goes by its ID
+    let postgres = by_pid(&processes, 1900);
+    assert_eq!(
+        postgres.container,
+        Some(container(Runtime::Podman, POSTGRES_ID))
+    );
+    assert_eq!(postgres.pid_namespace, Some(4026532601));
+
+    // The host's namespace is not worth mentioning
+    assert_eq!(by_pid(&processes, 1200).container, None);
+    assert_eq!(by_pid(&processes, 1200).pid_namespace, None);
+
+    // Names are remembered; the stand-in is gone, and the name stays
+    let processes = backend.sample();
+    assert_eq!(
+        by_pid(&processes, 1800)
+            .container
+            .as_ref()
+            .map(Container::label),
+        Some("web")
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn containers_are_stopped_through_the_runtime_that_knows_them() {
+    let dir = socket_dir("stop");
+    let rootless = dir.join("rootless.sock");
+    let rootful = dir.join("rootful.sock");
+    let rootless_server = stand_in(&rootless, vec![(404, r#"{"message":"no such container"}"#)]);
+    let rootful_server = stand_in(&rootful, vec![(204, "")]);
+    let sockets = RuntimeSockets::with_paths(
+        Vec::new(),
+        vec![dir.join("missing.sock"), rootless, rootful],
+    );
+
+    assert_eq!(
+        sockets.stop(&container(Runtime::Podman, POSTGRES_ID)),
+        Ok(())
+    );
+    let request = format!("POST /containers/{POSTGRES_ID}/stop?t=10 HTTP/1.0");
+    assert_eq!(rootless_server.join().unwrap(), vec![request.clone()]);
+    assert_eq!(rootful_server.join().unwrap(), vec![request]);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn stop_failures_are_reported() {
+    let dir = socket_dir("failures");
+    let docker = dir.join("docker.sock");
+    let server = stand_in(
+        &docker,
+        vec![
+            (404, r#"{"message":"No such container"}"#),
+            (403, ""),
+            (
+                500,
+                r#"{"message":"cannot stop container: permission denied"}"#,
+            ),
+            // Already stopped
+            (304, ""),
+        ],
+    );
+    let sockets = RuntimeSockets::with_paths(vec![docker], Vec::new());
+    let nginx = container(Runtime::Docker, NGINX_ID);
+
+    assert_eq!(sockets.stop(&nginx), Err(ProcessError::NotFound));
+    assert_eq!(sockets.stop(&nginx), Err(ProcessError::PermissionDenied));
+    assert_eq!(
+        sockets.stop(&nginx),
+        Err(ProcessError::Unknown(
+            "cannot stop container: permission denied".to_string()
+        ))
+    );
+    assert_eq!(sockets.stop(&nginx), Ok(()));
+    server.join().unwrap();
+
+    // No runtime listening at all
+    let podman = RuntimeSockets::with_paths(Vec::new(), vec![dir.join("missing.sock")]);
+    assert_eq!(
+        podman.stop(&container(Runtime::Podman, POSTGRES_ID)),
+        Err(ProcessError::NotFound)
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn a_runtime_that_does_not_answer_leaves_containers_unnamed() {
+    let dir = socket_dir("hung");
+    let socket = dir.join("docker.sock");
+    let listener = UnixListener::bind(&socket).unwrap();
+    // Takes the request, then never answers it
+    let server = thread::spawn(move || {
+        let (_stream, _) = listener.accept().unwrap();
+        thread::sleep(Duration::from_secs(3));
+    });
+
+    let sockets = RuntimeSockets::with_paths(vec![socket], Vec::new());
+    let started = Instant::now();
+    assert!(sockets.names(Runtime::Docker).is_empty());
+    assert!(started.elapsed() < Duration::from_secs(2));
+
+    server.join().unwrap();
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,159 @@
+// SPDX-License-Identifier: MIT
+
+//! Drives the shared controller with samples of the real process table.
+
+use cosmic_applet_process_killer::controller::{
+    truncate_name, ConfirmationMode, Message, ProcessController,
+};
+use cosmic_applet_process_killer::memory::MemoryMetric;
+use cosmic_applet_process_killer::process::{ProcessInfo, ProcessManager};
+use cosmic_applet_process_killer::sampler::{
+    self, LatestSample, SamplerSettings, SnapshotDiff, DEFAULT_INTERVAL,
+};
+use std::collections::HashMap;
+use std::process::{Child, Command};
+
+fn spawn_sleeper() -> Child {
+    Command::new("sleep").arg("30").spawn().unwrap()
+}
+
+fn controller_with_all_processes() -> ProcessController {
+    let sample = ProcessManager::new().sample();
+    let mut controller = ProcessController::new();
+    controller.update(Message::Snapshot(SnapshotDiff::between(
+        &HashMap::new(),
+        &sample,
+    )));
+    controller.update(Message::ToggleShowAll(true));
+    controller
+}
+
+#[test]
+fn search_filters_by_pid() {
+    let mut child = spawn_sleeper();
+    let mut controller = controller_with_all_processes();
+
+    controller.update(Message::UpdateSearch(child.id().to_string()));
+
+    assert!(controller
+        .filtered_processes()
+        .iter()
+        .any(|p| p.pid == child.id()));
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[test]
+fn kill_requires_confirmation() {
+    let mut child = spawn_sleeper();
+    let mut controller = controller_with_all_processes();
+
+    controller.update(Message::KillProcess(child.id()));
+    let (process, mode) = controller.confirmation().unwrap();
+    assert_eq!(process.pid, child.id());
+    assert_eq!(mode, ConfirmationMode::Kill);
+    assert!(child.try_wait().unwrap().is_none());
+
+    controller.update(Message::ConfirmKill);
+    assert!(controller.confirmation().is_none());
+    assert!(!controller.toast().unwrap().is_error);
+    assert!(!child.wait().unwrap().success());
+}
+
+#[test]
+fn cancel_and_reselect_drop_the_confirmation() {
+    let mut child = spawn_sleeper();
+    let mut controller = controller_with_all_processes();
+
+    controller.update(Message::ForceKillProcess(child.id()));
+    assert_eq!(
+        controller.confirmation().map(|(_, mode)| mode),
+        Some(ConfirmationMode::ForceKill)
+    );
+    controller.update(Message::CancelConfirmation);
+    assert!(controller.confirmation().is_none());
+
+    controller.update(Message::ForceKillProcess(child.id()));
+    controller.update(Message::SelectProcess(Some(std::process::id())));
+    assert!(controller.confirmation().is_none());
+    assert!(child.try_wait().unwrap().is_none());
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[test]
+fn unknown_process_shows_an_error() {
+    let mut controller = controller_with_all_processes();
+
+    controller.update(Message::KillProcess(u32::MAX));
+
+    assert!(controller.confirmation().is_none());
+    assert!(controller.toast().unwrap().is_error);
+}
+
+#[test]
+fn snapshots_add_update_and_remove_processes() {
+    let mut child = spawn_sleeper();
+    let sample = ProcessManager::new().sample();
+    let mut controller = ProcessController::new();
+    assert!(!controller.has_snapshot());
+
+    let first = SnapshotDiff::between(&HashMap::new(), &sample);
+    controller.update(Message::Snapshot(first));
+    controller.update(Message::ToggleShowAll(true));
+    assert!(controller.has_snapshot());
+    assert!(controller.process(child.id()).is_some());
+
+    let previous: HashMap<_, _> = sample.iter().map(|p| (p.pid, p.clone())).collect();
+    let mut next = sample.clone();
+    next.retain(|p| p.pid != child.id());
+    let diff = SnapshotDiff::between(&previous, &next);
+    assert_eq!(diff.removed, vec![child.id()]);
+    assert!(diff.added.is_empty() && diff.updated.is_empty());
+
+    controller.update(Message::Snapshot(diff));
+    assert!(controller.process(child.id()).is_none());
+
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
+
+#[test]
+fn a_new_sampler_starts_from_an_empty_table() {
+    let exited = ProcessInfo {
+        pid: 4_000_000,
+        name: "exited".to_string(),
+        ..Default::default()
+    };
+    let mut controller = ProcessController::new();
+    controller.update(Message::Snapshot(SnapshotDiff {
+        added: vec![exited],
+        ..Default::default()
+    }));
+    controller.update(Message::ToggleShowAll(true));
+    assert!(controller.process(4_000_000).is_some());
+
+    // Started again with other settings, it only reports what is running
+    let settings = SamplerSettings {
+        interval: DEFAULT_INTERVAL,
+        memory_metric: MemoryMetric::Rss,
+    };
+    let (sampler, mut updates) = sampler::spawn(settings, LatestSample::new());
+    controller.update(Message::SamplerReady(sampler));
+    assert!(!controller.has_snapshot());
+    assert!(controller.process(4_000_000).is_none());
+
+    let first = updates.blocking_recv().unwrap();
+    assert!(matches!(first, Message::Snapshot(_)));
+    controller.update(first);
+    assert!(controller.has_snapshot());
+    assert!(controller.process(std::process::id()).is_some());
+    assert!(controller.process(4_000_000).is_none());
+}
+
+#[test]
+fn names_are_truncated_on_char_boundaries() {
+    assert_eq!(truncate_name("short", 15), "short");
+    assert_eq!(truncate_name("ããããããããããããããããã", 15), "ãããããããããããã...");
+}
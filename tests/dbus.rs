@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: MIT
+
+//! Exercises the D-Bus interface over a private peer-to-peer bus.
+
+use cosmic_applet_process_killer::dbus::{
+    ProcessKillerProxy, ProcessKillerService, Request, OBJECT_PATH,
+};
+use cosmic_applet_process_killer::process::{ProcessInfo, ProcessManager};
+use cosmic_applet_process_killer::sampler::LatestSample;
+use tokio::net::UnixStream;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+async fn private_bus() -> (zbus::Connection, zbus::Connection, UnboundedReceiver<Request>) {
+    let latest = LatestSample::new();
+    latest.set(ProcessManager::new().sample());
+    private_bus_with(latest).await
+}
+
+async fn private_bus_with(
+    latest: LatestSample,
+) -> (zbus::Connection, zbus::Connection, UnboundedReceiver<Request>) {
+    let (server_stream, client_stream) = UnixStream::pair().unwrap();
+    let (sender, requests) = unbounded_channel();
+
+    let server = zbus::connection::Builder::unix_stream(server_stream)
+        .server(zbus::Guid::generate())
+        .unwrap()
+        .p2p()
+        .serve_at(OBJECT_PATH, ProcessKillerService::new(latest, sender))
+        .unwrap()
+        .build();
+    let client = zbus::connection::Builder::unix_stream(client_stream)
+        .p2p()
+        .build();
+
+    let (server, client) = futures_util::try_join!(server, client).unwrap();
+    (server, client, requests)
+}
+
+#[tokio::test]
+async fn show_popup_is_forwarded() {
+    let (_server, client, mut requests) = private_bus().await;
+    let proxy = ProcessKillerProxy::new(&client).await.unwrap();
+
+    proxy.show_popup("firefox").await.unwrap();
+
+    assert_eq!(
+        requests.recv().await,
+        Some(Request::ShowPopup("firefox".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn list_processes_includes_this_process() {
+    let (_server, client, _requests) = private_bus().await;
+    let proxy = ProcessKillerProxy::new(&client).await.unwrap();
+    let pid = std::process::id();
+
+    let processes = proxy
+        .list_processes("pid", &pid.to_string(), true)
+        .await
+        .unwrap();
+
+    assert!(processes.iter().any(|p| p.pid == pid));
+    assert!(proxy.list_processes("bogus", "", true).await.is_err());
+}
+
+#[tokio::test]
+async fn request_kill_only_asks_for_confirmation() {
+    let (_server, client, mut requests) = private_bus().await;
+    let proxy = ProcessKillerProxy::new(&client).await.unwrap();
+    let pid = std::process::id();
+
+    proxy.request_kill(pid, true).await.unwrap();
+
+    assert_eq!(
+        requests.recv().await,
+        Some(Request::ConfirmKill { pid, force: true })
+    );
+    assert!(proxy.request_kill(u32::MAX, false).await.is_err());
+}
+
+#[tokio::test]
+async fn answers_come_from_the_latest_sample() {
+    let latest = LatestSample::new();
+    let (_server, client, _requests) = private_bus_with(latest.clone()).await;
+    let proxy = ProcessKillerProxy::new(&client).await.unwrap();
+
+    // Nothing sampled yet
+    assert!(proxy.get_process(4242).await.is_err());
+
+    latest.set(vec![
+        ProcessInfo {
+            pid: 4242,
+            name: "sampled".to_string(),
+            ..Default::default()
+        },
+        ProcessInfo {
+            pid: 1,
+            name: "systemd".to_string(),
+            ..Default::default()
+        },
+    ]);
+    assert_eq!(proxy.get_process(4242).await.unwrap().name, "sampled");
+    let pids: Vec<u32> = proxy
+        .list_processes("pid", "", true)
+        .await
+        .unwrap()
+        .iter()
+        .map(|p| p.pid)
+        .collect();
+    assert_eq!(pids, [1, 4242]);
+
+    // Protected processes are refused before asking anyone
+    assert!(proxy.request_kill(1, false).await.is_err());
+}
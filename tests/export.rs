@@ -0,0 +1,107 @@
+// SPDX-License-Identifier: MIT
+
+//! Snapshot export as JSON Lines and CSV.
+
+use cosmic_applet_process_killer::export::{write_snapshot, ExportFormat, SCHEMA_VERSION};
+use cosmic_applet_process_killer::memory::MemoryMetric;
+use cosmic_applet_process_killer::process::ProcessInfo;
+use serde_json::Value;
+
+fn process(pid: u32, name: &str, cmdline: &[&str]) -> ProcessInfo {
+    ProcessInfo {
+        pid,
+        name: name.to_string(),
+        cmdline: cmdline.iter().map(|arg| arg.to_string()).collect(),
+        ..Default::default()
+    }
+}
+
+fn export(format: ExportFormat, processes: &[&ProcessInfo]) -> String {
+    let mut out = Vec::new();
+    write_snapshot(&mut out, format, MemoryMetric::Pss, processes).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn json_lines_carry_the_schema_version_and_metric() {
+    let firefox = process(100, "firefox", &["/usr/lib/firefox/firefox"]);
+    let bash = process(200, "bash", &[]);
+    let out = export(ExportFormat::JsonLines, &[&firefox, &bash]);
+
+    let records = out
+        .lines()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(records.len(), 2);
+    for record in &records {
+        assert_eq!(record["schema_version"], SCHEMA_VERSION);
+        assert_eq!(record["memory_metric"], "pss");
+    }
+    assert_eq!(records[0]["pid"], 100);
+    assert_eq!(records[0]["name"], "firefox");
+    assert_eq!(records[1]["pid"], 200);
+}
+
+#[test]
+fn csv_header_matches_the_row_order() {
+    let firefox = ProcessInfo {
+        memory: Some(2048),
+        disk_read: 7,
+        ..process(100, "firefox", &[])
+    };
+    let out = export(ExportFormat::Csv, &[&firefox]);
+
+    let mut lines = out.lines();
+    let header = lines.next().unwrap().split(',').collect::<Vec<_>>();
+    let row = lines.next().unwrap().split(',').collect::<Vec<_>>();
+    assert_eq!(lines.next(), None);
+    assert_eq!(header[..2], ["schema_version", "memory_metric"]);
+    assert_eq!(header.len(), row.len());
+
+    let cell = |column: &str| row[header.iter().position(|c| *c == column).unwrap()];
+    assert_eq!(cell("schema_version"), SCHEMA_VERSION.to_string());
+    assert_eq!(cell("memory_metric"), "pss");
+    assert_eq!(cell("pid"), "100");
+    assert_eq!(cell("name"), "firefox");
+    assert_eq!(cell("memory"), "2048");
+    assert_eq!(cell("disk_read"), "7");
+    // Missing values are left empty
+    assert_eq!(cell("exe"), "");
+}
+
+#[test]
+fn csv_header_is_written_without_processes() {
+    let out = export(ExportFormat::Csv, &[]);
+    assert_eq!(out.lines().count(), 1);
+    assert!(out.starts_with("schema_version,memory_metric,pid,name,"));
+}
+
+#[test]
+fn csv_cells_with_separators_are_quoted() {
+    let row = |name: &str| {
+        let out = export(ExportFormat::Csv, &[&process(100, name, &[])]);
+        let (_, row) = out.split_once('\n').unwrap();
+        row.to_string()
+    };
+
+    assert!(row("plain").contains(",100,plain,"));
+    assert!(row("a,b").contains(",100,\"a,b\","));
+    assert!(row("say \"hi\"").contains(",100,\"say \"\"hi\"\"\","));
+    assert!(row("two\nlines").contains(",100,\"two\nlines\","));
+    assert!(row("cr\rlf").contains(",100,\"cr\rlf\","));
+}
+
+#[test]
+fn csv_keeps_nested_values_as_json() {
+    let out = export(ExportFormat::Csv, &[&process(100, "sh", &["sh", "-c"])]);
+    assert!(out.contains(",\"[\"\"sh\"\",\"\"-c\"\"]\","));
+}
+
+#[test]
+fn formats_are_named_like_their_extension() {
+    for format in [ExportFormat::JsonLines, ExportFormat::Csv] {
+        assert_eq!(ExportFormat::from_name(format.extension()), Some(format));
+    }
+    assert_eq!(ExportFormat::from_name("JSON"), Some(ExportFormat::JsonLines));
+    assert_eq!(ExportFormat::from_name("xml"), None);
+}
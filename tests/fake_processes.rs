@@ -0,0 +1,1112 @@
+// SPDX-License-Identifier: MIT
+
+//! Sort, filter, protection and kill flows against fake process tables, with
+//! signals recorded instead of delivered.
+
+use cosmic_applet_process_killer::affinity::CpuMask;
+use cosmic_applet_process_killer::backend::ProcessBackend;
+use cosmic_applet_process_killer::cgroup::{Freezer, Limiter, Limits};
+use cosmic_applet_process_killer::container::{Container, ContainerManager, Runtime};
+use cosmic_applet_process_killer::controller::{
+    ConfirmationMode, GroupTarget, Message, PendingAction, ProcessController,
+};
+use cosmic_applet_process_killer::files::{Holder, OpenFile, Usage};
+use cosmic_applet_process_killer::package::{Package, PackageKind};
+use cosmic_applet_process_killer::priority::{IoClass, IoPriority, Scheduler};
+use cosmic_applet_process_killer::process::{
+    nix_pid, ProcessError, ProcessInfo, ProcessManager, ProcessResult, SignalSender, SortBy,
+    ThreadInfo,
+};
+use cosmic_applet_process_killer::query::SearchStore;
+use cosmic_applet_process_killer::sampler::SnapshotDiff;
+use cosmic_applet_process_killer::systemd::{Unit, UnitAction, UnitManager, UnitScope};
+use futures_util::future::{self, BoxFuture};
+use nix::sys::signal::Signal;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+struct FakeBackend(Vec<ProcessInfo>);
+
+impl ProcessBackend for FakeBackend {
+    fn sample(&mut self) -> Vec<ProcessInfo> {
+        self.0.clone()
+    }
+}
+
+#[derive(Default)]
+struct RecordingSignals {
+    sent: Mutex<Vec<(u32, Signal)>>,
+    /// `(tid, signal)` of signals sent to single threads
+    sent_to_threads: Mutex<Vec<(u32, Signal)>>,
+    failure: Option<ProcessError>,
+}
+
+impl RecordingSignals {
+    fn sent(&self) -> Vec<(u32, Signal)> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+impl SignalSender for RecordingSignals {
+    fn send_signal(&self, pid: u32, signal: Signal) -> ProcessResult<()> {
+        if let Some(error) = &self.failure {
+            return Err(error.clone());
+        }
+        self.sent.lock().unwrap().push((pid, signal));
+        Ok(())
+    }
+
+    fn send_thread_signal(&self, _pid: u32, tid: u32, signal: Signal) -> ProcessResult<()> {
+        if let Some(error) = &self.failure {
+            return Err(error.clone());
+        }
+        self.sent_to_threads.lock().unwrap().push((tid, signal));
+        Ok(())
+    }
+}
+
+fn process(pid: u32, name: &str, cpu_usage: f32, memory: u64) -> ProcessInfo {
+    ProcessInfo {
+        pid,
+        name: name.to_string(),
+        cpu_usage,
+        memory: Some(memory),
+        status: "Run".to_string(),
+        ..Default::default()
+    }
+}
+
+fn table() -> Vec<ProcessInfo> {
+    let mut processes = vec![
+        process(1, "systemd", 0.1, 12_000),
+        process(400, "Firefox", 35.0, 900_000),
+        process(401, "firefox-bin", 12.5, 300_000),
+        process(900, "cargo", 80.0, 50_000),
+        process(1500, "bash", 0.0, 4_000),
+    ];
+    // Enough idle processes to push some out of the top list
+    processes.extend((2000..2012).map(|pid| process(pid, "idle", 0.0, 1_000)));
+    processes
+}
+
+fn controller(signals: Arc<RecordingSignals>) -> ProcessController {
+    let mut controller = ProcessController::with_signals(signals);
+    controller.update(Message::Snapshot(SnapshotDiff::between(
+        &HashMap::new(),
+        &table(),
+    )));
+    controller
+}
+
+/// Run the background work of `controller` as a frontend would, until it
+/// starts no more.
+fn run_jobs(controller: &mut ProcessController) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    loop {
+        let jobs = controller.take_jobs();
+        if jobs.is_empty() {
+            return;
+        }
+        for job in jobs {
+            let message = runtime.block_on(job);
+            controller.update(message);
+        }
+    }
+}
+
+fn pids(processes: &[ProcessInfo]) -> Vec<u32> {
+    processes.iter().map(|p| p.pid).collect()
+}
+
+fn found(controller: &ProcessController) -> Vec<u32> {
+    controller.filtered_processes().iter().map(|p| p.pid).collect()
+}
+
+#[test]
+fn manager_sorts_fake_samples() {
+    let mut manager = ProcessManager::with_backend(Box::new(FakeBackend(table())));
+
+    assert_eq!(&pids(&manager.get_processes(SortBy::Cpu))[..3], [900, 400, 401]);
+    assert_eq!(&pids(&manager.get_processes(SortBy::Memory))[..3], [400, 401, 900]);
+    assert_eq!(&pids(&manager.get_processes(SortBy::Pid))[..3], [1, 400, 401]);
+
+    // Names sort case-insensitively
+    let by_name = manager.get_processes(SortBy::Name);
+    let names: Vec<&str> = by_name.iter().take(4).map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["bash", "cargo", "Firefox", "firefox-bin"]);
+}
+
+#[test]
+fn disk_io_sorts_by_rate() {
+    let mut processes = table();
+    processes[1].disk_write = 5_000_000; // Firefox
+    processes[3].disk_read = 80_000_000; // cargo
+    processes[4].disk_read = 1_000; // bash
+    let mut manager = ProcessManager::with_backend(Box::new(FakeBackend(processes)));
+
+    assert_eq!(&pids(&manager.get_processes(SortBy::DiskRead))[..2], [900, 1500]);
+    assert_eq!(pids(&manager.get_processes(SortBy::DiskWrite))[0], 400);
+    assert_eq!(SortBy::from_name("write"), Some(SortBy::DiskWrite));
+    assert!(SortBy::DiskRead.needs_disk_io() && !SortBy::Cpu.needs_disk_io());
+}
+
+#[test]
+fn only_the_top_processes_are_listed_until_show_all() {
+    let mut controller = controller(Arc::default());
+
+    assert_eq!(controller.processes().len(), 10);
+    assert_eq!(controller.processes()[0].pid, 900);
+
+    controller.update(Message::ToggleShowAll(true));
+    assert_eq!(controller.processes().len(), table().len());
+
+    controller.update(Message::SortBy(SortBy::Memory));
+    assert_eq!(controller.processes()[0].pid, 400);
+}
+
+#[test]
+fn search_matches_name_and_pid() {
+    let mut controller = controller(Arc::default());
+
+    controller.update(Message::UpdateSearch("FIREFOX".to_string()));
+    let found: Vec<u32> = controller.filtered_processes().iter().map(|p| p.pid).collect();
+    assert_eq!(found, [400, 401]);
+
+    controller.update(Message::UpdateSearch("900".to_string()));
+    let found: Vec<u32> = controller.filtered_processes().iter().map(|p| p.pid).collect();
+    assert_eq!(found, [900]);
+}
+
+#[test]
+fn invalid_queries_keep_the_last_valid_filter() {
+    let mut controller = controller(Arc::default());
+
+    controller.update(Message::UpdateSearch("cpu>30".to_string()));
+    assert_eq!(found(&controller), [900, 400]);
+    assert!(controller.search_error().is_none());
+    assert!(controller.can_save_search());
+
+    // Half-typed query: the list stays as it was
+    controller.update(Message::UpdateSearch("cpu>30 mem>".to_string()));
+    assert_eq!(controller.search_query(), "cpu>30 mem>");
+    assert_eq!(found(&controller), [900, 400]);
+    assert!(controller.search_error().is_some());
+    assert!(!controller.can_save_search());
+
+    controller.update(Message::UpdateSearch("cpu>30 mem>500K".to_string()));
+    assert_eq!(found(&controller), [400]);
+    assert!(controller.search_error().is_none());
+}
+
+#[test]
+fn protected_processes_cannot_be_killed() {
+    let signals = Arc::new(RecordingSignals::default());
+    let mut controller = controller(signals.clone());
+
+    controller.update(Message::ForceKillProcess(1));
+
+    assert!(controller.confirmation().is_none());
+    assert!(controller.toast().unwrap().is_error);
+    assert!(signals.sent().is_empty());
+}
+
+#[test]
+fn confirmed_kills_send_the_matching_signal() {
+    let signals = Arc::new(RecordingSignals::default());
+    let mut controller = controller(signals.clone());
+
+    controller.update(Message::KillProcess(900));
+    assert_eq!(
+        controller.confirmation().map(|(p, mode)| (p.pid, mode)),
+        Some((900, ConfirmationMode::Kill))
+    );
+    assert!(signals.sent().is_empty());
+    controller.update(Message::ConfirmKill);
+
+    controller.update(Message::ForceKillProcess(400));
+    controller.update(Message::ConfirmForceKill);
+
+    assert_eq!(
+        signals.sent(),
+        [(900, Signal::SIGTERM), (400, Signal::SIGKILL)]
+    );
+    assert!(!controller.toast().unwrap().is_error);
+}
+
+#[test]
+fn cancelled_kills_send_nothing() {
+    let signals = Arc::new(RecordingSignals::default());
+    let mut controller = controller(signals.clone());
+
+    controller.update(Message::KillProcess(900));
+    controller.update(Message::CancelConfirmation);
+    controller.update(Message::ConfirmKill);
+
+    assert!(signals.sent().is_empty());
+}
+
+#[test]
+fn failed_signals_show_an_error() {
+    let signals = Arc::new(RecordingSignals {
+        failure: Some(ProcessError::PermissionDenied),
+        ..Default::default()
+    });
+    let mut controller = controller(signals);
+
+    controller.update(Message::KillProcess(1500));
+    controller.update(Message::ConfirmKill);
+
+    assert!(controller.toast().unwrap().is_error);
+}
+
+#[test]
+fn exited_processes_cannot_be_killed() {
+    let signals = Arc::new(RecordingSignals::default());
+    let mut controller = controller(signals.clone());
+
+    controller.update(Message::Snapshot(SnapshotDiff {
+        removed: vec![900],
+        ..Default::default()
+    }));
+    controller.update(Message::KillProcess(900));
+
+    assert!(controller.confirmation().is_none());
+    assert!(controller.toast().unwrap().is_error);
+    assert!(signals.sent().is_empty());
+}
+
+#[test]
+fn confirmation_shows_live_values() {
+    let mut controller = controller(Arc::default());
+
+    controller.update(Message::KillProcess(900));
+    assert!(controller.is_confirming());
+    controller.update(Message::Snapshot(SnapshotDiff {
+        updated: vec![process(900, "cargo", 20.0, 70_000)],
+        ..Default::default()
+    }));
+
+    let (process, _) = controller.confirmation().unwrap();
+    assert_eq!(process.cpu_usage, 20.0);
+    assert_eq!(process.memory, Some(70_000));
+}
+
+#[test]
+fn confirmation_is_cancelled_when_the_target_exits() {
+    let signals = Arc::new(RecordingSignals::default());
+    let mut controller = controller(signals.clone());
+
+    controller.update(Message::KillProcess(900));
+    controller.update(Message::Snapshot(SnapshotDiff {
+        removed: vec![900],
+        ..Default::default()
+    }));
+
+    assert!(controller.confirmation().is_none());
+    assert!(!controller.is_confirming());
+    assert!(!controller.toast().unwrap().is_error);
+
+    controller.update(Message::ConfirmKill);
+    assert!(signals.sent().is_empty());
+}
+
+#[test]
+fn a_reused_pid_is_not_the_selected_process() {
+    let signals = Arc::new(RecordingSignals::default());
+    let mut controller = controller(signals.clone());
+
+    controller.update(Message::SelectProcess(Some(1500)));
+    let old = controller.selected_process().unwrap().clone();
+    assert!(controller.is_selected(&old));
+
+    // bash exited and a new process got its PID
+    let newcomer = ProcessInfo {
+        start_time: old.start_time + 60,
+        ..process(1500, "make", 50.0, 8_000)
+    };
+    controller.update(Message::Snapshot(SnapshotDiff {
+        updated: vec![newcomer.clone()],
+        ..Default::default()
+    }));
+
+    assert!(controller.selected_process().is_none());
+    assert!(!controller.is_selected(&newcomer));
+}
+
+#[test]
+fn search_highlights_split_the_label() {
+    let mut controller = controller(Arc::default());
+
+    controller.update(Message::UpdateSearch("fox".to_string()));
+    assert_eq!(
+        controller.search_highlights("firefox-bin"),
+        [("fire", false), ("fox", true), ("-bin", false)]
+    );
+    assert_eq!(controller.search_highlights("cargo"), [("cargo", false)]);
+}
+
+#[derive(Default)]
+struct RecordingStore {
+    saved: Mutex<Vec<Vec<String>>>,
+    failure: Option<String>,
+}
+
+impl SearchStore for RecordingStore {
+    fn save(&self, searches: &[String]) -> Result<(), String> {
+        if let Some(error) = &self.failure {
+            return Err(error.clone());
+        }
+        self.saved.lock().unwrap().push(searches.to_vec());
+        Ok(())
+    }
+}
+
+#[test]
+fn searches_are_saved_once_and_removed() {
+    let store = Arc::new(RecordingStore::default());
+    let mut controller = controller(Arc::default()).with_search_store(store.clone());
+    controller.set_saved_searches(vec!["cpu>50".to_string()]);
+
+    controller.update(Message::UpdateSearch(" user:root ".to_string()));
+    controller.update(Message::SaveSearch);
+    // Already saved
+    controller.update(Message::SaveSearch);
+    assert_eq!(controller.saved_searches(), ["cpu>50", "user:root"]);
+
+    controller.update(Message::RemoveSavedSearch("cpu>50".to_string()));
+    assert_eq!(controller.saved_searches(), ["user:root"]);
+    assert_eq!(
+        *store.saved.lock().unwrap(),
+        [vec!["cpu>50", "user:root"], vec!["user:root"]]
+    );
+
+    // What could not be stored is not shown as saved
+    let store = Arc::new(RecordingStore {
+        failure: Some("read-only".to_string()),
+        ..Default::default()
+    });
+    let mut controller = controller.with_search_store(store);
+    controller.update(Message::SaveSearch);
+    controller.update(Message::RemoveSavedSearch("user:root".to_string()));
+    assert_eq!(controller.saved_searches(), ["user:root"]);
+}
+
+#[derive(Default)]
+struct RecordingScheduler {
+    changes: Mutex<Vec<(u32, i32)>>,
+    pinned: Mutex<Vec<(u32, CpuMask)>>,
+    failure: Option<ProcessError>,
+}
+
+impl Scheduler for RecordingScheduler {
+    fn set_nice(&self, pid: u32, nice: i32) -> ProcessResult<()> {
+        if let Some(error) = &self.failure {
+            return Err(error.clone());
+        }
+        self.changes.lock().unwrap().push((pid, nice));
+        Ok(())
+    }
+
+    fn set_io_priority(&self, _pid: u32, _priority: IoPriority) -> ProcessResult<()> {
+        self.failure.clone().map_or(Ok(()), Err)
+    }
+
+    fn affinity(&self, _pid: u32) -> ProcessResult<CpuMask> {
+        Ok(CpuMask::all())
+    }
+
+    fn set_affinity(&self, pid: u32, mask: &CpuMask, _all_threads: bool) -> ProcessResult<()> {
+        if let Some(error) = &self.failure {
+            return Err(error.clone());
+        }
+        self.pinned.lock().unwrap().push((pid, mask.clone()));
+        Ok(())
+    }
+}
+
+#[test]
+fn renicing_goes_through_the_scheduler() {
+    let scheduler = Arc::new(RecordingScheduler::default());
+    let mut controller = controller(Arc::default()).with_scheduler(scheduler.clone());
+
+    controller.update(Message::SetNice { pid: 900, nice: 10 });
+    assert_eq!(*scheduler.changes.lock().unwrap(), [(900, 10)]);
+    assert!(!controller.toast().unwrap().is_error);
+
+    // Unknown processes are not touched
+    controller.update(Message::SetNice { pid: 12345, nice: 10 });
+    assert_eq!(scheduler.changes.lock().unwrap().len(), 1);
+    assert!(controller.toast().unwrap().is_error);
+}
+
+#[test]
+fn refused_priority_changes_show_an_error() {
+    let scheduler = Arc::new(RecordingScheduler {
+        failure: Some(ProcessError::PermissionDenied),
+        ..Default::default()
+    });
+    let mut controller = controller(Arc::default()).with_scheduler(scheduler);
+
+    controller.update(Message::SetIoPriority {
+        pid: 900,
+        priority: IoPriority {
+            class: IoClass::RealTime,
+            level: 0,
+        },
+    });
+
+    let toast = controller.toast().unwrap();
+    assert!(toast.is_error);
+    assert!(toast.message.contains("cargo"));
+}
+
+#[test]
+fn pinning_goes_through_the_scheduler() {
+    let scheduler = Arc::new(RecordingScheduler::default());
+    let mut controller = controller(Arc::default()).with_scheduler(scheduler.clone());
+    assert_eq!(controller.affinity(900), Ok(CpuMask::all()));
+
+    let mask = CpuMask::from_cpus([0]);
+    controller.update(Message::SetAffinity {
+        pid: 900,
+        mask: mask.clone(),
+        all_threads: true,
+    });
+
+    assert_eq!(*scheduler.pinned.lock().unwrap(), [(900, mask)]);
+    assert!(!controller.toast().unwrap().is_error);
+}
+
+fn thread(tid: u32, name: &str, cpu_usage: f32) -> ThreadInfo {
+    ThreadInfo {
+        tid,
+        name: name.to_string(),
+        cpu_usage,
+        status: "Sleep".to_string(),
+        nice: 0,
+    }
+}
+
+#[test]
+fn threads_of_the_watched_process_are_kept_sorted() {
+    let mut controller = controller(Arc::default());
+    let threads = vec![thread(900, "cargo", 1.0), thread(901, "jobserver", 60.0)];
+
+    // Nothing is watched yet
+    controller.update(Message::Threads {
+        pid: 900,
+        threads: threads.clone(),
+    });
+    assert!(controller.threads().is_empty());
+
+    controller.update(Message::WatchThreads(Some(900)));
+    controller.update(Message::Threads { pid: 900, threads });
+    let tids = |c: &ProcessController| c.threads().iter().map(|t| t.tid).collect::<Vec<_>>();
+    assert_eq!(tids(&controller), [901, 900]);
+
+    controller.update(Message::SortThreadsBy(SortBy::Pid));
+    assert_eq!(tids(&controller), [900, 901]);
+
+    // Late samples of a previously watched process are dropped
+    controller.update(Message::WatchThreads(None));
+    controller.update(Message::Threads {
+        pid: 900,
+        threads: vec![thread(900, "cargo", 1.0)],
+    });
+    assert!(controller.threads().is_empty());
+}
+
+#[test]
+fn signals_reach_single_threads() {
+    let signals = Arc::new(RecordingSignals::default());
+    let mut controller = controller(signals.clone());
+
+    controller.update(Message::SignalThread {
+        pid: 900,
+        tid: 901,
+        signal: Signal::SIGUSR1,
+    });
+    assert_eq!(*signals.sent_to_threads.lock().unwrap(), [(901, Signal::SIGUSR1)]);
+    assert!(!controller.toast().unwrap().is_error);
+
+    // Threads of protected processes are left alone
+    controller.update(Message::SignalThread {
+        pid: 1,
+        tid: 1,
+        signal: Signal::SIGUSR2,
+    });
+    assert_eq!(signals.sent_to_threads.lock().unwrap().len(), 1);
+    assert!(controller.toast().unwrap().is_error);
+    assert!(signals.sent().is_empty());
+}
+
+fn holder(pid: u32, path: &str) -> Holder {
+    Holder {
+        pid,
+        files: vec![OpenFile {
+            path: PathBuf::from(path),
+            usage: Usage::WorkingDirectory,
+        }],
+    }
+}
+
+#[test]
+fn holders_of_the_current_lookup_can_be_killed() {
+    let signals = Arc::new(RecordingSignals::default());
+    let mut controller = controller(signals.clone());
+
+    controller.update(Message::FindHolders(PathBuf::from("/mnt/usb")));
+    assert_eq!(controller.holder_lookup().unwrap().holders, None);
+
+    // Answers to another lookup are dropped
+    controller.update(Message::Holders {
+        path: PathBuf::from("/mnt/cdrom"),
+        holders: vec![holder(1500, "/mnt/cdrom")],
+    });
+    assert_eq!(controller.holder_lookup().unwrap().holders, None);
+
+    controller.update(Message::Holders {
+        path: PathBuf::from("/mnt/usb"),
+        holders: vec![
+            holder(1, "/mnt/usb"),
+            holder(900, "/mnt/usb/target"),
+            holder(4242, "/mnt/usb"),
+        ],
+    });
+    controller.update(Message::KillHolders { force: true });
+
+    // Only after confirming, and neither systemd, which is protected, nor a
+    // process missing from the sample
+    assert!(signals.sent().is_empty());
+    let group = controller.group_kill().unwrap();
+    assert_eq!(group.target, GroupTarget::Holders(PathBuf::from("/mnt/usb")));
+    assert_eq!(group.signal, Signal::SIGKILL);
+    assert_eq!(group.processes.iter().map(|key| key.pid).collect::<Vec<_>>(), [900]);
+    assert!(controller.is_confirming());
+
+    controller.update(Message::ConfirmGroupKill);
+    assert_eq!(signals.sent(), [(900, Signal::SIGKILL)]);
+    assert!(!controller.toast().unwrap().is_error);
+    assert!(controller.group_kill().is_none());
+
+    controller.update(Message::CloseHolders);
+    assert!(controller.holder_lookup().is_none());
+}
+
+#[test]
+fn a_group_kill_leaves_reused_pids_alone() {
+    let signals = Arc::new(RecordingSignals::default());
+    let mut controller = controller(signals.clone());
+    controller.update(Message::FindHolders(PathBuf::from("/mnt/usb")));
+    controller.update(Message::Holders {
+        path: PathBuf::from("/mnt/usb"),
+        holders: vec![holder(900, "/mnt/usb")],
+    });
+    controller.update(Message::KillHolders { force: false });
+    assert!(controller.group_kill().is_some());
+
+    // cargo exited and another process got its PID before the answer
+    controller.update(Message::Snapshot(SnapshotDiff {
+        updated: vec![ProcessInfo {
+            start_time: 99,
+            ..process(900, "other", 0.0, 1_000)
+        }],
+        ..Default::default()
+    }));
+    assert!(controller.group_kill().is_none());
+    controller.update(Message::ConfirmGroupKill);
+    assert!(signals.sent().is_empty());
+
+    // Nothing left to kill is said right away
+    controller.update(Message::Holders {
+        path: PathBuf::from("/mnt/usb"),
+        holders: vec![holder(1, "/mnt/usb")],
+    });
+    controller.update(Message::KillHolders { force: false });
+    assert!(controller.group_kill().is_none());
+    assert!(controller.toast().unwrap().is_error);
+}
+
+/// A unit of our own user's manager
+fn own_unit(name: &str) -> Unit {
+    Unit {
+        name: name.to_string(),
+        scope: UnitScope::User,
+        uid: Some(nix::unistd::getuid().as_raw()),
+    }
+}
+
+/// Records unit actions instead of calling systemd
+#[derive(Default)]
+struct RecordingUnits {
+    actions: Mutex<Vec<(String, UnitAction)>>,
+    limits: Mutex<Vec<(String, Limits)>>,
+    limited_processes: Mutex<Vec<(u32, Limits)>>,
+}
+
+impl UnitManager for RecordingUnits {
+    fn run(&self, unit: &Unit, action: UnitAction) -> BoxFuture<'static, ProcessResult<()>> {
+        self.actions
+            .lock()
+            .unwrap()
+            .push((unit.name.clone(), action));
+        Box::pin(future::ready(Ok(())))
+    }
+
+    fn set_limits(&self, unit: &Unit, limits: Limits) -> BoxFuture<'static, ProcessResult<()>> {
+        self.limits.lock().unwrap().push((unit.name.clone(), limits));
+        Box::pin(future::ready(Ok(())))
+    }
+
+    fn limit_process(
+        &self,
+        pid: u32,
+        _uid: Option<u32>,
+        limits: Limits,
+    ) -> BoxFuture<'static, ProcessResult<()>> {
+        self.limited_processes.lock().unwrap().push((pid, limits));
+        Box::pin(future::ready(Ok(())))
+    }
+}
+
+#[test]
+fn units_are_acted_on_instead_of_their_processes() {
+    let signals = Arc::new(RecordingSignals::default());
+    let units = Arc::new(RecordingUnits::default());
+    let mut processes = table();
+    processes[3].unit = Some(own_unit("cargo-watch.service"));
+    processes[4].unit = Some(Unit {
+        name: "session-2.scope".to_string(),
+        scope: UnitScope::System,
+        uid: None,
+    });
+    let mut controller = ProcessController::with_signals(signals.clone()).with_units(units.clone());
+    controller.update(Message::Snapshot(SnapshotDiff::between(
+        &HashMap::new(),
+        &processes,
+    )));
+
+    // Every process of the unit goes along, so it is confirmed first
+    controller.update(Message::UnitAction {
+        pid: 900,
+        action: UnitAction::Stop,
+    });
+    assert_eq!(
+        controller.pending_action(),
+        Some(&PendingAction::Unit {
+            unit: own_unit("cargo-watch.service"),
+            action: UnitAction::Stop,
+        })
+    );
+    controller.update(Message::CancelConfirmation);
+    assert!(controller.pending_action().is_none());
+
+    controller.update(Message::UnitAction {
+        pid: 900,
+        action: UnitAction::Restart,
+    });
+    assert!(controller.is_confirming());
+    assert!(units.actions.lock().unwrap().is_empty());
+    controller.update(Message::ConfirmPendingAction);
+    assert_eq!(
+        *units.actions.lock().unwrap(),
+        [("cargo-watch.service".to_string(), UnitAction::Restart)]
+    );
+    // systemd answers in the background
+    assert!(controller.toast().is_none());
+    run_jobs(&mut controller);
+    assert!(signals.sent().is_empty());
+    assert!(!controller.toast().unwrap().is_error);
+
+    // Stopping a login session would log out: the process is signalled,
+    // once confirmed like any kill
+    controller.update(Message::UnitAction {
+        pid: 1500,
+        action: UnitAction::Kill,
+    });
+    assert!(signals.sent().is_empty());
+    let (process, mode) = controller.confirmation().unwrap();
+    assert_eq!((process.pid, mode), (1500, ConfirmationMode::ForceKill));
+    controller.update(Message::ConfirmForceKill);
+    assert_eq!(signals.sent(), [(1500, Signal::SIGKILL)]);
+
+    // Without a unit there is nothing to restart
+    controller.update(Message::UnitAction {
+        pid: 400,
+        action: UnitAction::Restart,
+    });
+    assert!(controller.toast().unwrap().is_error);
+    assert_eq!(units.actions.lock().unwrap().len(), 1);
+    assert_eq!(signals.sent().len(), 1);
+}
+
+#[test]
+fn the_unit_running_the_killer_is_left_alone() {
+    let units = Arc::new(RecordingUnits::default());
+    let mut processes = table();
+    processes[3].unit = Some(own_unit("cargo-watch.service"));
+    let mut own = processes[3].clone();
+    own.pid = std::process::id();
+    processes.push(own);
+    let mut controller = ProcessController::with_signals(Arc::new(RecordingSignals::default()))
+        .with_units(units.clone());
+    controller.update(Message::Snapshot(SnapshotDiff::between(
+        &HashMap::new(),
+        &processes,
+    )));
+
+    controller.update(Message::UnitAction {
+        pid: 900,
+        action: UnitAction::Kill,
+    });
+    assert!(controller.toast().unwrap().is_error);
+    assert!(controller.pending_action().is_none());
+    assert!(units.actions.lock().unwrap().is_empty());
+}
+
+const EDITOR_SCOPE: &str =
+    "/user.slice/user-1000.slice/user@1000.service/app.slice/app-org.example.Editor-1.scope";
+
+/// Records freezing and thawing instead of writing cgroup files
+#[derive(Default)]
+struct RecordingFreezer {
+    calls: Mutex<Vec<(String, bool)>>,
+}
+
+impl Freezer for RecordingFreezer {
+    fn set_frozen(&self, cgroup: &str, frozen: bool) -> ProcessResult<()> {
+        self.calls.lock().unwrap().push((cgroup.to_string(), frozen));
+        Ok(())
+    }
+}
+
+#[test]
+fn apps_are_frozen_through_their_cgroup() {
+    let freezer = Arc::new(RecordingFreezer::default());
+    let signals = Arc::new(RecordingSignals::default());
+    let mut processes = table();
+    for process in &mut processes[..3] {
+        process.app_cgroup = Some(EDITOR_SCOPE.to_string());
+    }
+    let mut controller =
+        ProcessController::with_signals(signals.clone()).with_freezer(freezer.clone());
+    controller.update(Message::Snapshot(SnapshotDiff::between(
+        &HashMap::new(),
+        &processes,
+    )));
+
+    controller.update(Message::SetFrozen {
+        pid: 401,
+        frozen: true,
+    });
+    assert_eq!(
+        *freezer.calls.lock().unwrap(),
+        [(EDITOR_SCOPE.to_string(), true)]
+    );
+    assert!(!controller.toast().unwrap().is_error);
+    assert!(signals.sent().is_empty());
+
+    // Outside of an app there is no cgroup to freeze
+    controller.update(Message::SetFrozen {
+        pid: 900,
+        frozen: true,
+    });
+    assert!(controller.toast().unwrap().is_error);
+
+    // Nor are protected processes frozen
+    controller.update(Message::SetFrozen {
+        pid: 1,
+        frozen: true,
+    });
+    assert!(controller.toast().unwrap().is_error);
+    assert_eq!(freezer.calls.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn the_app_running_the_killer_is_not_frozen() {
+    let freezer = Arc::new(RecordingFreezer::default());
+    let mut processes = table();
+    processes[1].app_cgroup = Some(EDITOR_SCOPE.to_string());
+    let mut own = processes[2].clone();
+    own.pid = std::process::id();
+    own.app_cgroup = Some(EDITOR_SCOPE.to_string());
+    processes.push(own);
+    let mut controller = ProcessController::with_signals(Arc::new(RecordingSignals::default()))
+        .with_freezer(freezer.clone());
+    controller.update(Message::Snapshot(SnapshotDiff::between(
+        &HashMap::new(),
+        &processes,
+    )));
+
+    controller.update(Message::SetFrozen {
+        pid: 400,
+        frozen: true,
+    });
+    assert!(controller.toast().unwrap().is_error);
+    assert!(freezer.calls.lock().unwrap().is_empty());
+
+    // Thawing it is still allowed
+    controller.update(Message::SetFrozen {
+        pid: 400,
+        frozen: false,
+    });
+    assert_eq!(
+        *freezer.calls.lock().unwrap(),
+        [(EDITOR_SCOPE.to_string(), false)]
+    );
+}
+
+/// Records limits, failing like a cgroup without the memory and CPU
+/// controllers when told to
+#[derive(Default)]
+struct RecordingLimiter {
+    limits: Mutex<Vec<(String, Limits)>>,
+    controllers_disabled: bool,
+}
+
+impl Limiter for RecordingLimiter {
+    fn set_limits(&self, cgroup: &str, limits: Limits) -> ProcessResult<()> {
+        if self.controllers_disabled {
+            return Err(ProcessError::NotFound);
+        }
+        self.limits.lock().unwrap().push((cgroup.to_string(), limits));
+        Ok(())
+    }
+}
+
+#[test]
+fn apps_are_limited_through_their_cgroup_and_the_rest_through_systemd() {
+    let limiter = Arc::new(RecordingLimiter::default());
+    let units = Arc::new(RecordingUnits::default());
+    let mut processes = table();
+    processes[1].app_cgroup = Some(EDITOR_SCOPE.to_string());
+    processes[3].unit = Some(own_unit("cargo-watch.service"));
+    let mut controller = ProcessController::with_signals(Arc::new(RecordingSignals::default()))
+        .with_limiter(limiter.clone())
+        .with_units(units.clone());
+    controller.update(Message::Snapshot(SnapshotDiff::between(
+        &HashMap::new(),
+        &processes,
+    )));
+
+    let limits = Limits {
+        memory_max: Some(1 << 30),
+        cpu_max: Some(50),
+    };
+    controller.update(Message::SetLimits { pid: 400, limits });
+    assert_eq!(
+        *limiter.limits.lock().unwrap(),
+        [(EDITOR_SCOPE.to_string(), limits)]
+    );
+    assert!(!controller.toast().unwrap().is_error);
+
+    // systemd answers in the background
+    controller.update(Message::ClearToast);
+    controller.update(Message::SetLimits { pid: 900, limits });
+    assert_eq!(
+        *units.limits.lock().unwrap(),
+        [("cargo-watch.service".to_string(), limits)]
+    );
+    assert!(controller.toast().is_none());
+    run_jobs(&mut controller);
+    assert!(!controller.toast().unwrap().is_error);
+
+    // Neither an app nor a service: limited on its own
+    controller.update(Message::ClearToast);
+    controller.update(Message::SetLimits { pid: 1500, limits });
+    assert_eq!(*units.limited_processes.lock().unwrap(), [(1500, limits)]);
+    run_jobs(&mut controller);
+    assert!(!controller.toast().unwrap().is_error);
+    assert_eq!(units.limits.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn apps_without_controllers_are_limited_through_systemd() {
+    let limiter = Arc::new(RecordingLimiter {
+        controllers_disabled: true,
+        ..Default::default()
+    });
+    let units = Arc::new(RecordingUnits::default());
+    let mut processes = table();
+    processes[1].app_cgroup = Some(EDITOR_SCOPE.to_string());
+    processes[1].unit = Some(own_unit("app-org.example.Editor-1.scope"));
+    let mut controller = ProcessController::with_signals(Arc::new(RecordingSignals::default()))
+        .with_limiter(limiter)
+        .with_units(units.clone());
+    controller.update(Message::Snapshot(SnapshotDiff::between(
+        &HashMap::new(),
+        &processes,
+    )));
+
+    controller.update(Message::SetLimits {
+        pid: 400,
+        limits: Limits::default(),
+    });
+    assert_eq!(
+        *units.limits.lock().unwrap(),
+        [("app-org.example.Editor-1.scope".to_string(), Limits::default())]
+    );
+    run_jobs(&mut controller);
+    assert!(!controller.toast().unwrap().is_error);
+}
+
+#[test]
+fn a_memory_cap_below_the_usage_is_warned_about() {
+    let limiter = Arc::new(RecordingLimiter::default());
+    let mut processes = table();
+    for process in &mut processes[1..3] {
+        process.app_cgroup = Some(EDITOR_SCOPE.to_string());
+    }
+    let mut controller = ProcessController::with_signals(Arc::new(RecordingSignals::default()))
+        .with_limiter(limiter.clone());
+    controller.update(Message::Snapshot(SnapshotDiff::between(
+        &HashMap::new(),
+        &processes,
+    )));
+
+    // Firefox uses 1,200,000 bytes across both of its processes
+    let limits = Limits {
+        memory_max: Some(1_000_000),
+        cpu_max: None,
+    };
+    controller.update(Message::SetLimits { pid: 400, limits });
+    assert_eq!(limiter.limits.lock().unwrap().len(), 1);
+    assert!(controller.toast().unwrap().is_error);
+
+    let limits = Limits {
+        memory_max: Some(2_000_000),
+        cpu_max: None,
+    };
+    controller.update(Message::SetLimits { pid: 400, limits });
+    assert!(!controller.toast().unwrap().is_error);
+}
+
+#[test]
+fn packaged_apps_are_killed_as_a_whole() {
+    let signals = Arc::new(RecordingSignals::default());
+    let flatpak = |instance: &str| Package {
+        kind: PackageKind::Flatpak,
+        app_id: "org.mozilla.firefox".to_string(),
+        instance: instance.to_string(),
+        icon: None,
+    };
+    let mut processes = table();
+    processes[1].package = Some(flatpak("1"));
+    processes[2].package = Some(flatpak("1"));
+    // Another running copy of the same app is left alone
+    processes[3].package = Some(flatpak("2"));
+    let mut controller = ProcessController::with_signals(signals.clone());
+    controller.update(Message::Snapshot(SnapshotDiff::between(
+        &HashMap::new(),
+        &processes,
+    )));
+
+    controller.update(Message::KillPackage(401));
+    let group = controller.group_kill().unwrap();
+    assert_eq!(group.target, GroupTarget::Package(flatpak("1")));
+    assert_eq!(group.signal, Signal::SIGKILL);
+    assert!(signals.sent().is_empty());
+
+    controller.update(Message::ConfirmGroupKill);
+    assert_eq!(
+        signals.sent(),
+        [(400, Signal::SIGKILL), (401, Signal::SIGKILL)]
+    );
+    assert!(!controller.toast().unwrap().is_error);
+
+    controller.update(Message::KillPackage(1500));
+    assert!(controller.toast().unwrap().is_error);
+    assert_eq!(signals.sent().len(), 2);
+}
+
+#[derive(Default)]
+struct RecordingContainers {
+    stopped: Mutex<Vec<String>>,
+}
+
+impl ContainerManager for RecordingContainers {
+    fn stop(&self, container: &Container) -> ProcessResult<()> {
+        self.stopped.lock().unwrap().push(container.id.clone());
+        Ok(())
+    }
+}
+
+#[test]
+fn containers_are_stopped_instead_of_their_processes_killed() {
+    let signals = Arc::new(RecordingSignals::default());
+    let containers = Arc::new(RecordingContainers::default());
+    let mut processes = table();
+    processes[3].container = Some(Container {
+        runtime: Runtime::Podman,
+        id: "8e2c4a6b1d3f".to_string(),
+        name: Some("builder".to_string()),
+    });
+    let mut controller =
+        ProcessController::with_signals(signals.clone()).with_containers(containers.clone());
+    controller.update(Message::Snapshot(SnapshotDiff::between(
+        &HashMap::new(),
+        &processes,
+    )));
+
+    controller.update(Message::StopContainer(900));
+    assert!(matches!(
+        controller.pending_action(),
+        Some(PendingAction::StopContainer(container)) if container.id == "8e2c4a6b1d3f"
+    ));
+    controller.update(Message::CancelConfirmation);
+    assert!(controller.pending_action().is_none());
+
+    controller.update(Message::StopContainer(900));
+    controller.update(Message::ConfirmPendingAction);
+    // The runtime is asked in the background
+    assert!(containers.stopped.lock().unwrap().is_empty());
+    assert!(controller.toast().is_none());
+    run_jobs(&mut controller);
+    assert_eq!(*containers.stopped.lock().unwrap(), ["8e2c4a6b1d3f"]);
+    assert!(!controller.toast().unwrap().is_error);
+    assert!(signals.sent().is_empty());
+
+    // Not in a container
+    controller.update(Message::StopContainer(1500));
+    assert!(controller.toast().unwrap().is_error);
+    assert_eq!(containers.stopped.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn the_container_running_the_killer_is_left_alone() {
+    let containers = Arc::new(RecordingContainers::default());
+    let mut processes = table();
+    processes[3].container = Some(Container {
+        runtime: Runtime::Podman,
+        id: "8e2c4a6b1d3f".to_string(),
+        name: Some("toolbox".to_string()),
+    });
+    let mut own = processes[3].clone();
+    own.pid = std::process::id();
+    processes.push(own);
+    let mut controller = ProcessController::with_signals(Arc::new(RecordingSignals::default()))
+        .with_containers(containers.clone());
+    controller.update(Message::Snapshot(SnapshotDiff::between(
+        &HashMap::new(),
+        &processes,
+    )));
+
+    controller.update(Message::StopContainer(900));
+    assert!(controller.toast().unwrap().is_error);
+    assert!(controller.pending_action().is_none());
+    assert!(containers.stopped.lock().unwrap().is_empty());
+}
+
+#[test]
+fn pids_that_cannot_be_processes_are_never_signalled() {
+    assert_eq!(nix_pid(4242).map(|pid| pid.as_raw()), Ok(4242));
+
+    // kill(2) would take these for our process group, or for every process
+    for pid in [0, u32::MAX, i32::MAX as u32 + 1] {
+        assert_eq!(nix_pid(pid), Err(ProcessError::NotFound));
+    }
+}
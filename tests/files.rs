@@ -0,0 +1,96 @@
+// SPDX-License-Identifier: MIT
+
+//! Open files of a process, and the processes holding a path.
+
+use cosmic_applet_process_killer::backend::ProcessBackend;
+use cosmic_applet_process_killer::files::{holders, open_files, parse_maps, OpenFile, Usage};
+use cosmic_applet_process_killer::process::ProcessInfo;
+use cosmic_applet_process_killer::procfs::ProcfsBackend;
+use std::path::{Path, PathBuf};
+
+fn fixture_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/proc")
+}
+
+fn file(path: &str, usage: Usage) -> OpenFile {
+    OpenFile {
+        path: PathBuf::from(path),
+        usage,
+    }
+}
+
+#[test]
+fn maps_list_each_mapped_path() {
+    let maps = "7f3b30a00000-7f3b30a28000 r--p 00000000 08:01 1837240    /usr/lib/libc.so.6\n\
+        7ffd4e1b2000-7ffd4e1d3000 rw-p 00000000 00:00 0          [stack]\n\
+        7f3b2c000000-7f3b2c021000 rw-p 00000000 00:00 0 \n\
+        7f3b31000000-7f3b31400000 rw-s 00000000 00:01 7   /memfd:buffer (deleted)\n\
+        7f3b31400000-7f3b31600000 r--p 00000000 08:01 9   /home/user/My Notes.txt\n";
+
+    assert_eq!(
+        parse_maps(maps),
+        [
+            PathBuf::from("/usr/lib/libc.so.6"),
+            PathBuf::from("/memfd:buffer"),
+            PathBuf::from("/home/user/My Notes.txt"),
+        ]
+    );
+}
+
+#[test]
+fn open_files_come_from_descriptors_maps_and_links() {
+    assert_eq!(
+        open_files(&fixture_root(), 1200),
+        [
+            file("/dev/null", Usage::Descriptor(0)),
+            file("/mnt/usb/backup/log.txt", Usage::Descriptor(7)),
+            file("/mnt/usb/old.tmp", Usage::Descriptor(8)),
+            file("/mnt/usb/My Data/cache.db", Usage::Mapped),
+            file("/usr/bin/python3.12", Usage::Mapped),
+            file("/usr/lib/libc.so.6", Usage::Mapped),
+            file("/mnt/usb/backup", Usage::WorkingDirectory),
+            file("/", Usage::RootDirectory),
+            file("/usr/bin/python3.12", Usage::Executable),
+        ]
+    );
+}
+
+#[test]
+fn holders_of_a_mount_point_include_everything_below_it() {
+    let found = holders(&fixture_root(), Path::new("/mnt/usb"));
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].pid, 1200);
+    assert_eq!(
+        found[0].files,
+        [
+            file("/mnt/usb/backup/log.txt", Usage::Descriptor(7)),
+            file("/mnt/usb/old.tmp", Usage::Descriptor(8)),
+            file("/mnt/usb/My Data/cache.db", Usage::Mapped),
+            file("/mnt/usb/backup", Usage::WorkingDirectory),
+        ]
+    );
+
+    // Paths are compared by component, not as text
+    assert!(holders(&fixture_root(), Path::new("/mnt/us")).is_empty());
+    let pids: Vec<u32> = holders(&fixture_root(), Path::new("/home/user"))
+        .iter()
+        .map(|holder| holder.pid)
+        .collect();
+    assert_eq!(pids, [1300]);
+}
+
+#[test]
+fn held_paths_are_only_sampled_on_demand() {
+    let mut backend = ProcfsBackend::with_root(fixture_root());
+    let held_by_1200 = |processes: Vec<ProcessInfo>| {
+        processes
+            .into_iter()
+            .find(|p| p.pid == 1200)
+            .unwrap()
+            .held_paths
+    };
+
+    assert!(held_by_1200(backend.sample()).is_empty());
+    backend.set_held_paths(vec![PathBuf::from("/mnt/usb"), PathBuf::from("/srv")]);
+    assert_eq!(held_by_1200(backend.sample()), [PathBuf::from("/mnt/usb")]);
+}
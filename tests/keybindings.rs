@@ -0,0 +1,99 @@
+// SPDX-License-Identifier: MIT
+
+//! Keyboard shortcuts and the bindings they are configured with.
+
+use cosmic_applet_process_killer::keybindings::{KeyAction, KeyBindings, Modifiers};
+
+const NONE: Modifiers = Modifiers {
+    ctrl: false,
+    alt: false,
+    shift: false,
+};
+
+const SHIFT: Modifiers = Modifiers {
+    shift: true,
+    ..NONE
+};
+
+const CTRL_ALT: Modifiers = Modifiers {
+    ctrl: true,
+    alt: true,
+    ..NONE
+};
+
+fn kill_bound_to(binding: &str) -> KeyBindings {
+    KeyBindings {
+        kill: binding.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn default_bindings() {
+    let bindings = KeyBindings::default();
+    assert_eq!(bindings.action_for("ArrowDown", NONE), Some(KeyAction::SelectNext));
+    assert_eq!(bindings.action_for("ArrowUp", NONE), Some(KeyAction::SelectPrevious));
+    assert_eq!(bindings.action_for("Delete", NONE), Some(KeyAction::Kill));
+    assert_eq!(bindings.action_for("Enter", NONE), Some(KeyAction::Confirm));
+    assert_eq!(bindings.action_for("Escape", NONE), Some(KeyAction::Cancel));
+    assert_eq!(bindings.action_for("a", NONE), None);
+}
+
+#[test]
+fn modifiers_must_match_exactly() {
+    let defaults = KeyBindings::default();
+    assert_eq!(defaults.action_for("Delete", SHIFT), Some(KeyAction::ForceKill));
+    assert_eq!(defaults.action_for("Delete", CTRL_ALT), None);
+
+    let bindings = kill_bound_to("Ctrl+Alt+K");
+    assert_eq!(bindings.action_for("k", CTRL_ALT), Some(KeyAction::Kill));
+    assert_eq!(bindings.action_for("k", NONE), None);
+    assert_eq!(bindings.action_for("k", Modifiers { ctrl: true, ..NONE }), None);
+    let all = Modifiers {
+        shift: true,
+        ..CTRL_ALT
+    };
+    assert_eq!(bindings.action_for("k", all), None);
+}
+
+#[test]
+fn bindings_ignore_case_and_spacing() {
+    let bindings = kill_bound_to("control + ALT + k");
+    assert_eq!(bindings.action_for("K", CTRL_ALT), Some(KeyAction::Kill));
+    assert_eq!(bindings.action_for("k", CTRL_ALT), Some(KeyAction::Kill));
+
+    let bindings = kill_bound_to("SHIFT+delete");
+    assert_eq!(bindings.action_for("Delete", SHIFT), Some(KeyAction::Kill));
+}
+
+#[test]
+fn invalid_bindings_match_nothing() {
+    for binding in ["", "Ctrl+", "+", "Hyper+K", "Ctrl+Meta+K"] {
+        let bindings = kill_bound_to(binding);
+        for modifiers in [NONE, SHIFT, CTRL_ALT] {
+            for key in ["", "K", "Delete"] {
+                assert_ne!(
+                    bindings.action_for(key, modifiers),
+                    Some(KeyAction::Kill),
+                    "{binding:?} matched {key:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn configured_bindings_replace_the_defaults() {
+    let bindings: KeyBindings = serde_json::from_str(r#"{"kill": "Ctrl+Alt+K"}"#).unwrap();
+    assert_eq!(bindings.action_for("K", CTRL_ALT), Some(KeyAction::Kill));
+    assert_eq!(bindings.action_for("Delete", NONE), None);
+    // Actions left out of the config keep their default key
+    assert_eq!(bindings.action_for("Delete", SHIFT), Some(KeyAction::ForceKill));
+    assert_eq!(bindings.action_for("Escape", NONE), Some(KeyAction::Cancel));
+}
+
+#[test]
+fn first_matching_action_wins_when_keys_collide() {
+    let bindings = kill_bound_to("ArrowDown");
+    assert_eq!(bindings.action_for("ArrowDown", NONE), Some(KeyAction::SelectNext));
+}
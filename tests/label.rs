@@ -0,0 +1,121 @@
+// SPDX-License-Identifier: MIT
+
+//! Row labels built from the command line, and other text shown in rows.
+
+use cosmic_applet_process_killer::process::{format_rate, ProcessInfo};
+
+fn process(name: &str, cmdline: &[&str]) -> ProcessInfo {
+    ProcessInfo {
+        pid: 100,
+        name: name.to_string(),
+        cmdline: cmdline.iter().map(|arg| arg.to_string()).collect(),
+        ..Default::default()
+    }
+}
+
+fn label(name: &str, cmdline: &[&str]) -> String {
+    process(name, cmdline).label()
+}
+
+#[test]
+fn kernel_threads_keep_their_name() {
+    assert_eq!(label("kworker/0:1", &[]), "kworker/0:1");
+}
+
+#[test]
+fn programs_show_their_arguments() {
+    assert_eq!(label("firefox", &["/usr/lib/firefox/firefox"]), "firefox");
+    assert_eq!(
+        label("firefox", &["/usr/lib/firefox/firefox", "--headless"]),
+        "firefox --headless"
+    );
+}
+
+#[test]
+fn interpreters_show_the_script() {
+    assert_eq!(
+        label(
+            "python3",
+            &["/usr/bin/python3", "-u", "/srv/app/manage.py", "runserver"]
+        ),
+        "python3 manage.py runserver"
+    );
+    assert_eq!(
+        label(
+            "node",
+            &[
+                "node",
+                "--max-old-space-size=4096",
+                "-r",
+                "ts-node/register",
+                "node_modules/.bin/webpack",
+                "serve"
+            ]
+        ),
+        "node webpack serve"
+    );
+    assert_eq!(
+        label("python3.12", &["python3.12", "-m", "http.server", "8000"]),
+        "python3.12 http.server 8000"
+    );
+}
+
+#[test]
+fn java_shows_the_jar_or_main_class() {
+    assert_eq!(
+        label(
+            "java",
+            &[
+                "/usr/bin/java",
+                "-Xmx2g",
+                "-jar",
+                "/opt/minecraft/server.jar",
+                "nogui"
+            ]
+        ),
+        "java server.jar nogui"
+    );
+    assert_eq!(
+        label(
+            "java",
+            &["java", "-cp", "lib/*:app.jar", "com.example.Main"]
+        ),
+        "java com.example.Main"
+    );
+}
+
+#[test]
+fn truncated_names_are_completed_from_the_program() {
+    // The kernel keeps 15 characters of the name
+    assert_eq!(
+        label(
+            "gnome-shell-cal",
+            &["/usr/libexec/gnome-shell-calendar-server"]
+        ),
+        "gnome-shell-calendar-server"
+    );
+}
+
+#[test]
+fn search_matches_command_line_and_executable() {
+    let webpack = ProcessInfo {
+        exe: Some("/usr/lib/node/bin/node".into()),
+        ..process(
+            "node",
+            &["node", "/home/user/app/node_modules/.bin/webpack", "serve"],
+        )
+    };
+
+    assert!(webpack.matches_search("webpack"));
+    assert!(webpack.matches_search("webpack serve"));
+    assert!(webpack.matches_search("/usr/lib/node"));
+    assert!(!webpack.matches_search("vite"));
+}
+
+#[test]
+fn disk_rates_are_readable() {
+    assert_eq!(format_rate(0), "0 B/s");
+    assert_eq!(format_rate(12 * 1024), "12 KB/s");
+    assert_eq!(format_rate(3 * 1024 * 1024 + 400 * 1024), "3.4 MB/s");
+    assert_eq!(format_rate(250 * 1024 * 1024), "250 MB/s");
+}
@@ -0,0 +1,43 @@
+// SPDX-License-Identifier: MIT
+
+//! Parsing of `smaps_rollup`.
+
+use cosmic_applet_process_killer::memory::{parse_smaps_rollup, MemoryBreakdown, MemoryMetric};
+
+const ROLLUP: &str = "\
+7f0000000000-7ffff0000000 ---p 00000000 00:00 0                          [rollup]
+Rss:                1000 kB
+Pss:                 600 kB
+Shared_Clean:        300 kB
+Shared_Dirty:        100 kB
+Private_Clean:       200 kB
+Private_Dirty:       400 kB
+Swap:                 50 kB
+SwapPss:              50 kB
+";
+
+#[test]
+fn counters_are_summed_into_the_breakdown() {
+    let breakdown = parse_smaps_rollup(ROLLUP).unwrap();
+    assert_eq!(
+        breakdown,
+        MemoryBreakdown {
+            rss: 1000 * 1024,
+            pss: 600 * 1024,
+            private: 600 * 1024,
+            shared: 400 * 1024,
+            swap: 50 * 1024,
+        }
+    );
+
+    assert_eq!(breakdown.get(MemoryMetric::Rss), 1000 * 1024);
+    assert_eq!(breakdown.get(MemoryMetric::Pss), 600 * 1024);
+    assert_eq!(breakdown.get(MemoryMetric::Uss), 600 * 1024);
+}
+
+#[test]
+fn files_without_counters_are_rejected() {
+    // Kernel threads have no mappings, so nothing but the header
+    assert_eq!(parse_smaps_rollup(""), None);
+    assert_eq!(parse_smaps_rollup("Rss: lots\n"), None);
+}
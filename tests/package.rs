@@ -0,0 +1,105 @@
+// SPDX-License-Identifier: MIT
+
+//! Flatpaks, Snaps and AppImages, from the captured tree in
+//! `tests/fixtures/proc` and the Flatpak instances in `tests/fixtures/flatpak`.
+
+use cosmic_applet_process_killer::backend::ProcessBackend;
+use cosmic_applet_process_killer::package::{
+    detect, parse_flatpak_info, parse_snap_label, Package, PackageKind,
+};
+use cosmic_applet_process_killer::process::ProcessInfo;
+use cosmic_applet_process_killer::procfs::ProcfsBackend;
+use std::path::{Path, PathBuf};
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+fn package_of(processes: &[ProcessInfo], pid: u32) -> Option<Package> {
+    processes.iter().find(|p| p.pid == pid)?.package.clone()
+}
+
+#[test]
+fn flatpak_info_names_the_app_and_instance() {
+    let info = "[Application]\nname=org.gnome.Calculator\nruntime=runtime/org.gnome.Platform\n\n\
+        [Instance]\ninstance-id=2840134927\nbranch=stable\n";
+    assert_eq!(
+        parse_flatpak_info(info),
+        Some(("org.gnome.Calculator".to_string(), "2840134927".to_string()))
+    );
+    // The runtime's name is not the app's
+    assert_eq!(
+        parse_flatpak_info("[Runtime]\nname=org.gnome.Platform\n"),
+        None
+    );
+}
+
+#[test]
+fn snap_labels_name_the_snap() {
+    assert_eq!(
+        parse_snap_label("snap.firefox.firefox (enforce)\n"),
+        Some("firefox")
+    );
+    assert_eq!(
+        parse_snap_label("snap.firefox.hook.configure (complain)"),
+        Some("firefox")
+    );
+    assert_eq!(parse_snap_label("unconfined"), None);
+    assert_eq!(parse_snap_label("/usr/bin/man (enforce)"), None);
+}
+
+#[test]
+fn packaged_processes_are_identified() {
+    let processes = ProcfsBackend::with_root(fixture("proc"))
+        .with_flatpak_dir(fixture("flatpak"))
+        .sample();
+
+    let calculator = Package {
+        kind: PackageKind::Flatpak,
+        app_id: "org.gnome.Calculator".to_string(),
+        instance: "2840134927".to_string(),
+        icon: Some("org.gnome.Calculator".to_string()),
+    };
+    // Inside the sandbox, and the bwrap process setting it up
+    assert_eq!(package_of(&processes, 1500), Some(calculator.clone()));
+    assert_eq!(package_of(&processes, 1499), Some(calculator));
+
+    let firefox = package_of(&processes, 1600).unwrap();
+    assert_eq!(firefox.kind, PackageKind::Snap);
+    assert_eq!(firefox.app_id, "firefox");
+    assert_eq!(firefox.instance, "snap.firefox.firefox-2f4c9d.scope");
+
+    let krita = package_of(&processes, 1700).unwrap();
+    assert_eq!(krita.kind, PackageKind::AppImage);
+    assert_eq!(krita.app_id, "krita-5.2.6-x86_64");
+    assert_eq!(krita.instance, "/tmp/.mount_KritaXyZ12");
+
+    assert_eq!(package_of(&processes, 1200), None);
+}
+
+#[test]
+fn appimage_mount_names_are_not_split_inside_characters() {
+    let dir = fixture("proc/nonexistent");
+    let package = |exe: &str| detect(&dir, Some(Path::new(exe)), None).unwrap();
+
+    assert_eq!(package("/tmp/.mount_KritäAb12Cd/AppRun").app_id, "Kritä");
+    // The last six bytes end inside a character
+    assert_eq!(package("/tmp/.mount_Krita本語x/AppRun").app_id, "Krita本語x");
+}
+
+#[test]
+fn searches_match_the_app_id() {
+    let process = ProcessInfo {
+        name: "gnome-calculato".to_string(),
+        package: Some(Package {
+            kind: PackageKind::Flatpak,
+            app_id: "org.gnome.Calculator".to_string(),
+            instance: "1".to_string(),
+            icon: None,
+        }),
+        ..Default::default()
+    };
+    assert!(process.matches_search("org.gnome"));
+}
@@ -0,0 +1,139 @@
+// SPDX-License-Identifier: MIT
+
+//! Nice and I/O priority changes on this test process, which runs alone in
+//! its own binary so lowering its priority does not slow other tests down.
+
+use cosmic_applet_process_killer::affinity;
+use cosmic_applet_process_killer::priority::{self, IoClass, IoPriority};
+use cosmic_applet_process_killer::process::ProcessError;
+use nix::errno::Errno;
+use nix::unistd::Uid;
+use std::cell::RefCell;
+use std::fs;
+use std::sync::mpsc;
+
+/// Nice value of one thread, from its stat file
+fn thread_nice(tid: u32) -> i32 {
+    let stat = fs::read_to_string(format!("/proc/self/task/{tid}/stat")).unwrap();
+    let fields: Vec<&str> = stat[stat.rfind(')').unwrap() + 1..]
+        .split_whitespace()
+        .collect();
+    fields[19 - 3].parse().unwrap()
+}
+
+#[test]
+fn nice_applies_to_every_thread() {
+    let pid = std::process::id();
+    let (tid_sender, tid_receiver) = mpsc::channel();
+    let (done_sender, done_receiver) = mpsc::channel::<()>();
+    let worker = std::thread::spawn(move || {
+        tid_sender
+            .send(nix::unistd::gettid().as_raw() as u32)
+            .unwrap();
+        done_receiver.recv().unwrap();
+    });
+    let tid = tid_receiver.recv().unwrap();
+
+    let nice = (priority::nice(pid).unwrap() + 1).min(19);
+    priority::set_nice(pid, nice).unwrap();
+
+    assert_eq!(priority::nice(pid).unwrap(), nice);
+    assert_eq!(thread_nice(tid), nice);
+
+    done_sender.send(()).unwrap();
+    worker.join().unwrap();
+}
+
+#[test]
+fn io_priority_round_trips() {
+    let pid = std::process::id();
+    let nice = priority::nice(pid).unwrap();
+
+    for wanted in [
+        IoPriority {
+            class: IoClass::BestEffort,
+            level: 6,
+        },
+        IoPriority {
+            class: IoClass::Idle,
+            level: 0,
+        },
+    ] {
+        priority::set_io_priority(pid, wanted).unwrap();
+        assert_eq!(priority::io_priority(pid, nice).unwrap(), wanted);
+    }
+}
+
+#[test]
+fn default_io_priority_follows_nice() {
+    assert_eq!(IoPriority::from_nice(0).level, 4);
+    assert_eq!(IoPriority::from_nice(-20).level, 0);
+    assert_eq!(IoPriority::from_nice(19).level, 7);
+    assert_eq!(IoPriority::from_nice(5).class, IoClass::BestEffort);
+}
+
+#[test]
+fn errors_are_mapped() {
+    // Above the largest possible PID
+    let missing = i32::MAX as u32;
+    assert_eq!(priority::nice(missing), Err(ProcessError::NotFound));
+    assert_eq!(priority::set_nice(missing, 5), Err(ProcessError::NotFound));
+    // 0 would be this very process
+    assert_eq!(priority::nice(0), Err(ProcessError::NotFound));
+    assert_eq!(priority::set_nice(0, 5), Err(ProcessError::NotFound));
+    assert_eq!(priority::io_priority(0, 0), Err(ProcessError::NotFound));
+    let mask = affinity::affinity(std::process::id()).unwrap();
+    assert_eq!(
+        affinity::set_affinity(0, &mask, false),
+        Err(ProcessError::NotFound)
+    );
+
+    if !Uid::effective().is_root() {
+        assert_eq!(
+            priority::set_nice(std::process::id(), -20),
+            Err(ProcessError::PermissionDenied)
+        );
+    }
+}
+
+#[test]
+fn every_thread_is_tried_and_failures_reported_together() {
+    let tried = RefCell::new(Vec::new());
+    let apply = |failing: &'static [(u32, Errno)]| {
+        let tried = &tried;
+        move |tid: u32| {
+            tried.borrow_mut().push(tid);
+            failing
+                .iter()
+                .find(|(failed, _)| *failed == tid)
+                .map_or(Ok(()), |(_, errno)| Err(*errno))
+        }
+    };
+
+    // Thread 12 exited meanwhile, 11 could not be changed
+    let result = priority::apply_to_threads(
+        10,
+        [10, 11, 12, 13],
+        apply(&[(11, Errno::EPERM), (12, Errno::ESRCH)]),
+    );
+    assert_eq!(*tried.borrow(), [10, 11, 12, 13]);
+    assert_eq!(
+        result,
+        Err(ProcessError::Unknown(
+            "1 of 4 threads failed: Operation not permitted (11)".to_string()
+        ))
+    );
+
+    // All of them the same way
+    let result =
+        priority::apply_to_threads(10, [11], apply(&[(10, Errno::EPERM), (11, Errno::EPERM)]));
+    assert_eq!(result, Err(ProcessError::PermissionDenied));
+
+    // No main thread, no process
+    tried.borrow_mut().clear();
+    let result = priority::apply_to_threads(10, [11], apply(&[(10, Errno::ESRCH)]));
+    assert_eq!(result, Err(ProcessError::NotFound));
+    assert_eq!(*tried.borrow(), [10]);
+
+    assert_eq!(priority::apply_to_threads(10, [10, 11], apply(&[])), Ok(()));
+}
@@ -0,0 +1,349 @@
+// SPDX-License-Identifier: MIT
+
+//! Runs the procfs backend against the captured tree in `tests/fixtures/proc`.
+
+use cosmic_applet_process_killer::backend::ProcessBackend;
+use cosmic_applet_process_killer::memory::{MemoryBreakdown, MemoryMetric};
+use cosmic_applet_process_killer::process::{sort_processes, ProcessInfo, ProcessManager, SortBy};
+use cosmic_applet_process_killer::procfs::ProcfsBackend;
+use cosmic_applet_process_killer::systemd::{Unit, UnitScope};
+use nix::unistd::{sysconf, SysconfVar};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+fn fixture_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/proc")
+}
+
+fn sysconf_value(var: SysconfVar) -> u64 {
+    sysconf(var).unwrap().unwrap() as u64
+}
+
+/// Copy the fixture so a test can change it between samples.
+fn copy_fixture(test: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("procfs-{test}-{}", std::process::id()));
+    _ = fs::remove_dir_all(&root);
+    copy_dir(&fixture_root(), &root);
+    root
+}
+
+fn copy_dir(from: &Path, to: &Path) {
+    fs::create_dir_all(to).unwrap();
+    for entry in fs::read_dir(from).unwrap() {
+        let entry = entry.unwrap();
+        let target = to.join(entry.file_name());
+        let file_type = entry.file_type().unwrap();
+        if file_type.is_dir() {
+            copy_dir(&entry.path(), &target);
+        } else if file_type.is_symlink() {
+            std::os::unix::fs::symlink(fs::read_link(entry.path()).unwrap(), target).unwrap();
+        } else {
+            fs::copy(entry.path(), target).unwrap();
+        }
+    }
+}
+
+fn by_pid(processes: &[ProcessInfo], pid: u32) -> &ProcessInfo {
+    processes.iter().find(|p| p.pid == pid).unwrap()
+}
+
+#[test]
+fn lists_every_readable_process() {
+    let mut processes = ProcfsBackend::with_root(fixture_root()).sample();
+    processes.sort_by_key(|p| p.pid);
+
+    // 1400 has a truncated stat file; meminfo, net and stat are not processes
+    let pids: Vec<u32> = processes.iter().map(|p| p.pid).collect();
+    assert_eq!(pids, vec![1, 2, 1200, 1300, 1499, 1500, 1600, 1700, 1800, 1900]);
+}
+
+#[test]
+fn reads_the_stat_fields() {
+    let processes = ProcfsBackend::with_root(fixture_root()).sample();
+    let ticks = sysconf_value(SysconfVar::CLK_TCK);
+    let page_size = sysconf_value(SysconfVar::PAGE_SIZE);
+
+    let tricky = by_pid(&processes, 1200);
+    assert_eq!(tricky.name, "tricky) (name");
+    assert_eq!(tricky.status, "Run");
+    assert_eq!(tricky.parent_pid, Some(1));
+    assert_eq!(tricky.memory, Some(25600 * page_size));
+    assert_eq!(tricky.start_time, 1_700_000_000 + 52000 / ticks);
+    assert_eq!(tricky.nice, 10);
+    assert!(!tricky.is_system);
+    // Not the procfs of this system, so the kernel is not asked
+    assert_eq!(tricky.io_priority, None);
+    // smaps_rollup is only read for other metrics
+    assert_eq!(tricky.memory_breakdown, None);
+
+    let systemd = by_pid(&processes, 1);
+    assert_eq!(systemd.parent_pid, None);
+    assert!(systemd.is_system);
+
+    assert_eq!(by_pid(&processes, 1300).status, "Zombie");
+}
+
+#[test]
+fn reads_owner_command_line_and_executable() {
+    let processes = ProcfsBackend::with_root(fixture_root()).sample();
+
+    let tricky = by_pid(&processes, 1200);
+    assert_eq!(tricky.uid, Some(1000));
+    assert_eq!(
+        tricky.cmdline,
+        vec!["/usr/bin/python3", "/home/user/bin/tricky.py", "--serve"]
+    );
+    // Joined once for searches
+    assert_eq!(
+        tricky.search_cmdline,
+        "/usr/bin/python3 /home/user/bin/tricky.py --serve"
+    );
+    assert_eq!(tricky.exe.as_deref(), Some(Path::new("/usr/bin/python3.12")));
+    assert_eq!(
+        tricky.unit,
+        Some(Unit {
+            name: "tricky.service".to_string(),
+            scope: UnitScope::User,
+            uid: Some(1000),
+        })
+    );
+
+    // Kernel threads have no command line, executable or unit
+    let kthreadd = by_pid(&processes, 2);
+    assert_eq!(kthreadd.uid, Some(0));
+    assert!(kthreadd.cmdline.is_empty());
+    assert_eq!(kthreadd.exe, None);
+    assert_eq!(kthreadd.unit, None);
+}
+
+#[test]
+fn cpu_usage_is_measured_between_samples() {
+    let root = copy_fixture("cpu");
+    let mut backend = ProcfsBackend::with_root(&root);
+
+    let first = backend.sample();
+    assert!(first.iter().all(|p| p.cpu_usage == 0.0));
+
+    std::thread::sleep(Duration::from_millis(50));
+    let stat = fs::read_to_string(root.join("1200/stat")).unwrap();
+    fs::write(root.join("1200/stat"), stat.replace(" 4000 1000 ", " 4003 1002 ")).unwrap();
+
+    let second = backend.sample();
+    assert!(by_pid(&second, 1200).cpu_usage > 0.0);
+    assert_eq!(by_pid(&second, 1).cpu_usage, 0.0);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn static_data_is_cached_until_the_pid_is_reused() {
+    let root = copy_fixture("reuse");
+    let mut backend = ProcfsBackend::with_root(&root);
+    backend.sample();
+
+    // Still the same process: the cached command line is kept
+    fs::write(root.join("1200/cmdline"), b"/usr/bin/other\0").unwrap();
+    let cached = backend.sample();
+    assert_eq!(by_pid(&cached, 1200).cmdline[0], "/usr/bin/python3");
+
+    // A new start time means a new process behind the same PID
+    let stat = fs::read_to_string(root.join("1200/stat")).unwrap();
+    fs::write(root.join("1200/stat"), stat.replace(" 52000 ", " 99000 ")).unwrap();
+    let reused = backend.sample();
+    assert_eq!(by_pid(&reused, 1200).cmdline, vec!["/usr/bin/other"]);
+    assert_eq!(by_pid(&reused, 1200).cpu_usage, 0.0);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn moving_a_process_to_another_cgroup_changes_its_unit() {
+    let root = copy_fixture("moved");
+    let mut backend = ProcfsBackend::with_root(&root);
+    backend.sample();
+
+    fs::write(
+        root.join("1200/cgroup"),
+        "0::/user.slice/user-1000.slice/user@1000.service/app.slice/other.scope\n",
+    )
+    .unwrap();
+    let processes = backend.sample();
+    let unit = by_pid(&processes, 1200).unit.as_ref().unwrap();
+    assert_eq!(unit.name, "other.scope");
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn an_exec_is_the_same_process_running_another_program() {
+    let root = copy_fixture("exec");
+    let mut backend = ProcfsBackend::with_root(&root);
+    backend.sample();
+
+    std::thread::sleep(Duration::from_millis(50));
+    fs::write(root.join("1200/cmdline"), b"/usr/bin/other\0").unwrap();
+    let stat = fs::read_to_string(root.join("1200/stat")).unwrap();
+    let stat = stat.replace("(tricky) (name)", "(other)");
+    fs::write(root.join("1200/stat"), stat.replace(" 4000 1000 ", " 4003 1002 ")).unwrap();
+
+    // Read again under the new name, still measured against the last sample
+    let processes = backend.sample();
+    let other = by_pid(&processes, 1200);
+    assert_eq!(other.name, "other");
+    assert_eq!(other.cmdline, vec!["/usr/bin/other"]);
+    assert!(other.cpu_usage > 0.0);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn single_processes_are_read_without_moving_the_baseline() {
+    let root = copy_fixture("single");
+    let mut backend = ProcfsBackend::with_root(&root);
+    backend.sample();
+
+    std::thread::sleep(Duration::from_millis(50));
+    let stat = fs::read_to_string(root.join("1200/stat")).unwrap();
+    fs::write(root.join("1200/stat"), stat.replace(" 4000 1000 ", " 4003 1002 ")).unwrap();
+    let process = backend.process(1200).unwrap();
+    assert_eq!(process.name, "tricky) (name");
+    assert!(process.cpu_usage > 0.0);
+    assert_eq!(backend.process(1_000_000), None);
+
+    // The next sample still counts the ticks used since the previous one
+    let processes = backend.sample();
+    assert!(by_pid(&processes, 1200).cpu_usage > 0.0);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn exited_processes_disappear() {
+    let root = copy_fixture("exit");
+    let mut backend = ProcfsBackend::with_root(&root);
+    backend.sample();
+
+    fs::remove_dir_all(root.join("1300")).unwrap();
+    let processes = backend.sample();
+    assert!(processes.iter().all(|p| p.pid != 1300));
+    assert_eq!(processes.len(), 9);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn missing_root_yields_no_processes() {
+    let mut backend = ProcfsBackend::with_root("/nonexistent/proc");
+    assert!(backend.sample().is_empty());
+}
+
+#[test]
+fn memory_is_reported_by_the_chosen_metric() {
+    const MIB: u64 = 1024 * 1024;
+    let mut backend = ProcfsBackend::with_root(fixture_root());
+    backend.set_memory_metric(MemoryMetric::Uss);
+    let processes = backend.sample();
+
+    let tricky = by_pid(&processes, 1200);
+    assert_eq!(tricky.memory, Some(16 * MIB));
+    assert_eq!(
+        tricky.memory_breakdown,
+        Some(MemoryBreakdown {
+            rss: 40 * MIB,
+            pss: 18 * MIB,
+            private: 16 * MIB,
+            shared: 24 * MIB,
+            swap: 2 * MIB,
+        })
+    );
+    // Without smaps_rollup there is no USS, rather than RSS in its place
+    let systemd = by_pid(&processes, 1);
+    assert_eq!(systemd.memory_breakdown, None);
+    assert_eq!(systemd.memory, None);
+
+    // and it sorts below every process that has one
+    let mut sorted = processes.clone();
+    sort_processes(&mut sorted, SortBy::Memory);
+    assert_eq!(sorted[0].pid, 1200);
+    assert_eq!(sorted.last().unwrap().memory, None);
+
+    assert_eq!(backend.memory_breakdown(1200).unwrap().pss, 18 * MIB);
+}
+
+#[test]
+fn disk_io_is_measured_between_samples() {
+    let root = copy_fixture("io");
+    let mut backend = ProcfsBackend::with_root(&root);
+
+    // Not read unless asked for
+    backend.sample();
+    std::thread::sleep(Duration::from_millis(50));
+    assert_eq!(by_pid(&backend.sample(), 1200).disk_read, 0);
+
+    backend.set_disk_io(true);
+    backend.sample();
+    std::thread::sleep(Duration::from_millis(50));
+    let io = fs::read_to_string(root.join("1200/io")).unwrap();
+    fs::write(
+        root.join("1200/io"),
+        io.replace("read_bytes: 4194304", "read_bytes: 5242880"),
+    )
+    .unwrap();
+
+    let second = backend.sample();
+    let tricky = by_pid(&second, 1200);
+    // 1 MiB in at least 50 ms
+    assert!(tricky.disk_read > 0 && tricky.disk_read <= 20 * 1024 * 1024);
+    assert_eq!(tricky.disk_write, 0);
+    // No io file
+    assert_eq!(by_pid(&second, 1).disk_read, 0);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn disk_io_is_measured_from_the_first_sample_after_enabling_it() {
+    let root = copy_fixture("io-manager");
+    let mut manager = ProcessManager::with_backend(Box::new(ProcfsBackend::with_root(&root)));
+
+    manager.set_disk_io(true);
+    std::thread::sleep(Duration::from_millis(50));
+    let io = fs::read_to_string(root.join("1200/io")).unwrap();
+    fs::write(
+        root.join("1200/io"),
+        io.replace("read_bytes: 4194304", "read_bytes: 5242880"),
+    )
+    .unwrap();
+
+    assert!(by_pid(&manager.sample(), 1200).disk_read > 0);
+
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[test]
+fn threads_are_read_from_the_task_directory() {
+    let root = copy_fixture("threads");
+    let mut backend = ProcfsBackend::with_root(&root);
+
+    let mut first = backend.threads(1200);
+    first.sort_by_key(|thread| thread.tid);
+    let names: Vec<_> = first.iter().map(|t| (t.tid, t.name.as_str())).collect();
+    assert_eq!(names, [(1200, "tricky) (name"), (1201, "worker")]);
+    assert_eq!(first[1].status, "Sleep");
+    assert!(first.iter().all(|t| t.cpu_usage == 0.0 && t.nice == 10));
+
+    std::thread::sleep(Duration::from_millis(50));
+    let stat = root.join("1200/task/1201/stat");
+    let content = fs::read_to_string(&stat).unwrap();
+    fs::write(&stat, content.replace(" 300 100 ", " 90000 100 ")).unwrap();
+
+    let second = backend.threads(1200);
+    let worker = second.iter().find(|t| t.tid == 1201).unwrap();
+    // Capped to one core
+    assert_eq!(worker.cpu_usage, 100.0);
+    assert_eq!(second.iter().find(|t| t.tid == 1200).unwrap().cpu_usage, 0.0);
+
+    assert!(backend.threads(2).is_empty());
+    fs::remove_dir_all(root).unwrap();
+}
@@ -0,0 +1,189 @@
+// SPDX-License-Identifier: MIT
+
+//! Parsing and matching of search queries.
+
+use cosmic_applet_process_killer::process::ProcessInfo;
+use cosmic_applet_process_killer::query::{Query, QueryError};
+use nix::unistd::Uid;
+use std::path::PathBuf;
+
+const MB: u64 = 1024 * 1024;
+
+fn process(pid: u32, name: &str, cpu_usage: f32, memory: u64) -> ProcessInfo {
+    ProcessInfo {
+        pid,
+        name: name.to_string(),
+        cpu_usage,
+        memory: Some(memory),
+        status: "Sleep".to_string(),
+        uid: Some(1000),
+        ..Default::default()
+    }
+}
+
+fn table() -> Vec<ProcessInfo> {
+    vec![
+        ProcessInfo {
+            cmdline: vec!["/usr/lib/firefox/firefox".into(), "--headless".into()],
+            ..process(400, "firefox", 62.0, 2048 * MB)
+        },
+        process(401, "Firefox-bin", 3.0, 300 * MB),
+        ProcessInfo {
+            uid: Some(0),
+            ..process(1, "systemd", 0.1, 12 * MB)
+        },
+        ProcessInfo {
+            status: "Zombie".to_string(),
+            ..process(1300, "defunct", 0.0, 0)
+        },
+    ]
+}
+
+fn matching(query: &str) -> Vec<u32> {
+    let query = Query::parse(query).unwrap();
+    table()
+        .iter()
+        .filter(|p| query.matches(p))
+        .map(|p| p.pid)
+        .collect()
+}
+
+#[test]
+fn empty_query_matches_everything() {
+    assert!(Query::parse("  ").unwrap().is_empty());
+    assert_eq!(matching(""), [400, 401, 1, 1300]);
+}
+
+#[test]
+fn plain_words_match_name_or_pid() {
+    assert_eq!(matching("FIRE"), [400, 401]);
+    assert_eq!(matching("1300"), [1300]);
+    // Every term has to match
+    assert_eq!(matching("fire bin"), [401]);
+}
+
+#[test]
+fn numeric_comparisons() {
+    assert_eq!(matching("cpu>50"), [400]);
+    assert_eq!(matching("cpu<=0.1"), [1, 1300]);
+    assert_eq!(matching("cpu>=3%"), [400, 401]);
+    assert_eq!(matching("mem>1G"), [400]);
+    assert_eq!(matching("mem<300M mem>0"), [1]);
+    assert_eq!(matching("mem:300"), [401]);
+    assert_eq!(matching("pid>1000"), [1300]);
+    assert_eq!(matching("pid=1"), [1]);
+}
+
+#[test]
+fn port_filter_uses_sampled_ports() {
+    let query = Query::parse("port:8080").unwrap();
+    assert!(query.needs_ports());
+    assert!(!Query::parse("cpu>5").unwrap().needs_ports());
+
+    let server = ProcessInfo {
+        ports: vec![53, 8080],
+        ..process(500, "server", 0.0, 0)
+    };
+    assert!(query.matches(&server));
+    assert!(!query.matches(&table()[0]));
+    assert_eq!(
+        Query::parse("port>80").unwrap_err(),
+        QueryError::NotComparable("port".into())
+    );
+}
+
+#[test]
+fn file_filter_uses_sampled_paths() {
+    let query = Query::parse("file:/mnt/usb/").unwrap();
+    assert_eq!(query.held_paths(), [PathBuf::from("/mnt/usb")]);
+    assert!(Query::parse("cpu>5").unwrap().held_paths().is_empty());
+
+    let holder = ProcessInfo {
+        held_paths: vec![PathBuf::from("/mnt/usb")],
+        ..process(500, "rsync", 0.0, 0)
+    };
+    assert!(query.matches(&holder));
+    assert!(!query.matches(&process(501, "bash", 0.0, 0)));
+
+    assert_eq!(
+        Query::parse(r#"file:"/media/My Disk""#).unwrap().held_paths(),
+        [PathBuf::from("/media/My Disk")]
+    );
+    assert_eq!(
+        Query::parse("file:usb").unwrap_err(),
+        QueryError::InvalidPath("usb".into())
+    );
+}
+
+#[test]
+fn owner_filters() {
+    assert_eq!(matching("user:0"), [1]);
+    assert_eq!(matching("user:root"), [1]);
+
+    let me = Query::parse("user:me").unwrap();
+    let mine = ProcessInfo {
+        uid: Some(Uid::current().as_raw()),
+        ..Default::default()
+    };
+    assert!(me.matches(&mine));
+}
+
+#[test]
+fn text_and_regex_patterns() {
+    assert_eq!(matching("name:/^fire.*/"), [400, 401]);
+    assert_eq!(matching("name:/^firefox$/"), [400]);
+    assert_eq!(matching("cmd:--headless"), [400]);
+    assert_eq!(matching("cmd:/LIB/"), [400]);
+    assert_eq!(matching("state:zombie"), [1300]);
+    assert_eq!(matching("state:S"), [400, 401, 1]);
+}
+
+#[test]
+fn quoted_values_keep_their_spaces() {
+    let query = Query::parse(r#"cmd:"firefox --headless""#).unwrap();
+    assert!(query.matches(&table()[0]));
+
+    // A quoted word is plain text, even if it looks like a filter
+    let literal = Query::parse(r#""cpu>50""#).unwrap();
+    assert!(!literal.matches(&table()[0]));
+}
+
+#[test]
+fn invalid_queries_are_explained() {
+    let error = |query: &str| Query::parse(query).unwrap_err();
+
+    assert_eq!(error("color:red"), QueryError::UnknownField("color".into()));
+    assert_eq!(error("cpu>"), QueryError::MissingValue("cpu".into()));
+    assert_eq!(error("name>a"), QueryError::NotComparable("name".into()));
+    assert_eq!(error("cpu>lots"), QueryError::InvalidNumber("lots".into()));
+    assert_eq!(error("mem>1Q"), QueryError::InvalidSize("1Q".into()));
+    assert_eq!(
+        error("state:asleep"),
+        QueryError::UnknownState("asleep".into())
+    );
+    assert_eq!(error(r#"cmd:"open"#), QueryError::UnclosedQuote);
+    assert!(matches!(error("name:/(/"), QueryError::InvalidRegex(pattern, _) if pattern == "("));
+    assert!(matches!(
+        error("user:no-such-user-here"),
+        QueryError::UnknownUser(_)
+    ));
+}
+
+#[test]
+fn words_with_symbols_are_plain_text() {
+    // Not a field name, so not a filter
+    assert!(Query::parse("c++:x").is_ok());
+    assert!(Query::parse("a/b:c").is_ok());
+}
+
+#[test]
+fn highlights_cover_the_searched_text() {
+    let text = "python3 Manage.py runserver";
+    let highlights = |query: &str| Query::parse(query).unwrap().highlights(text);
+
+    assert_eq!(highlights("manage"), vec![8..14]);
+    assert_eq!(highlights("cmd:/run\\w+/ py"), [0..2, 15..17, 18..27]);
+    // Filters on numbers do not highlight anything
+    assert!(highlights("cpu>5").is_empty());
+    assert!(highlights("").is_empty());
+}
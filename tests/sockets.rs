@@ -0,0 +1,71 @@
+// SPDX-License-Identifier: MIT
+
+//! Sockets from `/proc/net`, matched to processes through their descriptors.
+
+use cosmic_applet_process_killer::backend::ProcessBackend;
+use cosmic_applet_process_killer::process::ProcessInfo;
+use cosmic_applet_process_killer::procfs::ProcfsBackend;
+use cosmic_applet_process_killer::sockets::{
+    parse_inet_table, process_sockets, socket_inodes, Protocol, SocketInfo,
+};
+use std::path::{Path, PathBuf};
+
+fn fixture_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/proc")
+}
+
+#[test]
+fn inet_tables_are_decoded() {
+    let table = "  sl  local_address rem_address   st\n   \
+        0: 0100007F:C350 0100007F:0050 01 00000000:00000000 00:00000000 00000000  1000 0 5002\n";
+    let sockets: Vec<SocketInfo> = parse_inet_table(table, Protocol::Tcp).collect();
+
+    assert_eq!(
+        sockets,
+        [SocketInfo {
+            inode: 5002,
+            protocol: Protocol::Tcp,
+            local: "127.0.0.1:50000".to_string(),
+            remote: Some("127.0.0.1:80".to_string()),
+            state: Some("ESTABLISHED".to_string()),
+            port: Some(50000),
+        }]
+    );
+}
+
+#[test]
+fn sockets_are_matched_through_descriptors() {
+    let root = fixture_root();
+    let mut inodes = socket_inodes(&root, 1200);
+    inodes.sort();
+    // Pipes and files are not sockets
+    assert_eq!(inodes, [5001, 5003, 5004]);
+
+    let sockets = process_sockets(&root, 1200);
+    let summary: Vec<_> = sockets
+        .iter()
+        .map(|s| (s.protocol, s.local.as_str(), s.state.as_deref()))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (Protocol::Tcp, "127.0.0.1:8080", Some("LISTEN")),
+            (Protocol::Udp, "[::1]:53", None),
+            (Protocol::Unix, "/run/user/1000/bus", Some("LISTEN")),
+        ]
+    );
+
+    // No descriptors to read
+    assert!(process_sockets(&root, 1).is_empty());
+}
+
+#[test]
+fn ports_are_only_sampled_on_demand() {
+    let mut backend = ProcfsBackend::with_root(fixture_root());
+    let ports_of_1200 =
+        |processes: Vec<ProcessInfo>| processes.into_iter().find(|p| p.pid == 1200).unwrap().ports;
+
+    assert!(ports_of_1200(backend.sample()).is_empty());
+    backend.set_ports(true);
+    assert_eq!(ports_of_1200(backend.sample()), [53, 8080]);
+}
@@ -0,0 +1,41 @@
+// SPDX-License-Identifier: MIT
+
+//! Startup flags handed to an already running window on a second launch.
+
+use cosmic_applet_process_killer::process::SortBy;
+use cosmic_applet_process_killer::standalone::Flags;
+
+fn round_trip(flags: &Flags) -> Flags {
+    serde_json::from_str(&flags.to_string()).unwrap()
+}
+
+#[test]
+fn flags_survive_activation() {
+    let flags = Flags {
+        search: Some("name:\"fire fox\" cpu>50".to_string()),
+        pid: Some(42),
+        sort_by: Some(SortBy::DiskWrite),
+        show_all: true,
+        tree: true,
+    };
+    assert_eq!(round_trip(&flags), flags);
+
+    let flags = Flags {
+        pid: Some(7),
+        ..Default::default()
+    };
+    assert_eq!(round_trip(&flags), flags);
+    assert_eq!(round_trip(&Flags::default()), Flags::default());
+}
+
+#[test]
+fn unset_flags_are_left_out() {
+    assert_eq!(Flags::default().to_string(), "{}");
+    let flags = Flags {
+        search: Some("fire".to_string()),
+        sort_by: Some(SortBy::Memory),
+        tree: true,
+        ..Default::default()
+    };
+    assert_eq!(flags.to_string(), r#"{"search":"fire","sort_by":"memory","tree":true}"#);
+}
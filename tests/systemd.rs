@@ -0,0 +1,283 @@
+// SPDX-License-Identifier: MIT
+
+//! systemd units from cgroups, and unit actions against a stand-in manager
+//! on a private peer-to-peer bus.
+
+use cosmic_applet_process_killer::cgroup::Limits;
+use cosmic_applet_process_killer::process::ProcessError;
+use cosmic_applet_process_killer::systemd::{
+    parse_cgroup, run_action, set_unit_limits, start_limited_scope, unit_cgroup, unit_of, Unit,
+    UnitAction, UnitScope,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::net::UnixStream;
+use zbus::zvariant::{OwnedObjectPath, OwnedValue};
+
+fn unit(name: &str, scope: UnitScope) -> Option<Unit> {
+    Some(Unit {
+        name: name.to_string(),
+        scope,
+        uid: None,
+    })
+}
+
+fn user_unit(name: &str, uid: u32) -> Option<Unit> {
+    Some(Unit {
+        name: name.to_string(),
+        scope: UnitScope::User,
+        uid: Some(uid),
+    })
+}
+
+#[test]
+fn cgroups_name_the_innermost_unit() {
+    assert_eq!(
+        unit_of("/system.slice/nginx.service"),
+        unit("nginx.service", UnitScope::System)
+    );
+    assert_eq!(
+        unit_of("/user.slice/user-1000.slice/user@1000.service/app.slice/app-firefox-1234.scope"),
+        user_unit("app-firefox-1234.scope", 1000)
+    );
+    // Each user has a manager of their own
+    assert_eq!(
+        unit_of("/user.slice/user-1001.slice/user@1001.service/app.slice/foo.service"),
+        user_unit("foo.service", 1001)
+    );
+    // Sub-cgroups of a service still belong to it
+    assert_eq!(
+        unit_of("/system.slice/docker.service/payload"),
+        unit("docker.service", UnitScope::System)
+    );
+    // The user manager itself is a system unit
+    assert_eq!(
+        unit_of("/user.slice/user-1000.slice/user@1000.service"),
+        unit("user@1000.service", UnitScope::System)
+    );
+    assert_eq!(unit_of("/"), None);
+    assert_eq!(unit_of("/user.slice"), None);
+
+    assert_eq!(
+        unit_cgroup("/system.slice/docker.service/payload"),
+        Some("/system.slice/docker.service")
+    );
+    assert_eq!(unit_cgroup("/user.slice"), None);
+}
+
+#[test]
+fn cgroup_files_of_both_hierarchies_are_read() {
+    assert_eq!(
+        parse_cgroup("0::/system.slice/sshd.service\n"),
+        Some("/system.slice/sshd.service")
+    );
+    let legacy =
+        "12:cpu,cpuacct:/system.slice/sshd.service\n1:name=systemd:/system.slice/sshd.service\n";
+    assert_eq!(parse_cgroup(legacy), Some("/system.slice/sshd.service"));
+    assert_eq!(parse_cgroup(""), None);
+}
+
+#[test]
+fn sessions_and_managers_are_not_manageable() {
+    for name in ["init.scope", "session-2.scope", "user@1000.service"] {
+        assert!(
+            !unit(name, UnitScope::System).unwrap().is_manageable(),
+            "{name}"
+        );
+    }
+    assert!(unit("nginx.service", UnitScope::System)
+        .unwrap()
+        .is_manageable());
+    let uid = nix::unistd::getuid().as_raw();
+    assert!(user_unit("app-firefox-1234.scope", uid)
+        .unwrap()
+        .is_manageable());
+    // Units of other users are out of reach of our session bus
+    assert!(!user_unit("foo.service", uid + 1).unwrap().is_manageable());
+}
+
+/// Stand-in for the systemd manager, recording the calls it gets
+#[derive(Default)]
+struct FakeSystemd {
+    calls: Arc<Mutex<Vec<String>>>,
+}
+
+#[derive(Debug, zbus::DBusError)]
+#[zbus(prefix = "org.freedesktop.systemd1")]
+enum SystemdError {
+    #[zbus(error)]
+    ZBus(zbus::Error),
+    NoSuchUnit(String),
+}
+
+#[zbus::interface(name = "org.freedesktop.systemd1.Manager")]
+impl FakeSystemd {
+    fn stop_unit(&self, name: &str, mode: &str) -> Result<OwnedObjectPath, SystemdError> {
+        self.record(format!("stop {name} {mode}"), name)
+    }
+
+    fn restart_unit(&self, name: &str, mode: &str) -> Result<OwnedObjectPath, SystemdError> {
+        self.record(format!("restart {name} {mode}"), name)
+    }
+
+    fn kill_unit(&self, name: &str, whom: &str, signal: i32) -> Result<(), SystemdError> {
+        self.record(format!("kill {name} {whom} {signal}"), name)
+            .map(drop)
+    }
+
+    fn set_unit_properties(
+        &self,
+        name: &str,
+        runtime: bool,
+        properties: Vec<(String, OwnedValue)>,
+    ) -> Result<(), SystemdError> {
+        let properties: HashMap<String, u64> = properties
+            .into_iter()
+            .map(|(property, value)| (property, u64::try_from(value).unwrap()))
+            .collect();
+        self.record(
+            format!(
+                "set {name} {runtime} MemoryMax={} CPUQuotaPerSecUSec={}",
+                properties["MemoryMax"], properties["CPUQuotaPerSecUSec"]
+            ),
+            name,
+        )
+        .map(drop)
+    }
+
+    fn start_transient_unit(
+        &self,
+        name: &str,
+        mode: &str,
+        properties: Vec<(String, OwnedValue)>,
+        _aux: Vec<(String, Vec<(String, OwnedValue)>)>,
+    ) -> Result<OwnedObjectPath, SystemdError> {
+        let mut properties: HashMap<String, OwnedValue> = properties.into_iter().collect();
+        let pids = Vec::<u32>::try_from(properties.remove("PIDs").unwrap()).unwrap();
+        let slice = properties
+            .remove("Slice")
+            .map(|slice| String::try_from(slice).unwrap());
+        let memory_max = u64::try_from(properties.remove("MemoryMax").unwrap()).unwrap();
+        self.record(
+            format!("start {name} {mode} PIDs={pids:?} Slice={slice:?} MemoryMax={memory_max}"),
+            name,
+        )
+    }
+}
+
+impl FakeSystemd {
+    fn record(&self, call: String, name: &str) -> Result<OwnedObjectPath, SystemdError> {
+        if name == "missing.service" {
+            return Err(SystemdError::NoSuchUnit(format!("Unit {name} not loaded.")));
+        }
+        self.calls.lock().unwrap().push(call);
+        Ok(OwnedObjectPath::try_from("/org/freedesktop/systemd1/job/1").unwrap())
+    }
+}
+
+async fn private_bus(systemd: FakeSystemd) -> (zbus::Connection, zbus::Connection) {
+    let (server_stream, client_stream) = UnixStream::pair().unwrap();
+    let server = zbus::connection::Builder::unix_stream(server_stream)
+        .server(zbus::Guid::generate())
+        .unwrap()
+        .p2p()
+        .serve_at("/org/freedesktop/systemd1", systemd)
+        .unwrap()
+        .build();
+    let client = zbus::connection::Builder::unix_stream(client_stream)
+        .p2p()
+        .build();
+
+    futures_util::try_join!(server, client).unwrap()
+}
+
+#[tokio::test]
+async fn unit_actions_call_the_manager() {
+    let systemd = FakeSystemd::default();
+    let calls = systemd.calls.clone();
+    let (_server, client) = private_bus(systemd).await;
+
+    run_action(&client, "nginx.service", UnitAction::Stop)
+        .await
+        .unwrap();
+    run_action(&client, "nginx.service", UnitAction::Restart)
+        .await
+        .unwrap();
+    run_action(&client, "nginx.service", UnitAction::Kill)
+        .await
+        .unwrap();
+    assert_eq!(
+        *calls.lock().unwrap(),
+        [
+            "stop nginx.service replace",
+            "restart nginx.service replace",
+            "kill nginx.service all 9",
+        ]
+    );
+
+    assert_eq!(
+        run_action(&client, "missing.service", UnitAction::Stop).await,
+        Err(ProcessError::NotFound)
+    );
+}
+
+#[tokio::test]
+async fn limits_are_set_as_runtime_properties() {
+    let systemd = FakeSystemd::default();
+    let calls = systemd.calls.clone();
+    let (_server, client) = private_bus(systemd).await;
+
+    let limits = Limits {
+        memory_max: Some(1024 * 1024 * 1024),
+        cpu_max: Some(50),
+    };
+    set_unit_limits(&client, "nginx.service", limits)
+        .await
+        .unwrap();
+    // No limit is systemd's infinity
+    set_unit_limits(&client, "nginx.service", Limits::default())
+        .await
+        .unwrap();
+    assert_eq!(
+        *calls.lock().unwrap(),
+        [
+            "set nginx.service true MemoryMax=1073741824 CPUQuotaPerSecUSec=500000",
+            "set nginx.service true MemoryMax=18446744073709551615 \
+             CPUQuotaPerSecUSec=18446744073709551615",
+        ]
+    );
+
+    assert_eq!(
+        set_unit_limits(&client, "missing.service", limits).await,
+        Err(ProcessError::NotFound)
+    );
+}
+
+#[tokio::test]
+async fn other_processes_are_limited_in_a_scope_of_their_own() {
+    let systemd = FakeSystemd::default();
+    let calls = systemd.calls.clone();
+    let (_server, client) = private_bus(systemd).await;
+
+    let limits = Limits {
+        memory_max: Some(1024 * 1024 * 1024),
+        cpu_max: None,
+    };
+    assert_eq!(
+        start_limited_scope(&client, 4242, Some(1000), limits).await,
+        Ok("cosmic-process-killer-4242.scope".to_string())
+    );
+    // Processes of root stay among the system's
+    start_limited_scope(&client, 4243, Some(0), limits)
+        .await
+        .unwrap();
+    assert_eq!(
+        *calls.lock().unwrap(),
+        [
+            "start cosmic-process-killer-4242.scope fail PIDs=[4242] \
+             Slice=Some(\"user-1000.slice\") MemoryMax=1073741824",
+            "start cosmic-process-killer-4243.scope fail PIDs=[4243] Slice=None \
+             MemoryMax=1073741824",
+        ]
+    );
+}
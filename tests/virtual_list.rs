@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: MIT
+
+//! Row windowing, scroll anchoring and frozen ordering of the process list.
+
+use cosmic_applet_process_killer::virtual_list::VirtualList;
+
+const ROW: f32 = 10.0;
+
+/// A 50 px viewport over `count` rows numbered from 1
+fn list(count: u32) -> VirtualList {
+    let mut list = VirtualList::new(ROW, 50.0);
+    list.set_rows(1..=count);
+    list
+}
+
+#[test]
+fn only_rows_near_the_viewport_are_built() {
+    let mut list = list(2000);
+    assert_eq!(list.visible_range(), 0..10);
+
+    list.scrolled(1000.0, 50.0);
+    assert_eq!(list.visible_range(), 96..110);
+    assert_eq!(list.height_of(96), 960.0);
+
+    list.scrolled(19_950.0, 50.0);
+    assert_eq!(list.visible_range(), 1991..2000);
+}
+
+#[test]
+fn short_lists_are_built_entirely() {
+    assert_eq!(list(3).visible_range(), 0..3);
+    assert_eq!(list(0).visible_range(), 0..0);
+}
+
+#[test]
+fn scroll_position_follows_the_top_process() {
+    let mut list = list(100);
+    list.scrolled(203.0, 50.0);
+
+    // Process 21 was at the top; after a re-sort it is the 41st row
+    let resorted: Vec<u32> = (1..=20).chain(101..=120).chain(21..=100).collect();
+    assert_eq!(list.set_rows(resorted), Some(403.0));
+
+    // Same order again: nothing to do
+    let unchanged = list.rows().to_vec();
+    assert_eq!(list.set_rows(unchanged), None);
+}
+
+#[test]
+fn the_top_of_the_list_is_not_anchored() {
+    let mut list = list(10);
+
+    // A new process sorting first comes into view
+    assert_eq!(list.set_rows([99].into_iter().chain(1..=10)), None);
+    assert_eq!(list.rows()[0], 99);
+}
+
+#[test]
+fn frozen_rows_keep_their_order() {
+    let mut list = list(5);
+    list.set_hovered(true);
+
+    list.set_rows([6, 5, 4, 2, 1]);
+    assert_eq!(list.rows(), [1, 2, 4, 5, 6]);
+
+    list.set_hovered(false);
+    list.set_rows([6, 5, 4, 2, 1]);
+    assert_eq!(list.rows(), [6, 5, 4, 2, 1]);
+}
+
+#[test]
+fn pausing_freezes_until_both_reasons_are_gone() {
+    let mut list = list(3);
+    list.set_hovered(true);
+    list.set_paused(true);
+    list.set_hovered(false);
+
+    list.set_rows([3, 2, 1]);
+    assert_eq!(list.rows(), [1, 2, 3]);
+
+    list.set_paused(false);
+    list.set_rows([3, 2, 1]);
+    assert_eq!(list.rows(), [3, 2, 1]);
+}
+
+#[test]
+fn exited_processes_do_not_leave_the_view_past_the_end() {
+    let mut list = list(100);
+    list.scrolled(950.0, 50.0);
+
+    assert_eq!(list.set_rows(1..=20), Some(150.0));
+    assert_eq!(list.visible_range(), 11..20);
+}
+
+#[test]
+fn selected_rows_are_scrolled_into_view() {
+    let mut list = list(100);
+
+    assert_eq!(list.reveal(3), None);
+    assert_eq!(list.reveal(10), Some(50.0));
+    assert_eq!(list.reveal(8), None);
+    assert_eq!(list.reveal(2), Some(10.0));
+    assert_eq!(list.reveal(1000), None);
+}